@@ -0,0 +1,831 @@
+//! Collision world with a uniform grid broadphase
+//!
+//! `CollisionWorld` is a resource holding one proxy per active, enabled
+//! collider, bucketed into grid cells over the ground plane. Overlap queries
+//! only test the proxies in the cells they touch, whatever render components
+//! the entities carry.
+//!
+//! Proxies follow their colliders through component hooks when a `Collider`
+//! is added, removed or switched, and through `update_collision_world` when
+//! colliders or their global transforms change.
+
+use std::collections::{HashMap, HashSet};
+use macroquad::prelude::*;
+use crate::ecs::{Entity, Resource, World, With};
+use crate::ecs::component::ComponentHooks;
+use crate::ecs::query::Changed;
+use crate::ecs::components::{Collider, ColliderShape, ColliderMaterial, CharacterController, Transform, GlobalTransform, Parent};
+
+/// A collider as seen by the broadphase
+#[derive(Debug, Clone)]
+pub struct ColliderProxy {
+    pub shape: ColliderShape,
+    pub transform: Transform,  // World-space transform when the proxy was updated
+    pub is_static: bool,
+    pub is_trigger: bool,
+    pub layer: u32,            // Collision layers, as on the `Collider`
+    pub mask: u32,
+    pub material: ColliderMaterial,
+    pub min: Vec3,             // Bounding box used for the grid and early rejection
+    pub max: Vec3,
+}
+
+impl ColliderProxy {
+    fn new(collider: &Collider, transform: Transform) -> Self {
+        let (min, max) = collider.shape.get_bounds(&transform);
+        Self {
+            shape: collider.shape.clone(),
+            transform,
+            is_static: collider.is_static,
+            is_trigger: collider.is_trigger,
+            layer: collider.layer,
+            mask: collider.mask,
+            material: collider.material.clone(),
+            min,
+            max,
+        }
+    }
+
+    /// Check if this collider stops movement, as opposed to a trigger
+    pub fn is_solid(&self) -> bool {
+        !self.is_trigger
+    }
+
+    /// Check if this collider is on any of the layers in `mask`
+    pub fn in_layers(&self, mask: u32) -> bool {
+        self.layer & mask != 0
+    }
+
+    /// Check if this collider and `collider` are on layers in each other's masks
+    pub fn interacts_with(&self, collider: &Collider) -> bool {
+        self.in_layers(collider.mask) && collider.layer & self.mask != 0
+    }
+
+    fn bounds_overlap(&self, min: Vec3, max: Vec3) -> bool {
+        self.min.x <= max.x && self.max.x >= min.x &&
+        self.min.y <= max.y && self.max.y >= min.y &&
+        self.min.z <= max.z && self.max.z >= min.z
+    }
+}
+
+/// First collider hit by `CollisionWorld::sweep_capsule`
+#[derive(Debug, Clone, Copy)]
+pub struct SweepHit {
+    pub entity: Entity,
+    pub time: f32,     // Fraction of the motion that can be travelled without touching
+    pub normal: Vec3,  // Unit normal of the hit surface, pointing back at the capsule
+}
+
+/// First collider hit by `CollisionWorld::raycast` or `CollisionWorld::sphere_cast`
+// Casts are query API for gameplay code that doesn't exist yet
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct RaycastHit {
+    pub entity: Entity,
+    pub point: Vec3,     // Where the ray, or the cast sphere, touches the collider
+    pub normal: Vec3,    // Unit normal of the hit surface, pointing back along the cast
+    pub distance: f32,   // Distance travelled along the cast direction
+}
+
+/// Which colliders a raycast or sphere cast can hit
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct CastFilter {
+    pub mask: u32,               // Layers that can be hit
+    pub include_triggers: bool,  // Hit trigger colliders as well as solid ones
+    pub exclude: Option<Entity>, // Usually the entity casting
+}
+
+#[allow(dead_code)]
+impl CastFilter {
+    /// Hit solid colliders on every layer
+    pub fn new() -> Self {
+        Self {
+            mask: Collider::ALL_LAYERS,
+            include_triggers: false,
+            exclude: None,
+        }
+    }
+
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn with_triggers(mut self, include_triggers: bool) -> Self {
+        self.include_triggers = include_triggers;
+        self
+    }
+
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.exclude = Some(entity);
+        self
+    }
+
+    /// Check if a collider passes the filter
+    pub fn accepts(&self, entity: Entity, proxy: &ColliderProxy) -> bool {
+        self.exclude != Some(entity) &&
+        proxy.in_layers(self.mask) &&
+        (self.include_triggers || proxy.is_solid())
+    }
+}
+
+impl Default for CastFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Spatial index of every active, enabled collider in the world
+#[derive(Debug, Clone)]
+pub struct CollisionWorld {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<Entity>>,
+    proxies: HashMap<Entity, ColliderProxy>,
+}
+
+impl CollisionWorld {
+    /// Create an empty collision world with square grid cells of `cell_size`
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            proxies: HashMap::new(),
+        }
+    }
+
+    /// Add or move the proxy of an entity's collider
+    pub fn insert(&mut self, entity: Entity, collider: &Collider, transform: Transform) {
+        self.remove(entity);
+
+        let proxy = ColliderProxy::new(collider, transform);
+        for cell in self.cells_overlapping(proxy.min, proxy.max) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+        self.proxies.insert(entity, proxy);
+    }
+
+    /// Remove an entity's proxy, returning whether it had one
+    pub fn remove(&mut self, entity: Entity) -> bool {
+        let proxy = match self.proxies.remove(&entity) {
+            Some(proxy) => proxy,
+            None => return false,
+        };
+
+        for cell in self.cells_overlapping(proxy.min, proxy.max) {
+            if let Some(entities) = self.cells.get_mut(&cell) {
+                entities.retain(|&other| other != entity);
+                if entities.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        true
+    }
+
+    /// Get the proxy of an entity's collider
+    pub fn get(&self, entity: Entity) -> Option<&ColliderProxy> {
+        self.proxies.get(&entity)
+    }
+
+    /// Check if an entity's collider is in the collision world
+    // Only the tests look proxies up or count them so far
+    #[allow(dead_code)]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.proxies.contains_key(&entity)
+    }
+
+    /// Number of colliders in the collision world
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    /// Check if the collision world has no colliders
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    /// Entities whose colliders overlap a sphere
+    // No gameplay system asks for sphere or box overlaps yet
+    #[allow(dead_code)]
+    pub fn overlap_sphere(&self, center: Vec3, radius: f32) -> Vec<Entity> {
+        self.overlap_shape(&ColliderShape::Sphere { radius }, center)
+    }
+
+    /// Entities whose colliders overlap an axis-aligned box of `size`
+    #[allow(dead_code)]
+    pub fn overlap_box(&self, center: Vec3, size: Vec3) -> Vec<Entity> {
+        self.overlap_shape(&ColliderShape::Box { size }, center)
+    }
+
+    /// Entities whose colliders overlap an upright capsule
+    pub fn overlap_capsule(&self, center: Vec3, height: f32, radius: f32) -> Vec<Entity> {
+        self.overlap_shape(&ColliderShape::Capsule { height, radius }, center)
+    }
+
+    /// Entities whose colliders overlap a shape centred on `position`, in the
+    /// order their grid cells are visited
+    pub fn overlap_shape(&self, shape: &ColliderShape, position: Vec3) -> Vec<Entity> {
+        self.overlap_transformed(shape, &Transform::new(position))
+    }
+
+    /// Entities whose colliders overlap a shape placed by `transform`, so
+    /// boxes can be rotated
+    pub fn overlap_transformed(&self, shape: &ColliderShape, transform: &Transform) -> Vec<Entity> {
+        let (min, max) = shape.get_bounds(transform);
+
+        self.candidates(min, max)
+            .into_iter()
+            .filter(|entity| {
+                let proxy = &self.proxies[entity];
+                shape.overlaps_with(transform, &proxy.shape, &proxy.transform)
+            })
+            .collect()
+    }
+
+    /// Cast a ray and report the first collider accepted by `filter` that it
+    /// enters within `max_distance`
+    ///
+    /// Boxes are hit as oriented by their transforms. Colliders containing
+    /// `origin` are ignored, so a ray can be cast from inside the caster.
+    ///
+    /// Only the grid cells the ray passes through are visited, nearest first,
+    /// and the walk stops as soon as no later cell could hold a closer hit.
+    #[allow(dead_code)]
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32, filter: &CastFilter) -> Option<RaycastHit> {
+        let direction = direction.try_normalize()?;
+
+        let mut seen = HashSet::new();
+        let mut closest: Option<RaycastHit> = None;
+        for (cell, exit) in self.cells_along_ray(origin, direction, max_distance) {
+            for &entity in self.cells.get(&cell).into_iter().flatten() {
+                let proxy = &self.proxies[&entity];
+                if !seen.insert(entity) || !filter.accepts(entity, proxy) {
+                    continue;
+                }
+                let limit = closest.map_or(max_distance, |hit| hit.distance);
+                if let Some((distance, normal)) = proxy.shape.raycast(&proxy.transform, origin, direction, limit) {
+                    let point = origin + direction * distance;
+                    closest = Some(RaycastHit { entity, point, normal, distance });
+                }
+            }
+            if closest.is_some_and(|hit| hit.distance <= exit) {
+                break;
+            }
+        }
+        closest
+    }
+
+    /// Move a sphere of `radius` along a ray and report the first collider
+    /// accepted by `filter` that it touches within `max_distance`
+    ///
+    /// Like `sweep_capsule`, colliders the sphere starts in only count if the
+    /// cast heads further into them.
+    #[allow(dead_code)]
+    pub fn sphere_cast(&self, origin: Vec3, radius: f32, direction: Vec3, max_distance: f32, filter: &CastFilter) -> Option<RaycastHit> {
+        let direction = direction.try_normalize()?;
+        let motion = direction * max_distance;
+        let hit = self.sweep(origin, motion, 0.0, radius, |entity, proxy| filter.accepts(entity, proxy))?;
+
+        let center = origin + motion * hit.time;
+        let proxy = &self.proxies[&hit.entity];
+        Some(RaycastHit {
+            entity: hit.entity,
+            point: proxy.shape.closest_point(&proxy.transform, center),
+            normal: hit.normal,
+            distance: max_distance * hit.time,
+        })
+    }
+
+    /// Move an upright capsule along `motion` and report the first collider
+    /// accepted by `filter` that it runs into
+    ///
+    /// The capsule advances in steps no longer than its radius, so it cannot
+    /// pass through thin colliders, and the contact is then narrowed down
+    /// between the last free and the first blocked step. Colliders the capsule
+    /// already overlaps only block motion heading further into them.
+    pub fn sweep_capsule(&self, start: Vec3, motion: Vec3, height: f32, radius: f32, filter: impl Fn(&ColliderProxy) -> bool) -> Option<SweepHit> {
+        self.sweep(start, motion, height, radius, |_, proxy| filter(proxy))
+    }
+
+    fn sweep(&self, start: Vec3, motion: Vec3, height: f32, radius: f32, filter: impl Fn(Entity, &ColliderProxy) -> bool) -> Option<SweepHit> {
+        let distance = motion.length();
+        if distance <= f32::EPSILON {
+            return None;
+        }
+
+        let shape = ColliderShape::Capsule { height, radius };
+        let overlaps_at = |entity: Entity, position: Vec3| {
+            let proxy = &self.proxies[&entity];
+            shape.overlaps_with(&Transform::new(position), &proxy.shape, &proxy.transform)
+        };
+
+        let (start_min, start_max) = shape.get_bounds(&Transform::new(start));
+        let (end_min, end_max) = shape.get_bounds(&Transform::new(start + motion));
+        let mut blockers = Vec::new();
+        for entity in self.candidates(start_min.min(end_min), start_max.max(end_max)) {
+            if !filter(entity, &self.proxies[&entity]) {
+                continue;
+            }
+            if !overlaps_at(entity, start) {
+                blockers.push(entity);
+                continue;
+            }
+            let normal = self.contact_normal(entity, start, height, -motion / distance);
+            if normal.dot(motion) < 0.0 {
+                return Some(SweepHit { entity, time: 0.0, normal });
+            }
+        }
+        if blockers.is_empty() {
+            return None;
+        }
+
+        let first_blocker = |time: f32| blockers.iter().copied().find(|&entity| overlaps_at(entity, start + motion * time));
+        let steps = (distance / radius.max(0.01)).ceil() as u32;
+        let mut free = 0.0;
+        for step in 1..=steps {
+            let time = step as f32 / steps as f32;
+            let mut entity = match first_blocker(time) {
+                Some(entity) => entity,
+                None => {
+                    free = time;
+                    continue;
+                }
+            };
+
+            let mut blocked = time;
+            for _ in 0..16 {
+                let middle = (free + blocked) * 0.5;
+                match first_blocker(middle) {
+                    Some(other) => {
+                        blocked = middle;
+                        entity = other;
+                    }
+                    None => free = middle,
+                }
+            }
+
+            let normal = self.contact_normal(entity, start + motion * blocked, height, -motion / distance);
+            return Some(SweepHit { entity, time: free, normal });
+        }
+        None
+    }
+
+    /// Direction from an entity's collider towards an upright capsule core
+    /// touching it, or `fallback` if the core is inside the collider
+    fn contact_normal(&self, entity: Entity, position: Vec3, height: f32, fallback: Vec3) -> Vec3 {
+        let proxy = &self.proxies[&entity];
+        let half_height = Vec3::new(0.0, height * 0.5, 0.0);
+        let (on_core, on_collider) = proxy.shape.closest_to_segment(&proxy.transform, position - half_height, position + half_height);
+        (on_core - on_collider).try_normalize().unwrap_or(fallback)
+    }
+
+    /// Entities whose proxy bounds touch a bounding box, each listed once
+    fn candidates(&self, min: Vec3, max: Vec3) -> Vec<Entity> {
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        for cell in self.cells_overlapping(min, max) {
+            for &entity in self.cells.get(&cell).into_iter().flatten() {
+                if seen.insert(entity) && self.proxies[&entity].bounds_overlap(min, max) {
+                    candidates.push(entity);
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Grid cells a ray crosses within `max_distance` on the x/z plane, in
+    /// order, each with the distance at which the ray leaves it
+    ///
+    /// Walks the grid one cell boundary at a time (Amanatides and Woo), so
+    /// nothing beyond the ray's path is visited.
+    fn cells_along_ray(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> impl Iterator<Item = ((i32, i32), f32)> {
+        let cell_size = self.cell_size;
+        // Distance to the first boundary crossed on one axis, and between boundaries
+        let axis = |coord: i32, origin: f32, direction: f32| {
+            if direction.abs() <= f32::EPSILON {
+                return (0, f32::INFINITY, f32::INFINITY);
+            }
+            let step = direction.signum() as i32;
+            let boundary = (coord + i32::from(step > 0)) as f32 * cell_size;
+            (step, (boundary - origin) / direction, cell_size / direction.abs())
+        };
+
+        let mut cell = (self.cell_coord(origin.x), self.cell_coord(origin.z));
+        let (step_x, mut next_x, delta_x) = axis(cell.0, origin.x, direction.x);
+        let (step_z, mut next_z, delta_z) = axis(cell.1, origin.z, direction.z);
+        let mut entered = 0.0;
+        std::iter::from_fn(move || {
+            if entered > max_distance {
+                return None;
+            }
+            let current = cell;
+            let exit = next_x.min(next_z);
+            if next_x < next_z {
+                cell.0 += step_x;
+                next_x += delta_x;
+            } else {
+                cell.1 += step_z;
+                next_z += delta_z;
+            }
+            entered = exit;
+            Some((current, exit))
+        })
+    }
+
+    fn cell_coord(&self, value: f32) -> i32 {
+        (value / self.cell_size).floor() as i32
+    }
+
+    /// Grid cells covered by a bounding box on the x/z plane
+    fn cells_overlapping(&self, min: Vec3, max: Vec3) -> impl Iterator<Item = (i32, i32)> {
+        let (min_x, max_x) = (self.cell_coord(min.x), self.cell_coord(max.x));
+        let (min_z, max_z) = (self.cell_coord(min.z), self.cell_coord(max.z));
+        (min_x..=max_x).flat_map(move |x| (min_z..=max_z).map(move |z| (x, z)))
+    }
+}
+
+impl Default for CollisionWorld {
+    fn default() -> Self {
+        Self::new(2.0)
+    }
+}
+
+impl Resource for CollisionWorld {}
+
+/// Bring an entity's proxy in line with its collider, active state and
+/// world-space transform
+///
+/// Entities that have not been through `World::propagate_transforms` yet use
+/// their local `Transform` if they have no parent. Characters' proxies sit at
+/// the centre of their `CharacterController` capsule.
+pub fn refresh_collider(world: &mut World, entity: Entity) {
+    let mut transform = match world.get::<GlobalTransform>(entity) {
+        Some(global) => Some(global.transform().clone()),
+        None if !world.has::<Parent>(entity) => world.get::<Transform>(entity).cloned(),
+        None => None,
+    };
+    // A character's capsule hangs below its transform rather than around it
+    if let (Some(transform), Some(controller)) = (transform.as_mut(), world.get::<CharacterController>(entity)) {
+        transform.position = controller.capsule(transform.position).0;
+    }
+    let collider = world.get::<Collider>(entity)
+        .filter(|collider| collider.is_enabled() && world.is_active(entity))
+        .cloned();
+
+    let collision = match world.resource_mut::<CollisionWorld>() {
+        Some(collision) => collision,
+        None => return,
+    };
+    match (collider, transform) {
+        (Some(collider), Some(transform)) if transform.is_enabled() => collision.insert(entity, &collider, transform),
+        _ => {
+            collision.remove(entity);
+        }
+    }
+}
+
+/// Update the proxies of colliders that changed, moved or went away since the
+/// last `World::clear_trackers`
+///
+/// Runs after `World::propagate_transforms` so moved colliders use their new
+/// global transforms.
+pub fn update_collision_world(world: &mut World) {
+    if !world.has_resource::<CollisionWorld>() {
+        return;
+    }
+
+    let mut dirty: Vec<Entity> = world.removed_components::<Collider>().iter().collect();
+    dirty.extend(world.removed_components::<GlobalTransform>().iter());
+    dirty.extend(world.query_filtered::<Entity, Changed<Collider>>().iter());
+    dirty.extend(world.query_filtered::<Entity, (With<Collider>, Changed<GlobalTransform>)>().iter());
+
+    for entity in dirty {
+        refresh_collider(world, entity);
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(refresh_collider),
+        on_remove: Some(|world, entity| {
+            if let Some(collision) = world.resource_mut::<CollisionWorld>() {
+                collision.remove(entity);
+            }
+        }),
+        on_enable: Some(refresh_collider),
+        on_disable: Some(refresh_collider),
+        ..ComponentHooks::new::<Collider>("Collider")
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        // The proxy moves to the centre of the character's capsule
+        on_add: Some(refresh_collider),
+        ..ComponentHooks::new::<CharacterController>("CharacterController")
+    }
+}
+
+/// Worlds and shapes shared by the collision, character and physics tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use std::sync::Arc;
+    use super::*;
+    use crate::ecs::{ConvexHull, TriMesh};
+
+    pub fn collision_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(CollisionWorld::new(1.0));
+        world
+    }
+
+    pub fn spawn_box(world: &mut World, position: Vec3, size: Vec3) -> Entity {
+        world.spawn()
+            .with(Transform::new(position))
+            .with(Collider::static_solid(ColliderShape::Box { size }))
+            .build()
+    }
+
+    /// A world with a floor whose top is at y = 0
+    pub fn floored_world() -> World {
+        let mut world = collision_world();
+        spawn_box(&mut world, Vec3::new(0.0, -0.05, 0.0), Vec3::new(40.0, 0.1, 40.0));
+        world
+    }
+
+    /// A world with a one-sided mesh floor at y = 0
+    pub fn mesh_floored_world() -> (World, Entity) {
+        let mut world = collision_world();
+        let vertices = vec![
+            Vec3::new(-20.0, 0.0, -20.0), Vec3::new(20.0, 0.0, -20.0),
+            Vec3::new(20.0, 0.0, 20.0), Vec3::new(-20.0, 0.0, 20.0),
+        ];
+        let floor = world.spawn()
+            .with(Transform::new(Vec3::ZERO))
+            .with(Collider::static_solid(ColliderShape::TriMesh {
+                mesh: Arc::new(TriMesh::new(vertices, vec![[0, 3, 2], [0, 2, 1]])),
+            }))
+            .build();
+        (world, floor)
+    }
+
+    /// A cube with sides of `size` centred on the origin
+    pub fn cube_hull(size: f32) -> ConvexHull {
+        let corners: Vec<Vec3> = (0..8)
+            .map(|i| Vec3::new((i & 1) as f32 - 0.5, ((i >> 1) & 1) as f32 - 0.5, ((i >> 2) & 1) as f32 - 0.5) * size)
+            .collect();
+        ConvexHull::new(&corners).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use super::fixtures::{collision_world, cube_hull, mesh_floored_world, spawn_box};
+    use crate::ecs::{CharacterController, Component, TriMesh};
+
+    #[test]
+    fn test_overlap_queries() {
+        let mut world = collision_world();
+        let wall = spawn_box(&mut world, Vec3::new(0.0, 0.5, 0.0), Vec3::ONE);
+        let pillar = spawn_box(&mut world, Vec3::new(5.0, 0.5, 5.0), Vec3::new(0.5, 3.0, 0.5));
+        let wide = spawn_box(&mut world, Vec3::new(10.0, 0.0, 0.0), Vec3::new(8.0, 0.2, 8.0));
+
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        assert_eq!(collision.len(), 3);
+        assert_eq!(collision.overlap_sphere(Vec3::new(0.0, 0.5, 0.9), 0.5), vec![wall]);
+        assert!(collision.overlap_sphere(Vec3::new(0.9, 0.5, 0.9), 0.5).is_empty());
+        assert_eq!(collision.overlap_box(Vec3::new(5.0, 2.0, 5.5), Vec3::ONE), vec![pillar]);
+        assert_eq!(collision.overlap_capsule(Vec3::new(7.0, 0.6, 3.5), 1.8, 0.25), vec![wide]);
+
+        let everything = collision.overlap_box(Vec3::new(5.0, 0.5, 0.0), Vec3::new(20.0, 2.0, 20.0));
+        assert_eq!(everything.len(), 3);
+    }
+
+    #[test]
+    fn test_proxies_follow_colliders() {
+        let mut world = collision_world();
+        let wall = spawn_box(&mut world, Vec3::new(0.0, 0.5, 0.0), Vec3::ONE);
+        let probe = |world: &World, x: f32| world.resource::<CollisionWorld>().unwrap().overlap_sphere(Vec3::new(x, 0.5, 0.0), 0.2);
+        assert_eq!(probe(&world, 0.0), vec![wall]);
+
+        // Moving the wall is picked up once transforms are propagated
+        world.clear_trackers();
+        world.get_mut::<Transform>(wall).unwrap().position.x = 3.0;
+        world.propagate_transforms();
+        update_collision_world(&mut world);
+        assert!(probe(&world, 0.0).is_empty());
+        assert_eq!(probe(&world, 3.0), vec![wall]);
+
+        // Deactivating, disabling and removing take the wall out straight away
+        world.set_active(wall, false);
+        assert!(probe(&world, 3.0).is_empty());
+        world.set_active(wall, true);
+        assert_eq!(probe(&world, 3.0), vec![wall]);
+
+        world.set_enabled(wall, false);
+        assert!(probe(&world, 3.0).is_empty());
+        world.set_enabled(wall, true);
+
+        world.get_mut::<Collider>(wall).unwrap().disable();
+        update_collision_world(&mut world);
+        assert!(probe(&world, 3.0).is_empty());
+        world.get_mut::<Collider>(wall).unwrap().enable();
+        update_collision_world(&mut world);
+        assert_eq!(probe(&world, 3.0), vec![wall]);
+
+        world.remove::<Collider>(wall);
+        assert!(world.resource::<CollisionWorld>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_character_proxies_cover_their_capsule() {
+        let mut world = collision_world();
+        let controller = CharacterController::new(1.8, 0.25);
+        let character = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, controller.foot_offset, 0.0)))
+            .with(Collider::dynamic_solid(controller.collider_shape()))
+            .with(controller)
+            .build();
+
+        // Feet to head, with the feet on the ground
+        let proxy = world.resource::<CollisionWorld>().unwrap().get(character).unwrap();
+        assert!(proxy.min.y.abs() < 1e-4);
+        assert!((proxy.max.y - 1.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rotated_box_colliders() {
+        let mut world = collision_world();
+        // A thin wall turned 45 degrees, running from (-x, +z) to (+x, -z)
+        let wall = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, 0.0)).with_rotation(Vec3::new(0.0, std::f32::consts::FRAC_PI_4, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(4.0, 2.0, 0.2) }))
+            .build();
+
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        assert_eq!(collision.overlap_sphere(Vec3::new(1.0, 1.0, -1.0), 0.2), vec![wall]);
+        assert!(collision.overlap_sphere(Vec3::new(1.0, 1.0, 1.0), 0.2).is_empty());
+        assert_eq!(collision.overlap_box(Vec3::new(1.3, 1.0, -1.5), Vec3::splat(0.2)), vec![wall]);
+        assert!(collision.overlap_box(Vec3::new(1.5, 1.0, 1.5), Vec3::splat(0.5)).is_empty());
+
+        assert!(Collider::check_position_collision(&world, Vec3::new(-1.0, 0.6, 1.2), 0.25));
+        assert!(!Collider::check_position_collision(&world, Vec3::new(1.0, 0.6, 1.0), 0.25));
+    }
+
+    #[test]
+    fn test_position_collision_ignores_render_components() {
+        let mut world = collision_world();
+        spawn_box(&mut world, Vec3::new(2.0, 0.5, 0.0), Vec3::ONE);
+        world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 0.5, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Box { size: Vec3::ONE }))
+            .build();
+
+        assert!(Collider::check_position_collision(&world, Vec3::new(1.6, 0.6, 0.0), 0.25));
+        assert!(!Collider::check_position_collision(&world, Vec3::new(0.0, 0.6, 0.0), 0.25));
+    }
+
+    #[test]
+    fn test_raycasts_hit_every_shape_and_respect_filters() {
+        let mut world = collision_world();
+        let wall = spawn_box(&mut world, Vec3::new(5.0, 1.0, 0.0), Vec3::new(1.0, 2.0, 4.0));
+        let ball = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, 5.0)))
+            .with(Collider::static_solid(ColliderShape::Sphere { radius: 0.5 }))
+            .build();
+        let pole = world.spawn()
+            .with(Transform::new(Vec3::new(-5.0, 1.0, 0.0)))
+            .with(Collider::dynamic_solid(ColliderShape::Capsule { height: 1.0, radius: 0.5 })
+                .with_layers(Collider::CHARACTER_LAYER, Collider::ALL_LAYERS))
+            .build();
+        let diamond = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, -5.0)).with_rotation(Vec3::new(0.0, std::f32::consts::FRAC_PI_4, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::splat(1.0) }))
+            .build();
+        let sensor = world.spawn()
+            .with(Transform::new(Vec3::new(2.5, 1.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Box { size: Vec3::splat(1.0) }))
+            .build();
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        let origin = Vec3::new(0.0, 1.0, 0.0);
+        let filter = CastFilter::new();
+
+        let hit = collision.raycast(origin, Vec3::X, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, wall);
+        assert!((hit.distance - 4.5).abs() < 1e-4 && hit.normal.abs_diff_eq(-Vec3::X, 1e-4));
+        assert!(hit.point.abs_diff_eq(Vec3::new(4.5, 1.0, 0.0), 1e-4));
+        assert!(collision.raycast(origin, Vec3::X, 4.0, &filter).is_none());
+        assert_eq!(collision.raycast(origin, Vec3::X, 20.0, &filter.with_triggers(true)).unwrap().entity, sensor);
+
+        let hit = collision.raycast(origin, Vec3::Z, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, ball);
+        assert!((hit.distance - 4.5).abs() < 1e-4 && hit.normal.abs_diff_eq(-Vec3::Z, 1e-4));
+
+        let hit = collision.raycast(Vec3::new(0.0, 1.4, 0.0), -Vec3::X, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, pole);
+        assert!((hit.distance - 4.5).abs() < 0.01 && hit.normal.abs_diff_eq(Vec3::X, 1e-4));
+        let over = collision.raycast(Vec3::new(-5.0, 5.0, 0.0), -Vec3::Y, 20.0, &filter).unwrap();
+        assert!((over.point.y - 2.0).abs() < 1e-4 && over.normal.abs_diff_eq(Vec3::Y, 1e-4));
+        assert!(collision.raycast(origin, -Vec3::X, 20.0, &filter.with_mask(Collider::DEFAULT_LAYER)).is_none());
+        assert!(collision.raycast(origin, -Vec3::X, 20.0, &filter.excluding(pole)).is_none());
+
+        // The rotated box shows a corner to the ray
+        let hit = collision.raycast(origin, -Vec3::Z, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, diamond);
+        assert!((hit.distance - (5.0 - std::f32::consts::FRAC_1_SQRT_2)).abs() < 1e-3, "hit at {}", hit.distance);
+
+        // Rays starting inside a collider pass out of it
+        assert_eq!(collision.raycast(Vec3::new(-5.0, 1.0, 0.0), Vec3::X, 20.0, &filter).unwrap().entity, wall);
+    }
+
+    #[test]
+    fn test_raycasts_walk_the_grid_in_every_direction() {
+        let mut world = collision_world();
+        let near = spawn_box(&mut world, Vec3::new(-3.0, 0.5, -3.0), Vec3::ONE);
+        let far = spawn_box(&mut world, Vec3::new(-6.0, 0.5, -6.0), Vec3::ONE);
+        // Inside the diagonal ray's bounding box but off its path
+        let side = spawn_box(&mut world, Vec3::new(-1.0, 0.5, -5.0), Vec3::ONE);
+        let filter = CastFilter::new();
+        let collision = world.resource::<CollisionWorld>().unwrap();
+
+        let diagonal = Vec3::new(-1.0, 0.0, -1.0);
+        let hit = collision.raycast(Vec3::new(0.3, 0.5, 0.3), diagonal, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, near);
+        assert!(hit.point.abs_diff_eq(Vec3::new(-2.5, 0.5, -2.5), 1e-4));
+        let hit = collision.raycast(Vec3::new(-4.5, 0.5, -4.5), diagonal, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, far);
+        let hit = collision.raycast(Vec3::new(0.3, 0.5, -0.5), Vec3::new(-0.2, 0.0, -1.0), 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, side);
+
+        // Rays along a grid line and straight down stay in their cells
+        let hit = collision.raycast(Vec3::new(-3.0, 0.5, 4.0), -Vec3::Z, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, near);
+        assert!((hit.distance - 6.5).abs() < 1e-4);
+        let hit = collision.raycast(Vec3::new(-6.0, 10.0, -6.0), -Vec3::Y, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, far);
+        assert!((hit.distance - 9.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sphere_cast_stops_at_surfaces() {
+        let mut world = collision_world();
+        let wall = spawn_box(&mut world, Vec3::new(5.0, 1.0, 0.0), Vec3::new(1.0, 2.0, 4.0));
+        let collision = world.resource::<CollisionWorld>().unwrap();
+
+        let hit = collision.sphere_cast(Vec3::new(0.0, 1.0, 0.0), 0.5, Vec3::X, 20.0, &CastFilter::new()).unwrap();
+        assert_eq!(hit.entity, wall);
+        assert!((hit.distance - 4.0).abs() < 0.01, "hit at {}", hit.distance);
+        assert!(hit.point.abs_diff_eq(Vec3::new(4.5, 1.0, 0.0), 0.01));
+        assert!(hit.normal.abs_diff_eq(-Vec3::X, 0.01));
+
+        // Passing just beside the wall misses it, grazing it does not
+        assert!(collision.sphere_cast(Vec3::new(0.0, 1.0, 2.6), 0.5, Vec3::X, 20.0, &CastFilter::new()).is_none());
+        assert!(collision.sphere_cast(Vec3::new(0.0, 1.0, 2.4), 0.5, Vec3::X, 20.0, &CastFilter::new()).is_some());
+    }
+
+    #[test]
+    fn test_mesh_and_convex_colliders() {
+        let (mut world, floor) = mesh_floored_world();
+        let hull = cube_hull(1.0);
+        let crate_mesh = TriMesh::new(hull.vertices().to_vec(), hull.faces().to_vec());
+        let solid_crate = world.spawn()
+            .with(Transform::new(Vec3::new(5.0, 1.0, 0.0)))
+            .with(Collider::static_solid(ColliderShape::TriMesh { mesh: Arc::new(crate_mesh) }))
+            .build();
+        let diamond = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, -5.0)).with_rotation(Vec3::new(0.0, std::f32::consts::FRAC_PI_4, 0.0)))
+            .with(Collider::static_solid(ColliderShape::ConvexHull { hull: Arc::new(hull) }))
+            .build();
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        let filter = CastFilter::new();
+
+        // Mesh triangles only block rays from their front
+        let hit = collision.raycast(Vec3::new(3.0, 5.0, 2.0), -Vec3::Y, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, floor);
+        assert!((hit.distance - 5.0).abs() < 1e-4 && hit.normal.abs_diff_eq(Vec3::Y, 1e-4));
+        assert!(collision.raycast(Vec3::new(3.0, -5.0, 2.0), Vec3::Y, 20.0, &filter).is_none());
+
+        let hit = collision.raycast(Vec3::new(0.0, 1.0, 0.0), Vec3::X, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, solid_crate);
+        assert!((hit.distance - 4.5).abs() < 1e-4 && hit.normal.abs_diff_eq(-Vec3::X, 1e-4));
+
+        // The rotated hull shows a corner to the ray
+        let hit = collision.raycast(Vec3::new(0.0, 1.0, 0.0), -Vec3::Z, 20.0, &filter).unwrap();
+        assert_eq!(hit.entity, diamond);
+        assert!((hit.distance - (5.0 - std::f32::consts::FRAC_1_SQRT_2)).abs() < 1e-3, "hit at {}", hit.distance);
+
+        // Overlaps count shapes touching the surface and shapes inside closed meshes
+        assert_eq!(collision.overlap_sphere(Vec3::new(5.0, 1.0, 0.9), 0.5), vec![solid_crate]);
+        assert_eq!(collision.overlap_sphere(Vec3::new(5.0, 1.0, 0.0), 0.1), vec![solid_crate]);
+        assert!(collision.overlap_sphere(Vec3::new(5.0, 1.0, 1.2), 0.1).is_empty());
+        assert_eq!(collision.overlap_capsule(Vec3::new(8.0, 0.6, 0.0), 0.8, 0.25), vec![floor]);
+        assert_eq!(collision.overlap_box(Vec3::new(0.0, 1.0, -4.2), Vec3::splat(0.5)), vec![diamond]);
+        assert!(collision.overlap_box(Vec3::new(0.0, 1.0, -3.8), Vec3::splat(0.5)).is_empty());
+        assert_eq!(collision.overlap_box(Vec3::new(5.0, 1.0, 0.7), Vec3::splat(0.5)), vec![solid_crate]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Deferred world edits
+//!
+//! Systems can't spawn or despawn entities while they iterate a query, because
+//! the query borrows the storages being changed. Instead they record the edit
+//! in a `Commands` buffer taken from the world, and the schedule applies every
+//! recorded command at the end of the stage, in the order they were recorded.
+//!
+//! ```ignore
+//! let mut commands = world.commands();
+//! for (entity, light) in world.query::<(Entity, &LightSource)>() {
+//!     if light.intensity <= 0.0 {
+//!         commands.despawn(entity);
+//!     }
+//! }
+//! let marker = commands.spawn((Transform::new(Vec3::ZERO), Wall::new()));
+//! ```
+
+use std::sync::{Arc, Mutex};
+use crate::ecs::{Component, Entity, World};
+use crate::ecs::entity::EntityReserver;
+
+/// A recorded world edit
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Commands recorded but not yet applied, shared by a world and its `Commands`
+pub(crate) type CommandQueue = Arc<Mutex<Vec<Command>>>;
+
+/// A set of components that can be added to an entity in one go
+///
+/// Implemented for every component and for tuples of up to eight bundles.
+pub trait Bundle: Send + 'static {
+    /// Add every component in the bundle to `entity`
+    fn insert_into(self, world: &mut World, entity: Entity);
+}
+
+impl<T: Component> Bundle for T {
+    fn insert_into(self, world: &mut World, entity: Entity) {
+        world.add(entity, self);
+    }
+}
+
+macro_rules! impl_bundle_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Bundle),*> Bundle for ($($name,)*) {
+            fn insert_into(self, world: &mut World, entity: Entity) {
+                let ($($name,)*) = self;
+                $($name.insert_into(world, entity);)*
+            }
+        }
+    };
+}
+
+impl_bundle_tuple!(A);
+impl_bundle_tuple!(A, B);
+impl_bundle_tuple!(A, B, C);
+impl_bundle_tuple!(A, B, C, D);
+impl_bundle_tuple!(A, B, C, D, E);
+impl_bundle_tuple!(A, B, C, D, E, F);
+impl_bundle_tuple!(A, B, C, D, E, F, G);
+impl_bundle_tuple!(A, B, C, D, E, F, G, H);
+
+/// Buffer of deferred world edits, obtained with `World::commands`
+///
+/// Entities spawned through commands get their handle immediately, so later
+/// commands can refer to them, but they only become valid once applied.
+#[derive(Clone)]
+pub struct Commands {
+    queue: CommandQueue,
+    reserver: EntityReserver,
+}
+
+impl Commands {
+    pub(crate) fn new(queue: CommandQueue, reserver: EntityReserver) -> Self {
+        Self { queue, reserver }
+    }
+
+    /// Record an arbitrary edit
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.lock().unwrap().push(Box::new(command));
+    }
+
+    /// Spawn an entity with a bundle of components
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        let entity = self.reserver.reserve();
+        self.add(move |world| {
+            if world.spawn_reserved(entity) {
+                bundle.insert_into(world, entity);
+            }
+        });
+        entity
+    }
+
+    /// Despawn an entity and its descendants
+    pub fn despawn(&mut self, entity: Entity) {
+        self.add(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    /// Add a bundle of components to an entity, replacing existing ones
+    #[allow(dead_code)] // The game only spawns, despawns and toggles entities through commands so far
+    pub fn insert(&mut self, entity: Entity, bundle: impl Bundle) {
+        self.add(move |world| {
+            if world.is_valid(entity) {
+                bundle.insert_into(world, entity);
+            }
+        });
+    }
+
+    /// Remove a component from an entity
+    #[allow(dead_code)]
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.add(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    /// Activate or deactivate an entity and its descendants
+    pub fn set_active(&mut self, entity: Entity, active: bool) {
+        self.add(move |world| world.set_active(entity, active));
+    }
+
+    /// Enable every component of an entity
+    #[allow(dead_code)]
+    pub fn enable(&mut self, entity: Entity) {
+        self.add(move |world| world.set_enabled(entity, true));
+    }
+
+    /// Disable every component of an entity
+    #[allow(dead_code)]
+    pub fn disable(&mut self, entity: Entity) {
+        self.add(move |world| world.set_enabled(entity, false));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+
+    impl Component for Health {
+        fn is_enabled(&self) -> bool {
+            self.0 > 0
+        }
+
+        fn disable(&mut self) {
+            self.0 = 0;
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Armor(u32);
+
+    impl Component for Armor {}
+
+    #[test]
+    fn test_commands_are_deferred_until_applied() {
+        let mut world = World::new();
+        let existing = world.spawn().with(Health(3)).build();
+
+        let mut commands = world.commands();
+        for (entity, _) in world.query::<(Entity, &Health)>() {
+            commands.insert(entity, Armor(1));
+        }
+        let spawned = commands.spawn((Health(5), Armor(2)));
+        commands.disable(existing);
+
+        // Nothing happens until the buffer is applied
+        assert!(!world.is_valid(spawned));
+        assert!(!world.has::<Armor>(existing));
+
+        world.apply_commands();
+        assert_eq!(world.get::<Armor>(spawned), Some(&Armor(2)));
+        assert_eq!(world.get::<Armor>(existing), Some(&Armor(1)));
+        assert_eq!(world.get::<Health>(existing), Some(&Health(0)));
+
+        commands.remove::<Armor>(spawned);
+        commands.despawn(existing);
+        world.apply_commands();
+        assert!(!world.has::<Armor>(spawned));
+        assert!(!world.is_valid(existing));
+    }
+}
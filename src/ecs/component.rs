@@ -3,12 +3,18 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use crate::ecs::{Entity, ComponentTypeId};
-use crate::ecs::entity::EntityId;
+use crate::ecs::reflect::{Reflect, ReflectFns};
 
 /// Component registration for automatic discovery
 pub struct ComponentRegistration {
     pub type_name: &'static str,
     pub updater: fn(&mut crate::ecs::World, f32),
+    /// Runs `Component::pre_update` for every instance of the type
+    pub pre_updater: fn(&mut crate::ecs::World, f32),
+    /// Runs `Component::update` for every instance of the type
+    pub phase_updater: fn(&mut crate::ecs::World, f32),
+    /// Field access for types that implement `Reflect`
+    pub reflect: Option<ReflectFns>,
 }
 
 impl ComponentRegistration {
@@ -18,6 +24,25 @@ impl ComponentRegistration {
             updater: |world, delta_time| {
                 world.update_component_type::<T>(delta_time);
             },
+            pre_updater: |world, delta_time| {
+                world.pre_update_component_type::<T>(delta_time);
+            },
+            phase_updater: |world, delta_time| {
+                world.phase_update_component_type::<T>(delta_time);
+            },
+            reflect: None,
+        }
+    }
+
+    /// Registration for a data component the world never updates, so it can
+    /// be found through reflection
+    pub const fn reflected<T: Component + Reflect>(type_name: &'static str) -> Self {
+        Self {
+            type_name,
+            updater: |_, _| {},
+            pre_updater: |_, _| {},
+            phase_updater: |_, _| {},
+            reflect: Some(ReflectFns::of::<T>()),
         }
     }
 }
@@ -25,6 +50,52 @@ impl ComponentRegistration {
 // Collect all registered components at compile time
 inventory::collect!(ComponentRegistration);
 
+/// Callback run by the world for one entity's component
+pub type ComponentHook = fn(&mut crate::ecs::World, Entity);
+
+/// Lifecycle callbacks for a component type, collected at startup
+///
+/// The world runs `on_add` right after the component is inserted and
+/// `on_remove` while it is still attached, including on despawn and
+/// `World::clear`. Replacing a component counts as a removal followed by an
+/// add. `on_enable`/`on_disable` run when the component starts or stops being
+/// both enabled and on an active entity, through `World::set_enabled` or
+/// `World::set_active`. A type may have several registrations, e.g. one per
+/// subsystem.
+///
+/// ```ignore
+/// inventory::submit! {
+///     ComponentHooks {
+///         on_add: Some(|world, entity| { /* index the collider */ }),
+///         ..ComponentHooks::new::<Collider>("Collider")
+///     }
+/// }
+/// ```
+pub struct ComponentHooks {
+    pub type_name: &'static str,
+    pub type_id: fn() -> TypeId,
+    pub on_add: Option<ComponentHook>,
+    pub on_remove: Option<ComponentHook>,
+    pub on_enable: Option<ComponentHook>,
+    pub on_disable: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Registration for `T` with no callbacks set
+    pub const fn new<T: Component>(type_name: &'static str) -> Self {
+        Self {
+            type_name,
+            type_id: TypeId::of::<T>,
+            on_add: None,
+            on_remove: None,
+            on_enable: None,
+            on_disable: None,
+        }
+    }
+}
+
+inventory::collect!(ComponentHooks);
+
 /// Trait for components that can automatically update themselves
 /// Components can only modify their own state, not other components
 pub trait AutoUpdatable: Component {
@@ -58,13 +129,19 @@ pub trait Component: 'static + Send + Sync {
     }
 
     /// Pre-update phase: read-only access to world, components can make decisions
-    fn pre_update(&mut self, _delta_time: f32, _world: &crate::ecs::World, _entity: crate::ecs::Entity) {
+    ///
+    /// Returns whether the component modified itself, so change detection can see it.
+    fn pre_update(&mut self, _delta_time: f32, _world: &crate::ecs::World, _entity: crate::ecs::Entity) -> bool {
         // Default implementation - components can override
+        false
     }
 
     /// Update phase: mutable access to world, components can modify other components
-    fn update(&mut self, _delta_time: f32, _world: &mut crate::ecs::World, _entity: crate::ecs::Entity) {
+    ///
+    /// Returns whether the component modified itself, so change detection can see it.
+    fn update(&mut self, _delta_time: f32, _world: &mut crate::ecs::World, _entity: crate::ecs::Entity) -> bool {
         // Default implementation - components can override
+        false
     }
 }
 
@@ -79,6 +156,12 @@ pub trait ComponentStorage: Any + Send + Sync {
     /// Check if an entity has this component
     fn has(&self, entity: Entity) -> bool;
     
+    /// Enable or disable an entity's component through `Component::enable`/`disable`
+    fn set_enabled(&mut self, entity: Entity, enabled: bool) -> bool;
+    
+    /// Check if an entity's component is enabled, `None` if it has none
+    fn enabled(&self, entity: Entity) -> Option<bool>;
+    
     /// Get the component type ID
     fn type_id(&self) -> ComponentTypeId;
     
@@ -98,19 +181,76 @@ pub trait ComponentStorage: Any + Send + Sync {
     
     /// Downcast to concrete type (mutable)
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    
+    /// Downcast to concrete type (owned)
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    
+    /// Start a new change detection period: components added or changed from
+    /// now on are stamped with `change_tick`, and only stamps newer than
+    /// `last_change_tick` count as added or changed. Forgets removals.
+    fn clear_trackers(&mut self, change_tick: u32, last_change_tick: u32);
+}
+
+/// When a component was added and last mutably accessed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: u32,
+    pub changed: u32,
+}
+
+impl ComponentTicks {
+    fn new(tick: u32) -> Self {
+        Self { added: tick, changed: tick }
+    }
+}
+
+/// Entities that lost a component of type `T` since the last `World::clear_trackers`,
+/// including entities that were despawned
+pub struct RemovedComponents<'w, T: Component> {
+    entities: &'w [Entity],
+    _marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<'w, T: Component> RemovedComponents<'w, T> {
+    pub(crate) fn new(entities: &'w [Entity]) -> Self {
+        Self { entities, _marker: std::marker::PhantomData }
+    }
+    
+    /// Iterate over the entities that lost the component
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + 'w {
+        self.entities.iter().copied()
+    }
+
 }
 
+/// Marks an unused slot in a storage's sparse array
+const EMPTY: u32 = u32::MAX;
+
 /// Concrete storage for a specific component type
+///
+/// A sparse set: `sparse` is indexed by entity id and holds the position of
+/// the entity's component in the packed `dense`/`entities` arrays. Membership
+/// checks and lookups are O(1), iteration walks only the packed arrays, and
+/// removal swaps the last component into the freed slot.
+///
+/// Each component also carries `ComponentTicks` for change detection. Any
+/// mutable access (`get_mut`, `iter_mut`, `&mut T` queries) counts as a change.
 #[derive(Debug)]
 pub struct TypedComponentStorage<T: Component> {
-    /// Sparse array mapping entity ID to dense array index
-    sparse: Vec<Option<usize>>,
-    /// Dense array of components
+    /// Entity id -> dense index, `EMPTY` when the entity has no component
+    sparse: Vec<u32>,
+    /// Packed components
     dense: Vec<T>,
-    /// Dense array of entity handles
+    /// Entity handle owning each packed component
     entities: Vec<Entity>,
-    /// Map from entity ID to dense index for faster lookup
-    entity_to_dense: HashMap<EntityId, usize>,
+    /// Added/changed ticks of each packed component
+    ticks: Vec<ComponentTicks>,
+    /// Entities whose component was removed during this change detection period
+    removed: Vec<Entity>,
+    /// Tick stamped on components added or changed now
+    change_tick: u32,
+    /// Ticks newer than this count as added or changed
+    last_change_tick: u32,
 }
 
 impl<T: Component> TypedComponentStorage<T> {
@@ -120,111 +260,113 @@ impl<T: Component> TypedComponentStorage<T> {
             sparse: Vec::new(),
             dense: Vec::new(),
             entities: Vec::new(),
-            entity_to_dense: HashMap::new(),
+            ticks: Vec::new(),
+            removed: Vec::new(),
+            change_tick: 1,
+            last_change_tick: 0,
+        }
+    }
+    
+    /// Dense index of an entity's component, if this exact entity (id and
+    /// generation) has one
+    #[inline]
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let index = *self.sparse.get(entity.id as usize)?;
+        if index == EMPTY {
+            return None;
         }
+        let index = index as usize;
+        (self.entities[index].generation == entity.generation).then_some(index)
     }
     
     /// Insert a component for an entity
     pub fn insert(&mut self, entity: Entity, component: T) -> bool {
         // Ensure sparse array is large enough
-        if entity.id as usize >= self.sparse.len() {
-            self.sparse.resize(entity.id as usize + 1, None);
+        let slot = entity.id as usize;
+        if slot >= self.sparse.len() {
+            self.sparse.resize(slot + 1, EMPTY);
         }
         
-        // Check if entity already has this component
-        if let Some(dense_index) = self.sparse[entity.id as usize] {
-            // Update existing component
-            self.dense[dense_index] = component;
-            self.entities[dense_index] = entity; // Update generation
-            return false; // Didn't add new component
+        // Replace the existing component, which may belong to an older generation
+        let index = self.sparse[slot];
+        if index != EMPTY {
+            let index = index as usize;
+            let replaced = self.entities[index].generation == entity.generation;
+            self.dense[index] = component;
+            self.entities[index] = entity;
+            if replaced {
+                self.ticks[index].changed = self.change_tick;
+            } else {
+                self.ticks[index] = ComponentTicks::new(self.change_tick);
+            }
+            return !replaced;
         }
         
         // Add new component
-        let dense_index = self.dense.len();
+        self.sparse[slot] = self.dense.len() as u32;
         self.dense.push(component);
         self.entities.push(entity);
-        self.sparse[entity.id as usize] = Some(dense_index);
-        self.entity_to_dense.insert(entity.id, dense_index);
+        self.ticks.push(ComponentTicks::new(self.change_tick));
         
         true // Added new component
     }
     
     /// Remove a component for an entity
     pub fn remove(&mut self, entity: Entity) -> bool {
-        if entity.id as usize >= self.sparse.len() {
-            return false;
-        }
+        let index = match self.dense_index(entity) {
+            Some(index) => index,
+            None => return false,
+        };
         
-        if let Some(dense_index) = self.sparse[entity.id as usize] {
-            // Verify generation matches
-            if self.entities[dense_index] != entity {
-                return false;
-            }
-            
-            // Swap-remove from dense arrays
-            let last_index = self.dense.len() - 1;
-            if dense_index != last_index {
-                // Move last element to removed position
-                self.dense.swap(dense_index, last_index);
-                self.entities.swap(dense_index, last_index);
-                
-                // Update sparse array for moved entity
-                let moved_entity = self.entities[dense_index];
-                self.sparse[moved_entity.id as usize] = Some(dense_index);
-                self.entity_to_dense.insert(moved_entity.id, dense_index);
-            }
-            
-            // Remove last element
-            self.dense.pop();
-            self.entities.pop();
-            self.sparse[entity.id as usize] = None;
-            self.entity_to_dense.remove(&entity.id);
-            
-            true
-        } else {
-            false
+        // Swap-remove from dense arrays and repoint the moved entity
+        self.dense.swap_remove(index);
+        self.entities.swap_remove(index);
+        self.ticks.swap_remove(index);
+        if let Some(moved) = self.entities.get(index) {
+            self.sparse[moved.id as usize] = index as u32;
         }
+        self.sparse[entity.id as usize] = EMPTY;
+        self.removed.push(entity);
+        
+        true
     }
     
     /// Get a component for an entity
     pub fn get(&self, entity: Entity) -> Option<&T> {
-        if entity.id as usize >= self.sparse.len() {
-            return None;
-        }
-        
-        if let Some(dense_index) = self.sparse[entity.id as usize] {
-            // Verify generation matches
-            if self.entities[dense_index] == entity {
-                Some(&self.dense[dense_index])
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        self.dense_index(entity).map(|index| &self.dense[index])
     }
     
-    /// Get a mutable component for an entity
+    /// Get a mutable component for an entity, marking it changed
     pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
-        if entity.id as usize >= self.sparse.len() {
-            return None;
-        }
-        
-        if let Some(dense_index) = self.sparse[entity.id as usize] {
-            // Verify generation matches
-            if self.entities[dense_index] == entity {
-                Some(&mut self.dense[dense_index])
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        let index = self.dense_index(entity)?;
+        self.ticks[index].changed = self.change_tick;
+        Some(&mut self.dense[index])
+    }
+    
+    /// Get the added/changed ticks of an entity's component
+    pub fn ticks(&self, entity: Entity) -> Option<ComponentTicks> {
+        self.dense_index(entity).map(|index| self.ticks[index])
+    }
+    
+    /// Check if an entity's component was added since the last `World::clear_trackers`
+    pub fn is_added(&self, entity: Entity) -> bool {
+        self.ticks(entity).is_some_and(|ticks| ticks.added > self.last_change_tick)
+    }
+    
+    /// Check if an entity's component was added or mutably accessed since the
+    /// last `World::clear_trackers`
+    pub fn is_changed(&self, entity: Entity) -> bool {
+        self.ticks(entity).is_some_and(|ticks| ticks.changed > self.last_change_tick)
+    }
+    
+    /// Entities whose component was removed since the last `World::clear_trackers`
+    pub fn removed(&self) -> &[Entity] {
+        &self.removed
     }
     
     /// Check if an entity has this component
     pub fn has(&self, entity: Entity) -> bool {
-        self.get(entity).is_some()
+        self.dense_index(entity).is_some()
     }
     
     /// Iterate over all components and their entities
@@ -232,11 +374,46 @@ impl<T: Component> TypedComponentStorage<T> {
         self.entities.iter().zip(self.dense.iter()).map(|(&entity, component)| (entity, component))
     }
     
-    /// Iterate over all components and their entities (mutable)
+    /// Iterate over all components and their entities (mutable), marking every component changed
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.mark_all_changed();
         self.entities.iter().zip(self.dense.iter_mut()).map(|(&entity, component)| (entity, component))
     }
     
+    /// Iterate over all components and their entities (mutable) without marking
+    /// them changed; callers mark what they modified with `set_changed`
+    pub(crate) fn iter_mut_untracked(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.entities.iter().zip(self.dense.iter_mut()).map(|(&entity, component)| (entity, component))
+    }
+    
+    /// Mark an entity's component changed
+    pub fn set_changed(&mut self, entity: Entity) {
+        if let Some(index) = self.dense_index(entity) {
+            self.ticks[index].changed = self.change_tick;
+        }
+    }
+    
+    #[allow(dead_code)] // Only reached through `iter_mut` and `components_mut`, which the game never calls
+    fn mark_all_changed(&mut self) {
+        for ticks in &mut self.ticks {
+            ticks.changed = self.change_tick;
+        }
+    }
+    
+    /// Raw pointer to an entity's component for queries that write, marking
+    /// it changed; lets a query hand out several mutable borrows at once
+    ///
+    /// # Safety
+    /// `this` must point to a live, mutably borrowed storage, the caller must
+    /// not create aliasing mutable references through the returned pointer,
+    /// and nothing else may be reading the storage's ticks concurrently.
+    pub(crate) unsafe fn get_ptr_mut(this: *mut Self, entity: Entity) -> Option<*mut T> {
+        let index = (*this).dense_index(entity)?;
+        let change_tick = (*this).change_tick;
+        (*(*this).ticks.as_mut_ptr().add(index)).changed = change_tick;
+        Some((*this).dense.as_mut_ptr().add(index))
+    }
+    
     /// Get all entities that have this component
     pub fn entities(&self) -> &[Entity] {
         &self.entities
@@ -247,8 +424,9 @@ impl<T: Component> TypedComponentStorage<T> {
         &self.dense
     }
     
-    /// Get all components (dense array, mutable)
+    /// Get all components (dense array, mutable), marking every component changed
     pub fn components_mut(&mut self) -> &mut [T] {
+        self.mark_all_changed();
         &mut self.dense
     }
 }
@@ -270,6 +448,24 @@ impl<T: Component> ComponentStorage for TypedComponentStorage<T> {
         self.has(entity)
     }
     
+    fn set_enabled(&mut self, entity: Entity, enabled: bool) -> bool {
+        match self.get_mut(entity) {
+            Some(component) => {
+                if enabled {
+                    component.enable();
+                } else {
+                    component.disable();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    
+    fn enabled(&self, entity: Entity) -> Option<bool> {
+        self.get(entity).map(Component::is_enabled)
+    }
+    
     fn type_id(&self) -> ComponentTypeId {
         TypeId::of::<T>()
     }
@@ -278,7 +474,8 @@ impl<T: Component> ComponentStorage for TypedComponentStorage<T> {
         self.sparse.clear();
         self.dense.clear();
         self.entities.clear();
-        self.entity_to_dense.clear();
+        self.ticks.clear();
+        self.removed.clear();
     }
     
     fn len(&self) -> usize {
@@ -292,6 +489,16 @@ impl<T: Component> ComponentStorage for TypedComponentStorage<T> {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+    
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+    
+    fn clear_trackers(&mut self, change_tick: u32, last_change_tick: u32) {
+        self.change_tick = change_tick;
+        self.last_change_tick = last_change_tick;
+        self.removed.clear();
+    }
 }
 
 impl<T: Component> Default for TypedComponentStorage<T> {
@@ -300,11 +507,20 @@ impl<T: Component> Default for TypedComponentStorage<T> {
     }
 }
 
+impl Default for ComponentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Manager for all component storages
-#[derive(Default)]
 pub struct ComponentManager {
     /// Map from component type ID to storage
     storages: HashMap<ComponentTypeId, Box<dyn ComponentStorage>>,
+    /// Tick stamped on components added or changed now
+    change_tick: u32,
+    /// Ticks newer than this count as added or changed
+    last_change_tick: u32,
 }
 
 impl ComponentManager {
@@ -312,6 +528,8 @@ impl ComponentManager {
     pub fn new() -> Self {
         Self {
             storages: HashMap::new(),
+            change_tick: 1,
+            last_change_tick: 0,
         }
     }
     
@@ -319,7 +537,18 @@ impl ComponentManager {
     pub fn register<T: Component>(&mut self) {
         let type_id = TypeId::of::<T>();
         if !self.storages.contains_key(&type_id) {
-            self.storages.insert(type_id, Box::new(TypedComponentStorage::<T>::new()));
+            let mut storage = TypedComponentStorage::<T>::new();
+            storage.clear_trackers(self.change_tick, self.last_change_tick);
+            self.storages.insert(type_id, Box::new(storage));
+        }
+    }
+    
+    /// Start a new change detection period in every storage
+    pub fn clear_trackers(&mut self) {
+        self.last_change_tick = self.change_tick;
+        self.change_tick += 1;
+        for storage in self.storages.values_mut() {
+            storage.clear_trackers(self.change_tick, self.last_change_tick);
         }
     }
     
@@ -389,6 +618,17 @@ impl ComponentManager {
         }
     }
     
+    /// Check if an entity has a component of a type known only by its id
+    pub fn has_type(&self, type_id: ComponentTypeId, entity: Entity) -> bool {
+        self.storages.get(&type_id).is_some_and(|storage| storage.has(entity))
+    }
+    
+    /// Check if an entity's component of a type known only by its id is
+    /// enabled, `None` if it has none
+    pub fn enabled_type(&self, type_id: ComponentTypeId, entity: Entity) -> Option<bool> {
+        self.storages.get(&type_id).and_then(|storage| storage.enabled(entity))
+    }
+    
     /// Get typed storage for a component type
     pub fn storage<T: Component>(&self) -> Option<&TypedComponentStorage<T>> {
         let type_id = TypeId::of::<T>();
@@ -412,6 +652,20 @@ impl ComponentManager {
         }
     }
     
+    /// Get mutable typed storage without registering the component type
+    pub(crate) fn existing_storage_mut<T: Component>(&mut self) -> Option<&mut TypedComponentStorage<T>> {
+        self.storages.get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<TypedComponentStorage<T>>()
+    }
+    
+    /// Enable or disable every component of an entity
+    pub fn set_enabled_all(&mut self, entity: Entity, enabled: bool) {
+        for storage in self.storages.values_mut() {
+            storage.set_enabled(entity, enabled);
+        }
+    }
+    
     /// Remove all components for an entity
     pub fn remove_all(&mut self, entity: Entity) {
         for storage in self.storages.values_mut() {
@@ -425,26 +679,61 @@ impl ComponentManager {
             storage.clear();
         }
     }
-
-    /// Pre-update all components of a specific type
-    pub fn pre_update_components<T: Component>(&mut self, delta_time: f32, world: &crate::ecs::World) {
-        if let Some(storage) = self.storage_mut::<T>() {
-            for (entity, component) in storage.iter_mut() {
-                if entity.enabled && component.is_enabled() {
-                    component.pre_update(delta_time, world, entity);
-                }
+    
+    /// Temporarily take a component type's storage out of the manager, so its
+    /// components can be handed the rest of the world while they update
+    pub(crate) fn take_storage<T: Component>(&mut self) -> Option<Box<dyn ComponentStorage>> {
+        self.storages.remove(&TypeId::of::<T>())
+    }
+    
+    /// Put back a storage taken with `take_storage`. Components of the same
+    /// type added while the storage was out are merged into it.
+    pub(crate) fn restore_storage<T: Component>(&mut self, mut storage: Box<dyn ComponentStorage>) {
+        if let Some(added) = self.storages.remove(&TypeId::of::<T>()) {
+            let added = added.into_any().downcast::<TypedComponentStorage<T>>()
+                .expect("component storage type mismatch");
+            let typed = storage.as_any_mut().downcast_mut::<TypedComponentStorage<T>>()
+                .expect("component storage type mismatch");
+            for (entity, component) in added.entities.into_iter().zip(added.dense) {
+                typed.insert(entity, component);
             }
+            typed.removed.extend(added.removed);
         }
+        self.storages.insert(TypeId::of::<T>(), storage);
     }
+}
 
-    /// Update all components of a specific type
-    pub fn update_components<T: Component>(&mut self, delta_time: f32, world: &mut crate::ecs::World) {
-        if let Some(storage) = self.storage_mut::<T>() {
-            for (entity, component) in storage.iter_mut() {
-                if entity.enabled && component.is_enabled() {
-                    component.update(delta_time, world, entity);
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+    
+    impl Component for Health {}
+    
+    #[test]
+    fn test_sparse_set_swap_remove_keeps_lookups_valid() {
+        let mut storage = TypedComponentStorage::new();
+        let entities: Vec<Entity> = (0..4).map(|id| Entity::new(id, 0)).collect();
+        for (health, &entity) in entities.iter().enumerate() {
+            assert!(storage.insert(entity, Health(health as u32)));
         }
+        
+        // Removing from the middle moves the last component into the gap
+        assert!(storage.remove(entities[1]));
+        assert!(!storage.remove(entities[1]));
+        assert_eq!(storage.len(), 3);
+        assert_eq!(storage.get(entities[3]), Some(&Health(3)));
+        assert_eq!(storage.get(entities[0]), Some(&Health(0)));
+        assert!(!storage.has(entities[1]));
+        
+        // A recycled id with a newer generation doesn't see the old component
+        let recycled = Entity::new(0, 1);
+        assert_eq!(storage.get(recycled), None);
+        assert!(storage.insert(recycled, Health(10)));
+        assert_eq!(storage.get(entities[0]), None);
+        assert_eq!(storage.get(recycled), Some(&Health(10)));
+        assert_eq!(storage.len(), 3);
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,517 @@
+//! Character controller component for capsule movement
+
+use macroquad::prelude::*;
+use crate::ecs::{Component, Entity, component::ComponentRegistration};
+use crate::ecs::collision::{CollisionWorld, ColliderProxy};
+use crate::ecs::reflect::reflect_component;
+use super::{ColliderShape, Transform};
+
+/// Slide iterations per move, enough to settle into a corner
+const MAX_SLIDES: usize = 4;
+
+/// How far above the ground a falling character may stop and still land
+const LANDING_TOLERANCE: f32 = 0.02;
+
+/// Gap left between a pushed character and whatever pushed it
+const PUSH_SKIN: f32 = 0.005;
+
+/// The surface a character stands on, found by `CharacterController::probe_ground`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundHit {
+    pub height: f32,     // Height of the surface under the feet
+    pub normal: Vec3,    // Unit surface normal
+    pub entity: Entity,  // Collider the surface belongs to
+}
+
+/// Moves an entity as an upright capsule that slides along walls, steps onto
+/// low ledges and stands on walkable ground
+///
+/// The capsule hangs from the entity's `Transform`: its lowest point, the
+/// feet, is `foot_offset` below the transform position. Only static solid
+/// colliders in the `CollisionWorld` block it.
+#[derive(Debug, Clone)]
+pub struct CharacterController {
+    pub height: f32,        // Total capsule height, feet to head
+    pub radius: f32,        // Capsule radius
+    pub foot_offset: f32,   // Distance from the transform position down to the feet
+    pub step_height: f32,   // Tallest ledge walked onto without jumping
+    pub max_slope: f32,     // Steepest walkable ground, in radians
+    pub coyote_time: f32,   // Seconds after walking off a ledge that a jump is still allowed
+    pub is_grounded: bool,  // Standing on ground rather than jumping or falling
+    pub air_time: f32,      // Seconds since the character last stood on ground
+    pub ground: Option<GroundHit>,  // What the character stands on while grounded
+    pub enabled: bool,
+}
+
+impl CharacterController {
+    /// Create a controller for a capsule of the given total height and radius
+    pub fn new(height: f32, radius: f32) -> Self {
+        Self {
+            height,
+            radius,
+            foot_offset: (height * 0.5 - radius).max(0.0),  // Transform a radius below the capsule's centre
+            step_height: 0.3,
+            max_slope: 45.0_f32.to_radians(),
+            coyote_time: 0.1,
+            is_grounded: true,
+            air_time: 0.0,
+            ground: None,
+            enabled: true,
+        }
+    }
+
+    /// Check if this component is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Move horizontally by `motion` from `position`, returning where the
+    /// character ends up
+    ///
+    /// The body is swept through the collision world and slides along what
+    /// it hits. It starts `step_height` above the feet, so lower ledges pass
+    /// underneath and are stepped onto when the character is snapped to the
+    /// ground. Ground steeper than `max_slope` rising under the feet blocks
+    /// the move; if no ground is left the character stops being grounded.
+    pub fn move_and_slide(&mut self, collision: &CollisionWorld, position: Vec3, motion: Vec3) -> Vec3 {
+        let feet = position.y - self.foot_offset;
+        let (core_height, center_height) = self.body();
+        let mut center = Vec3::new(position.x, feet + center_height, position.z);
+        let mut remaining = Vec3::new(motion.x, 0.0, motion.z);
+
+        for _ in 0..MAX_SLIDES {
+            if remaining.length_squared() <= f32::EPSILON * f32::EPSILON {
+                break;
+            }
+            match collision.sweep_capsule(center, remaining, core_height, self.radius, blocks_character) {
+                Some(hit) => {
+                    center += remaining * hit.time;
+                    // Slide along the wall; only its horizontal push counts so the body never climbs
+                    let left = remaining * (1.0 - hit.time);
+                    let normal = Vec3::new(hit.normal.x, 0.0, hit.normal.z).normalize_or_zero();
+                    remaining = left - normal * left.dot(normal);
+                }
+                None => {
+                    center += remaining;
+                    break;
+                }
+            }
+        }
+
+        let mut moved = Vec3::new(center.x, position.y, center.z);
+        if self.is_grounded {
+            match self.probe_ground(collision, moved) {
+                Some(hit) if self.is_walkable(hit.normal) => {
+                    moved.y = hit.height + self.foot_offset;
+                    self.ground = Some(hit);
+                }
+                Some(hit) if hit.height > feet => return position,
+                _ => self.leave_ground(),
+            }
+        }
+        moved
+    }
+
+    /// Move an airborne character by `velocity` for `delta_time` under the
+    /// `gravity` acceleration, returning where it ends up
+    ///
+    /// The whole capsule is swept, so the character stops under ceilings and
+    /// slides down ground too steep to stand on, carrying the slide in
+    /// `velocity`. It lands on walkable ground once it comes to rest on it
+    /// while gravity pulls it down, which clears `velocity`; in zero-g or
+    /// sideways gravity it drifts on. Grounded characters stay put.
+    pub fn fall(&mut self, collision: &CollisionWorld, position: Vec3, velocity: &mut Vec3, gravity: Vec3, delta_time: f32) -> Vec3 {
+        if self.is_grounded {
+            return position;
+        }
+        self.air_time += delta_time;
+        *velocity += gravity * delta_time;
+
+        let (mut center, core_height) = self.capsule(position);
+        let center_offset = center.y - position.y;
+        let mut remaining = *velocity * delta_time;
+
+        for _ in 0..MAX_SLIDES {
+            if remaining.length_squared() <= f32::EPSILON * f32::EPSILON {
+                break;
+            }
+            match collision.sweep_capsule(center, remaining, core_height, self.radius, blocks_character) {
+                Some(hit) => {
+                    center += remaining * hit.time;
+                    let left = remaining * (1.0 - hit.time);
+                    remaining = left - hit.normal * left.dot(hit.normal);
+                    let into_surface = velocity.dot(hit.normal);
+                    if into_surface < 0.0 {
+                        *velocity -= hit.normal * into_surface;
+                    }
+                }
+                None => {
+                    center += remaining;
+                    break;
+                }
+            }
+        }
+
+        let mut moved = Vec3::new(center.x, center.y - center_offset, center.z);
+        if gravity.y < 0.0 && velocity.y <= 0.0 {
+            let feet = moved.y - self.foot_offset;
+            if let Some(hit) = self.find_ground(collision, moved).filter(|hit| hit.height >= feet - LANDING_TOLERANCE) {
+                moved.y = hit.height + self.foot_offset;
+                *velocity = Vec3::ZERO;
+                self.land(hit);
+            }
+        }
+        moved
+    }
+
+    /// Check if a jump may start: on the ground, or within `coyote_time` of
+    /// walking off it
+    pub fn can_jump(&self) -> bool {
+        self.is_grounded || self.air_time < self.coyote_time
+    }
+
+    /// Leave the ground for a jump, returning false if the character cannot
+    /// jump right now
+    pub fn jump(&mut self) -> bool {
+        if !self.can_jump() {
+            return false;
+        }
+        self.leave_ground();
+        // Spend the coyote time so the jump cannot be repeated in mid-air
+        self.air_time = self.air_time.max(self.coyote_time);
+        true
+    }
+
+    /// Stand on `ground`
+    pub fn land(&mut self, ground: GroundHit) {
+        self.is_grounded = true;
+        self.air_time = 0.0;
+        self.ground = Some(ground);
+    }
+
+    /// Start falling, keeping the time already spent in the air
+    pub fn leave_ground(&mut self) {
+        self.is_grounded = false;
+        self.ground = None;
+    }
+
+    /// Check if the body would overlap a blocking collider with the entity's
+    /// transform at `position`
+    pub fn is_blocked_at(&self, collision: &CollisionWorld, position: Vec3) -> bool {
+        let (core_height, center_height) = self.body();
+        let center = Vec3::new(position.x, position.y - self.foot_offset + center_height, position.z);
+        collision.overlap_capsule(center, core_height, self.radius)
+            .into_iter()
+            .filter_map(|entity| collision.get(entity))
+            .any(blocks_character)
+    }
+
+    /// How far the body must move sideways to stop overlapping `shape` placed
+    /// by `transform`, with the entity's transform at `position`, or `None`
+    /// if they don't overlap
+    ///
+    /// A body whose core is inside the shape is pushed along `fallback`. The
+    /// push is zero when the shape presses straight down or up on the body.
+    pub fn separation(&self, shape: &ColliderShape, transform: &Transform, position: Vec3, fallback: Vec3) -> Option<Vec3> {
+        let (core_height, center_height) = self.body();
+        let center = Vec3::new(position.x, position.y - self.foot_offset + center_height, position.z);
+        let half_height = Vec3::new(0.0, core_height * 0.5, 0.0);
+        let (on_core, on_shape) = shape.closest_to_segment(transform, center - half_height, center + half_height);
+        let distance = on_core.distance(on_shape);
+        if distance >= self.radius {
+            return None;
+        }
+        let away = if distance > f32::EPSILON { on_core - on_shape } else { fallback };
+        let direction = Vec3::new(away.x, 0.0, away.z).normalize_or_zero();
+        Some(direction * (self.radius - distance + PUSH_SKIN))
+    }
+
+    /// The walkable ground within `step_height` above or below the feet at
+    /// `position`
+    pub fn find_ground(&self, collision: &CollisionWorld, position: Vec3) -> Option<GroundHit> {
+        self.probe_ground(collision, position)
+            .filter(|hit| self.is_walkable(hit.normal))
+    }
+
+    /// Drop a sphere of the capsule's radius from `step_height` above the feet
+    /// at `position` to `step_height` below them, returning the surface it
+    /// lands on, walkable or not
+    pub fn probe_ground(&self, collision: &CollisionWorld, position: Vec3) -> Option<GroundHit> {
+        let feet = position.y - self.foot_offset;
+        let start = Vec3::new(position.x, feet + self.step_height + self.radius, position.z);
+        let drop = Vec3::new(0.0, -2.0 * self.step_height, 0.0);
+        collision.sweep_capsule(start, drop, 0.0, self.radius, blocks_character)
+            .map(|hit| GroundHit {
+                height: start.y + drop.y * hit.time - self.radius,
+                normal: hit.normal,
+                entity: hit.entity,
+            })
+    }
+
+    /// Check if ground with the given normal is flat enough to stand on
+    pub fn is_walkable(&self, normal: Vec3) -> bool {
+        normal.y >= self.max_slope.cos()
+    }
+
+    /// Centre and core height of the whole capsule, feet to head, with the
+    /// entity's transform at `position`
+    pub fn capsule(&self, position: Vec3) -> (Vec3, f32) {
+        let core_height = (self.height - 2.0 * self.radius).max(0.0);
+        let feet = position.y - self.foot_offset;
+        (Vec3::new(position.x, feet + self.radius + core_height * 0.5, position.z), core_height)
+    }
+
+    /// Collider matching the whole capsule, to be placed at its centre
+    pub fn collider_shape(&self) -> ColliderShape {
+        let (_, core_height) = self.capsule(Vec3::ZERO);
+        ColliderShape::Capsule { height: core_height, radius: self.radius }
+    }
+
+    /// Core height of the body capsule and its centre's height above the feet
+    fn body(&self) -> (f32, f32) {
+        let core_height = (self.height - self.step_height - 2.0 * self.radius).max(0.0);
+        (core_height, self.step_height + self.radius + core_height * 0.5)
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self::new(1.8, 0.25)
+    }
+}
+
+/// Colliders a character cannot move through
+fn blocks_character(proxy: &ColliderProxy) -> bool {
+    proxy.is_static && proxy.is_solid()
+}
+
+impl Component for CharacterController {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+reflect_component!(CharacterController {
+    height: f32,
+    radius: f32,
+    foot_offset: f32,
+    step_height: f32,
+    max_slope: f32,
+    coyote_time: f32,
+    is_grounded: bool,
+    air_time: f32,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<CharacterController>("CharacterController")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::ecs::{Collider, Gravity, World};
+    use crate::ecs::collision::fixtures::{cube_hull, floored_world, mesh_floored_world, spawn_box};
+
+    fn move_character(world: &World, controller: &mut CharacterController, position: Vec3, motion: Vec3) -> Vec3 {
+        controller.move_and_slide(world.resource::<CollisionWorld>().unwrap(), position, motion)
+    }
+
+    #[test]
+    fn test_character_slides_and_cannot_tunnel() {
+        let mut world = floored_world();
+        spawn_box(&mut world, Vec3::new(2.0, 1.5, 0.0), Vec3::new(0.05, 3.0, 10.0));
+        let mut controller = CharacterController::default();
+        let start = Vec3::new(0.0, controller.foot_offset, 0.0);
+
+        // One huge step straight at a thin wall stops at the wall
+        let stopped = move_character(&world, &mut controller, start, Vec3::new(10.0, 0.0, 0.0));
+        assert!(stopped.x < 2.0 - controller.radius + 0.01 && stopped.x > 1.5, "stopped at {:?}", stopped);
+        assert!((stopped.y - controller.foot_offset).abs() < 0.01);
+
+        // Moving diagonally into the wall keeps the sideways part
+        let slid = move_character(&world, &mut controller, stopped, Vec3::new(1.0, 0.0, 1.0));
+        assert!(slid.x < 2.0 - controller.radius + 0.01);
+        assert!((slid.z - 1.0).abs() < 0.01, "slid to {:?}", slid);
+        assert!(controller.is_grounded);
+    }
+
+    #[test]
+    fn test_character_steps_up_and_respects_slopes() {
+        let mut world = floored_world();
+        spawn_box(&mut world, Vec3::new(2.0, 0.1, 0.0), Vec3::new(1.0, 0.2, 4.0));
+        spawn_box(&mut world, Vec3::new(-2.0, 0.5, 0.0), Vec3::new(1.0, 1.0, 4.0));
+        let ramp = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 0.0, 3.0)).with_rotation(Vec3::new(1.2, 0.0, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(4.0, 0.1, 1.0) }))
+            .build();
+        let mut controller = CharacterController::default();
+        let start = Vec3::new(0.0, controller.foot_offset, 0.0);
+
+        // A 0.2 ledge is stepped onto
+        let stepped = move_character(&world, &mut controller, start, Vec3::new(2.0, 0.0, 0.0));
+        assert!((stepped.x - 2.0).abs() < 0.01 && (stepped.y - 0.2 - controller.foot_offset).abs() < 0.01, "stepped to {:?}", stepped);
+
+        // A 1.0 block is a wall
+        let blocked = move_character(&world, &mut controller, start, Vec3::new(-2.0, 0.0, 0.0));
+        assert!(blocked.x > -1.5 && (blocked.y - controller.foot_offset).abs() < 0.01, "blocked at {:?}", blocked);
+
+        // Ground tilted past the slope limit is not walked onto
+        assert!(world.resource::<CollisionWorld>().unwrap().contains(ramp));
+        let steep = move_character(&world, &mut controller, start, Vec3::new(0.0, 0.0, 3.0));
+        assert_eq!(steep, start);
+
+        // Stepping down the low ledge keeps the character grounded, walking
+        // off the tall block leaves it falling
+        let stepped_down = move_character(&world, &mut controller, stepped, Vec3::new(1.0, 0.0, 0.0));
+        assert!((stepped_down.y - controller.foot_offset).abs() < 0.01 && controller.is_grounded);
+        let on_block = Vec3::new(-2.0, 1.0 + controller.foot_offset, 0.0);
+        let ground = controller.find_ground(world.resource::<CollisionWorld>().unwrap(), on_block);
+        assert!(ground.is_some_and(|ground| (ground.height - 1.0).abs() < 0.01));
+        move_character(&world, &mut controller, on_block, Vec3::new(2.0, 0.0, 0.0));
+        assert!(!controller.is_grounded);
+    }
+
+    #[test]
+    fn test_character_capsule_stands_on_probed_ground() {
+        let mut world = floored_world();
+        spawn_box(&mut world, Vec3::new(2.0, 0.1, 0.0), Vec3::new(1.0, 0.2, 4.0));
+        let collision = world.resource::<CollisionWorld>().unwrap();
+
+        for (height, radius) in [(1.8, 0.25), (1.2, 0.4), (2.5, 0.3)] {
+            let controller = CharacterController::new(height, radius);
+            assert!((controller.foot_offset - (height * 0.5 - radius)).abs() < 1e-6);
+
+            // Standing the transform on the probed ground puts the bottom of
+            // the capsule on it, on the floor and on the ledge alike
+            for (x, ground_height) in [(0.0, 0.0), (2.0, 0.2)] {
+                let guess = Vec3::new(x, ground_height + controller.foot_offset + 0.1, 0.0);
+                let hit = controller.probe_ground(collision, guess).unwrap();
+                assert!((hit.height - ground_height).abs() < 0.01, "probed {:?} at x {}", hit.height, x);
+
+                let standing = Vec3::new(x, hit.height + controller.foot_offset, 0.0);
+                let (center, core_height) = controller.capsule(standing);
+                let bottom = center.y - core_height * 0.5 - controller.radius;
+                assert!((bottom - ground_height).abs() < 0.01, "capsule bottom at {} for {:?}", bottom, (height, radius));
+            }
+        }
+    }
+
+    /// Let a character fall for up to `ticks` fixed ticks, stopping once it lands
+    fn fall_character(world: &World, controller: &mut CharacterController, mut position: Vec3, ticks: usize) -> Vec3 {
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        let mut velocity = Vec3::ZERO;
+        for _ in 0..ticks {
+            position = controller.fall(collision, position, &mut velocity, Gravity::DEFAULT, 1.0 / 60.0);
+            if controller.is_grounded {
+                break;
+            }
+        }
+        position
+    }
+
+    #[test]
+    fn test_character_lands_on_raised_floors_and_slides_off_steep_ground() {
+        let mut world = floored_world();
+        let platform = spawn_box(&mut world, Vec3::new(0.0, 1.0, 0.0), Vec3::new(2.0, 0.2, 2.0));
+        spawn_box(&mut world, Vec3::new(4.0, 0.0, 0.0), Vec3::new(2.0, 0.1, 2.0));
+        world.spawn()
+            .with(Transform::new(Vec3::new(4.0, 1.0, 0.0)).with_rotation(Vec3::new(0.0, 0.0, 1.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(3.0, 0.1, 3.0) }))
+            .build();
+
+        // Dropped above the platform, the character lands on its top
+        let mut controller = CharacterController::default();
+        controller.leave_ground();
+        let landed = fall_character(&world, &mut controller, Vec3::new(0.0, 3.0, 0.0), 120);
+        assert!(controller.is_grounded, "still falling at {:?}", landed);
+        assert!((landed.y - 1.1 - controller.foot_offset).abs() < 0.01, "landed at {:?}", landed);
+        let ground = controller.ground.unwrap();
+        assert_eq!(ground.entity, platform);
+        assert!(ground.normal.y > 0.99);
+
+        // Ground tilted past the slope limit is slid down rather than stood on
+        let mut controller = CharacterController::default();
+        controller.leave_ground();
+        let slid = fall_character(&world, &mut controller, Vec3::new(4.0, 3.0, 0.0), 240);
+        assert!(controller.is_grounded, "still sliding at {:?}", slid);
+        assert!((slid.x - 4.0).abs() > 0.5, "stuck on the slope at {:?}", slid);
+    }
+
+    #[test]
+    fn test_character_jump_allows_coyote_time() {
+        let mut world = floored_world();
+        spawn_box(&mut world, Vec3::new(0.0, 0.5, 0.0), Vec3::new(2.0, 1.0, 2.0));
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        let mut controller = CharacterController::default();
+        let height = 1.0 + controller.foot_offset;
+        assert!(controller.can_jump());
+
+        // Walking off the block still allows a jump for a moment
+        controller.move_and_slide(collision, Vec3::new(0.0, height, 0.0), Vec3::new(2.0, 0.0, 0.0));
+        assert!(!controller.is_grounded && controller.can_jump());
+        let mut velocity = Vec3::ZERO;
+        controller.fall(collision, Vec3::new(2.0, height, 0.0), &mut velocity, Gravity::DEFAULT, 0.05);
+        assert!(controller.jump());
+
+        // Only once, and not after the coyote time runs out
+        assert!(!controller.jump());
+        let mut controller = CharacterController::default();
+        controller.leave_ground();
+        controller.fall(collision, Vec3::new(2.0, height, 0.0), &mut velocity, Gravity::DEFAULT, 0.2);
+        assert!(!controller.jump());
+    }
+
+    #[test]
+    fn test_character_drifts_in_zero_g() {
+        let world = floored_world();
+        let collision = world.resource::<CollisionWorld>().unwrap();
+        let mut controller = CharacterController::default();
+        controller.leave_ground();
+
+        // Drifting into the floor stops the character on it without landing
+        let mut velocity = Vec3::new(1.0, -1.0, 0.0);
+        let mut position = Vec3::new(0.0, 1.0, 0.0);
+        for _ in 0..60 {
+            position = controller.fall(collision, position, &mut velocity, Vec3::ZERO, 1.0 / 60.0);
+        }
+        assert!(!controller.is_grounded);
+        assert!((position.y - controller.foot_offset).abs() < 0.01 && (position.x - 1.0).abs() < 0.01, "drifted to {:?}", position);
+        assert!(velocity.abs_diff_eq(Vec3::X, 1e-4), "velocity {:?}", velocity);
+
+        // Sideways gravity pulls the character along the floor
+        let mut velocity = Vec3::ZERO;
+        controller.fall(collision, position, &mut velocity, Vec3::new(0.0, 0.0, 5.0), 0.5);
+        assert!(!controller.is_grounded && velocity.z > 2.0);
+    }
+
+    #[test]
+    fn test_character_walks_on_mesh_floors() {
+        let (mut world, floor) = mesh_floored_world();
+        world.spawn()
+            .with(Transform::new(Vec3::new(3.0, 1.0, 0.0)))
+            .with(Collider::static_solid(ColliderShape::ConvexHull { hull: Arc::new(cube_hull(2.0)) }))
+            .build();
+
+        let mut controller = CharacterController::default();
+        controller.leave_ground();
+        let landed = fall_character(&world, &mut controller, Vec3::new(0.0, 3.0, 0.0), 120);
+        assert!(controller.is_grounded, "still falling at {:?}", landed);
+        assert!((landed.y - controller.foot_offset).abs() < 0.01, "landed at {:?}", landed);
+        assert_eq!(controller.ground.unwrap().entity, floor);
+
+        // The hull is a wall too tall to step onto
+        let blocked = move_character(&world, &mut controller, landed, Vec3::new(4.0, 0.0, 0.0));
+        assert!(blocked.x < 2.0 - controller.radius + 0.01 && blocked.x > 1.0, "blocked at {:?}", blocked);
+        assert!((blocked.y - controller.foot_offset).abs() < 0.01 && controller.is_grounded);
+    }
+}
@@ -1,8 +1,12 @@
 //! Collision detection components
 
+use std::sync::Arc;
 use macroquad::prelude::*;
-use crate::ecs::{Component, World};
-use super::{Transform, StaticRenderer};
+use crate::ecs::{Component, World, component::ComponentRegistration};
+use crate::ecs::collision::CollisionWorld;
+use crate::ecs::reflect::reflect_component;
+use super::{Transform, CharacterController};
+use super::collision_mesh::{Aabb, TriMesh, ConvexHull, closest_on_triangle, convex_overlap};
 
 #[derive(Debug, Clone)]
 pub struct Collider {
@@ -10,6 +14,8 @@ pub struct Collider {
     pub is_static: bool,        // Static vs dynamic objects
     pub is_trigger: bool,       // Trigger vs solid collision
     pub material: ColliderMaterial, // Physics material properties
+    pub layer: u32,             // Collision layers this collider belongs to, as bit flags
+    pub mask: u32,              // Collision layers this collider interacts with
     pub enabled: bool,
 }
 
@@ -18,6 +24,18 @@ pub enum ColliderShape {
     Box { size: Vec3 },
     Sphere { radius: f32 },
     Capsule { height: f32, radius: f32 },
+    /// Triangles of a mesh, for static level geometry
+    TriMesh { mesh: Arc<TriMesh> },
+    /// Convex hull around the vertices of a mesh
+    ConvexHull { hull: Arc<ConvexHull> },
+}
+
+/// A point where two colliders touch, from `ColliderShape::contacts_with`
+#[derive(Debug, Clone, Copy)]
+pub struct ContactPoint {
+    pub point: Vec3,   // World-space contact point
+    pub normal: Vec3,  // Unit direction pushing the first shape out of the second
+    pub depth: f32,    // How far the shapes overlap along the normal
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +46,13 @@ pub struct ColliderMaterial {
 }
 
 impl Collider {
+    /// Layer every collider is on unless told otherwise
+    pub const DEFAULT_LAYER: u32 = 1 << 0;
+    /// Layer of the player and other characters
+    pub const CHARACTER_LAYER: u32 = 1 << 1;
+    /// Mask that interacts with every layer
+    pub const ALL_LAYERS: u32 = u32::MAX;
+
     /// Create a new collider with specified properties
     pub fn new(shape: ColliderShape, is_static: bool, is_trigger: bool) -> Self {
         Self {
@@ -35,6 +60,8 @@ impl Collider {
             is_static,
             is_trigger,
             material: ColliderMaterial::default(),
+            layer: Self::DEFAULT_LAYER,
+            mask: Self::ALL_LAYERS,
             enabled: true,
         }
     }
@@ -65,19 +92,16 @@ impl Collider {
         self
     }
 
-    pub fn with_enabled(mut self, enabled: bool) -> Self {
-        self.enabled = enabled;
+    /// Put the collider on the `layer` flags and let it interact with the `mask` flags
+    pub fn with_layers(mut self, layer: u32, mask: u32) -> Self {
+        self.layer = layer;
+        self.mask = mask;
         self
     }
 
-    /// Enable this component
-    pub fn enable(&mut self) {
-        self.enabled = true;
-    }
-
-    /// Disable this component
-    pub fn disable(&mut self) {
-        self.enabled = false;
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
     }
 
     /// Check if this component is enabled
@@ -86,32 +110,22 @@ impl Collider {
     }
 
     /// Check if this collider blocks movement (solid and not trigger and enabled)
+    // The collision world now filters proxies itself
+    #[allow(dead_code)]
     pub fn blocks_movement(&self) -> bool {
         self.enabled && !self.is_trigger
     }
 
     /// Check for collision at a specific position, considering the shape and size of colliders
+    ///
+    /// `player_position` is placed like a character's transform, with a
+    /// default `CharacterController` body of `player_radius`. Only static solid
+    /// colliders in the world's `CollisionWorld` block, so moving bodies such
+    /// as the player never collide with themselves.
     pub fn check_position_collision(world: &World, player_position: Vec3, player_radius: f32) -> bool {
-        let player_shape = ColliderShape::Capsule { height: 1.8, radius: player_radius };
-        let player_transform = Transform::new(player_position);
-
-        for (entity, transform, collider, _) in world.query_3::<Transform, Collider, StaticRenderer>() {
-            // Skip disabled entities or disabled colliders
-            if !world.is_valid(entity) || !entity.enabled || !collider.is_enabled() || !transform.is_enabled() {
-                continue;
-            }
-            
-            if !collider.blocks_movement() {
-                continue;
-            }
-
-            // Use proper shape-based collision detection
-            if player_shape.overlaps_with(&player_transform, &collider.shape, transform) {
-                return true;
-            }
-        }
-
-        false
+        let controller = CharacterController { radius: player_radius, ..CharacterController::default() };
+        world.resource::<CollisionWorld>()
+            .is_some_and(|collision| controller.is_blocked_at(collision, player_position))
     }
 
     /// Legacy grid-based collision check for backward compatibility
@@ -134,17 +148,31 @@ impl Component for Collider {
     }
 }
 
+reflect_component!(Collider {
+    shape: ColliderShape,
+    is_static: bool,
+    is_trigger: bool,
+    material: ColliderMaterial,
+    layer: u32,
+    mask: u32,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<Collider>("Collider")
+}
+
 impl ColliderShape {
     /// Check if a point is inside this collider shape at the given transform
+    ///
+    /// Boxes and meshes follow the transform's rotation; spheres and capsules
+    /// stay upright.
     pub fn contains_point(&self, point: Vec3, transform: &Transform) -> bool {
         match self {
+            ColliderShape::TriMesh { mesh } => mesh.contains(to_local(transform, point)),
+            ColliderShape::ConvexHull { hull } => hull.contains(to_local(transform, point)),
             ColliderShape::Box { size } => {
-                let half_size = *size * 0.5;
-                let local_point = point - transform.position;
-                
-                local_point.x.abs() <= half_size.x &&
-                local_point.y.abs() <= half_size.y &&
-                local_point.z.abs() <= half_size.z
+                OrientedBox::new(*size, transform).contains(point)
             },
             ColliderShape::Sphere { radius } => {
                 let distance = (point - transform.position).length();
@@ -172,79 +200,202 @@ impl ColliderShape {
     }
 
     /// Check if this collider overlaps with another collider
+    ///
+    /// Boxes and meshes are oriented by their transform's rotation. Spheres
+    /// and capsules ignore rotation, so capsules are always upright.
     pub fn overlaps_with(&self, self_transform: &Transform, other: &ColliderShape, other_transform: &Transform) -> bool {
         match (self, other) {
+            (ColliderShape::TriMesh { mesh }, _) => mesh_overlaps(mesh, self_transform, other, other_transform),
+            (_, ColliderShape::TriMesh { mesh }) => mesh_overlaps(mesh, other_transform, self, self_transform),
+            (ColliderShape::ConvexHull { hull }, _) => hull_overlaps(hull, self_transform, other, other_transform),
+            (_, ColliderShape::ConvexHull { hull }) => hull_overlaps(hull, other_transform, self, self_transform),
             (ColliderShape::Box { size: size1 }, ColliderShape::Box { size: size2 }) => {
-                let half_size1 = *size1 * 0.5;
-                let half_size2 = *size2 * 0.5;
-                
-                let pos1 = self_transform.position;
-                let pos2 = other_transform.position;
-                
-                (pos1.x - half_size1.x <= pos2.x + half_size2.x) &&
-                (pos1.x + half_size1.x >= pos2.x - half_size2.x) &&
-                (pos1.y - half_size1.y <= pos2.y + half_size2.y) &&
-                (pos1.y + half_size1.y >= pos2.y - half_size2.y) &&
-                (pos1.z - half_size1.z <= pos2.z + half_size2.z) &&
-                (pos1.z + half_size1.z >= pos2.z - half_size2.z)
+                OrientedBox::new(*size1, self_transform).overlaps(&OrientedBox::new(*size2, other_transform))
             },
             // Capsule vs Box collision (most common case for player vs walls)
-            (ColliderShape::Capsule { height, radius }, ColliderShape::Box { size }) |
+            (ColliderShape::Capsule { height, radius }, ColliderShape::Box { size }) => {
+                capsule_overlaps_box(self_transform.position, *height, *radius, &OrientedBox::new(*size, other_transform))
+            },
             (ColliderShape::Box { size }, ColliderShape::Capsule { height, radius }) => {
-                let (capsule_pos, box_pos, capsule_height, capsule_radius, box_size) = 
-                    if matches!(self, ColliderShape::Capsule { .. }) {
-                        (self_transform.position, other_transform.position, *height, *radius, *size)
-                    } else {
-                        (other_transform.position, self_transform.position, *height, *radius, *size)
-                    };
-
-                let box_half_size = box_size * 0.5;
-                
-                // Find the closest point on the box to the capsule center
-                let closest_x = (capsule_pos.x).clamp(box_pos.x - box_half_size.x, box_pos.x + box_half_size.x);
-                let closest_y = (capsule_pos.y).clamp(box_pos.y - box_half_size.y, box_pos.y + box_half_size.y);
-                let closest_z = (capsule_pos.z).clamp(box_pos.z - box_half_size.z, box_pos.z + box_half_size.z);
-                
-                let closest_point = Vec3::new(closest_x, closest_y, closest_z);
-                
-                // Check if the closest point is within the capsule's radius
-                let half_height = capsule_height * 0.5;
-                let capsule_center = capsule_pos;
-                
-                // Distance from capsule center to closest point on box
-                let diff = closest_point - capsule_center;
-                
-                // For capsule collision, we need to check against the cylindrical body and hemispheres
-                if diff.y.abs() <= half_height {
-                    // Point is within the cylindrical part of the capsule
-                    let horizontal_distance = (diff.x * diff.x + diff.z * diff.z).sqrt();
-                    horizontal_distance <= capsule_radius
-                } else {
-                    // Point is above or below the cylinder, check against hemisphere caps
-                    let cap_center_y = if diff.y > 0.0 { half_height } else { -half_height };
-                    let cap_center = Vec3::new(0.0, cap_center_y, 0.0);
-                    let distance_to_cap = (diff - cap_center).length();
-                    distance_to_cap <= capsule_radius
-                }
+                capsule_overlaps_box(other_transform.position, *height, *radius, &OrientedBox::new(*size, self_transform))
+            },
+            (ColliderShape::Sphere { radius: radius1 }, ColliderShape::Sphere { radius: radius2 }) => {
+                self_transform.position.distance(other_transform.position) <= radius1 + radius2
+            },
+            (ColliderShape::Sphere { radius }, ColliderShape::Box { size }) => {
+                OrientedBox::new(*size, other_transform).distance_to_point(self_transform.position) <= *radius
+            },
+            (ColliderShape::Box { size }, ColliderShape::Sphere { radius }) => {
+                OrientedBox::new(*size, self_transform).distance_to_point(other_transform.position) <= *radius
             },
-            // For other shape combinations, fall back to simple approximation
-            _ => {
-                // Simple approximation: test if center of one shape is inside the other
-                self.contains_point(other_transform.position, self_transform) ||
-                other.contains_point(self_transform.position, other_transform)
+            // Spheres are capsules without a cylindrical part
+            (ColliderShape::Sphere { radius: radius1 }, ColliderShape::Capsule { height, radius: radius2 }) => {
+                capsules_overlap(self_transform.position, 0.0, *radius1, other_transform.position, *height, *radius2)
+            },
+            (ColliderShape::Capsule { height, radius: radius1 }, ColliderShape::Sphere { radius: radius2 }) => {
+                capsules_overlap(self_transform.position, *height, *radius1, other_transform.position, 0.0, *radius2)
+            },
+            (ColliderShape::Capsule { height: height1, radius: radius1 }, ColliderShape::Capsule { height: height2, radius: radius2 }) => {
+                capsules_overlap(self_transform.position, *height1, *radius1, other_transform.position, *height2, *radius2)
+            },
+        }
+    }
+
+    /// Find where this shape touches another, with the normals pointing out
+    /// of the other shape
+    ///
+    /// Spheres and capsules touch at their single deepest point. Boxes touch
+    /// wherever one of their corners is inside the other shape, so a box
+    /// lying on a floor gets a contact per corner, and meshes wherever one of
+    /// their vertices is.
+    pub fn contacts_with(&self, self_transform: &Transform, other: &ColliderShape, other_transform: &Transform) -> Vec<ContactPoint> {
+        let rounded_contact = |center: Vec3, radius: f32| {
+            let on_other = other.closest_point(other_transform, center);
+            let distance = center.distance(on_other);
+            if distance >= radius {
+                None
+            } else if distance > 1e-5 {
+                Some(ContactPoint { point: on_other, normal: (center - on_other) / distance, depth: radius - distance })
+            } else {
+                other.penetration(other_transform, center)
+                    .map(|(normal, depth)| ContactPoint { point: center, normal, depth: depth + radius })
+            }
+        };
+
+        let vertex_contacts = |vertices: Vec<Vec3>| {
+            vertices.into_iter()
+                .filter_map(|vertex| {
+                    other.penetration(other_transform, vertex)
+                        .map(|(normal, depth)| ContactPoint { point: vertex, normal, depth })
+                })
+                .collect()
+        };
+
+        match self {
+            ColliderShape::Sphere { radius } => rounded_contact(self_transform.position, *radius).into_iter().collect(),
+            ColliderShape::Capsule { height, radius } => {
+                let half_height = Vec3::new(0.0, height * 0.5, 0.0);
+                let position = self_transform.position;
+                let (on_core, _) = other.closest_to_segment(other_transform, position - half_height, position + half_height);
+                rounded_contact(on_core, *radius).into_iter().collect()
+            },
+            ColliderShape::Box { size } => vertex_contacts(OrientedBox::new(*size, self_transform).corners().to_vec()),
+            ColliderShape::TriMesh { mesh } => {
+                vertex_contacts(mesh.vertices().iter().map(|&vertex| to_world(self_transform, vertex)).collect())
+            },
+            ColliderShape::ConvexHull { hull } => {
+                vertex_contacts(hull.vertices().iter().map(|&vertex| to_world(self_transform, vertex)).collect())
             }
         }
     }
 
-    /// Get the bounding box of this collider shape
-    pub fn get_bounds(&self, transform: &Transform) -> (Vec3, Vec3) {
+    /// Get the point of this collider shape closest to `point`, which is
+    /// `point` itself when it lies inside
+    pub fn closest_point(&self, transform: &Transform, point: Vec3) -> Vec3 {
         match self {
-            ColliderShape::Box { size } => {
-                let half_size = *size * 0.5;
-                let min = transform.position - half_size;
-                let max = transform.position + half_size;
-                (min, max)
+            ColliderShape::TriMesh { mesh } => to_world(transform, mesh.closest_point(to_local(transform, point))),
+            ColliderShape::ConvexHull { hull } => to_world(transform, hull.closest_point(to_local(transform, point))),
+            ColliderShape::Box { size } => OrientedBox::new(*size, transform).closest_point(point),
+            ColliderShape::Sphere { radius } => {
+                let offset = point - transform.position;
+                transform.position + offset.clamp_length_max(*radius)
             },
+            ColliderShape::Capsule { height, radius } => {
+                let half_height = height * 0.5;
+                let core = Vec3::new(
+                    transform.position.x,
+                    point.y.clamp(transform.position.y - half_height, transform.position.y + half_height),
+                    transform.position.z,
+                );
+                core + (point - core).clamp_length_max(*radius)
+            }
+        }
+    }
+
+    /// Find the shortest way out of this shape for a point inside it, as the
+    /// direction to push the point and how far
+    pub fn penetration(&self, transform: &Transform, point: Vec3) -> Option<(Vec3, f32)> {
+        let (offset, radius) = match self {
+            ColliderShape::Box { size } => return OrientedBox::new(*size, transform).penetration(point),
+            ColliderShape::TriMesh { mesh } => {
+                return mesh.penetration(to_local(transform, point))
+                    .map(|(normal, depth)| (transform.orientation() * normal, depth));
+            },
+            ColliderShape::ConvexHull { hull } => {
+                return hull.penetration(to_local(transform, point))
+                    .map(|(normal, depth)| (transform.orientation() * normal, depth));
+            },
+            ColliderShape::Sphere { radius } => (point - transform.position, *radius),
+            ColliderShape::Capsule { height, radius } => {
+                let half_height = height * 0.5;
+                let core_y = point.y.clamp(transform.position.y - half_height, transform.position.y + half_height);
+                (point - Vec3::new(transform.position.x, core_y, transform.position.z), *radius)
+            }
+        };
+        let distance = offset.length();
+        (distance < radius).then(|| (offset.try_normalize().unwrap_or(Vec3::Y), radius - distance))
+    }
+
+    /// Volume enclosed by this shape, ignoring scale
+    pub fn volume(&self) -> f32 {
+        let sphere = |radius: f32| 4.0 / 3.0 * std::f32::consts::PI * radius.powi(3);
+        match self {
+            ColliderShape::Box { size } => size.x * size.y * size.z,
+            ColliderShape::Sphere { radius } => sphere(*radius),
+            ColliderShape::Capsule { height, radius } => std::f32::consts::PI * radius * radius * height + sphere(*radius),
+            ColliderShape::TriMesh { mesh } => mesh.volume(),
+            ColliderShape::ConvexHull { hull } => hull.volume(),
+        }
+    }
+
+    /// Find the closest pair of points between a line segment and this shape,
+    /// returned as `(on_segment, on_shape)`
+    pub fn closest_to_segment(&self, transform: &Transform, start: Vec3, end: Vec3) -> (Vec3, Vec3) {
+        if let ColliderShape::TriMesh { mesh } = self {
+            // Meshes aren't convex, so search each nearby triangle instead
+            let (on_segment, on_mesh) = mesh.closest_to_segment(to_local(transform, start), to_local(transform, end));
+            return (to_world(transform, on_segment), to_world(transform, on_mesh));
+        }
+        let on_segment = closest_along_segment(start, end, |point| self.closest_point(transform, point).distance(point));
+        (on_segment, self.closest_point(transform, on_segment))
+    }
+
+    /// Intersect a ray with this shape, returning the distance along the ray
+    /// and the surface normal where it enters
+    ///
+    /// `direction` must be normalized. Rays starting inside the shape don't
+    /// hit it.
+    pub fn raycast(&self, transform: &Transform, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<(f32, Vec3)> {
+        let (distance, normal) = match self {
+            ColliderShape::Box { size } => OrientedBox::new(*size, transform).raycast(origin, direction)?,
+            ColliderShape::Sphere { radius } => {
+                let distance = ray_sphere(origin, direction, transform.position, *radius)?;
+                (distance, (origin + direction * distance - transform.position).normalize_or_zero())
+            },
+            ColliderShape::Capsule { height, radius } => {
+                ray_capsule(origin, direction, transform.position, height * 0.5, *radius)?
+            },
+            ColliderShape::TriMesh { mesh } => {
+                let local_direction = transform.orientation().inverse() * direction;
+                let (distance, normal) = mesh.raycast(to_local(transform, origin), local_direction, max_distance)?;
+                (distance, transform.orientation() * normal)
+            },
+            ColliderShape::ConvexHull { hull } => {
+                let local_direction = transform.orientation().inverse() * direction;
+                let (distance, normal) = hull.raycast(to_local(transform, origin), local_direction)?;
+                (distance, transform.orientation() * normal)
+            }
+        };
+        (distance <= max_distance).then_some((distance, normal))
+    }
+
+    /// Get the axis-aligned bounding box of this collider shape, enclosing
+    /// rotated boxes and meshes
+    pub fn get_bounds(&self, transform: &Transform) -> (Vec3, Vec3) {
+        match self {
+            ColliderShape::TriMesh { mesh } => OrientedBox::around(&mesh.bounds(), transform).bounds(),
+            ColliderShape::ConvexHull { hull } => OrientedBox::around(&hull.bounds(), transform).bounds(),
+            ColliderShape::Box { size } => OrientedBox::new(*size, transform).bounds(),
             ColliderShape::Sphere { radius } => {
                 let r = Vec3::new(*radius, *radius, *radius);
                 let min = transform.position - r;
@@ -260,6 +411,344 @@ impl ColliderShape {
             }
         }
     }
+
+    /// Furthest point of the shape in `direction`, for convex overlap tests
+    ///
+    /// Triangle meshes answer for their convex hull.
+    fn support(&self, transform: &Transform, direction: Vec3) -> Vec3 {
+        match self {
+            ColliderShape::Box { size } => OrientedBox::new(*size, transform).support(direction),
+            ColliderShape::Sphere { radius } => transform.position + direction.normalize_or_zero() * *radius,
+            ColliderShape::Capsule { height, radius } => {
+                let cap = Vec3::new(0.0, height * 0.5 * direction.y.signum(), 0.0);
+                transform.position + cap + direction.normalize_or_zero() * *radius
+            },
+            ColliderShape::TriMesh { mesh } => {
+                let local_direction = transform.orientation().inverse() * direction;
+                let furthest = mesh.vertices().iter().copied()
+                    .max_by(|a, b| a.dot(local_direction).total_cmp(&b.dot(local_direction)))
+                    .unwrap_or(Vec3::ZERO);
+                to_world(transform, furthest)
+            },
+            ColliderShape::ConvexHull { hull } => {
+                to_world(transform, hull.support(transform.orientation().inverse() * direction))
+            }
+        }
+    }
+}
+
+/// Express a world-space point in a transform's rotated frame
+fn to_local(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.orientation().inverse() * (point - transform.position)
+}
+
+/// Place a point from a transform's rotated frame in world space
+fn to_world(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.position + transform.orientation() * point
+}
+
+/// Bounds of `other`, as seen from a shape placed by `transform`
+fn local_bounds(transform: &Transform, other: &ColliderShape, other_transform: &Transform) -> Aabb {
+    let (min, max) = other.get_bounds(other_transform);
+    let world = OrientedBox { center: (min + max) * 0.5, axes: [Vec3::X, Vec3::Y, Vec3::Z], half_size: (max - min) * 0.5 };
+    Aabb::from_points(world.corners().map(|corner| to_local(transform, corner)))
+}
+
+/// Check if a triangle mesh overlaps another shape, testing only the
+/// triangles near it
+///
+/// Shapes entirely behind the mesh's surface overlap it too.
+fn mesh_overlaps(mesh: &TriMesh, transform: &Transform, other: &ColliderShape, other_transform: &Transform) -> bool {
+    if mesh.contains(to_local(transform, other_transform.position)) {
+        return true;
+    }
+    let bounds = local_bounds(transform, other, other_transform);
+    let to_world_triangle = |triangle: [Vec3; 3]| triangle.map(|corner| to_world(transform, corner));
+    let triangle_support = |triangle: [Vec3; 3]| move |direction: Vec3| {
+        triangle.into_iter().max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction))).unwrap_or(Vec3::ZERO)
+    };
+
+    match other {
+        ColliderShape::Sphere { radius } => mesh.any_triangle(&bounds, |triangle| {
+            let center = other_transform.position;
+            closest_on_triangle(center, to_world_triangle(triangle)).distance(center) <= *radius
+        }),
+        ColliderShape::Capsule { height, radius } => mesh.any_triangle(&bounds, |triangle| {
+            let triangle = to_world_triangle(triangle);
+            let half_height = Vec3::new(0.0, height * 0.5, 0.0);
+            let distance = |point: Vec3| closest_on_triangle(point, triangle).distance(point);
+            let on_core = closest_along_segment(other_transform.position - half_height, other_transform.position + half_height, distance);
+            distance(on_core) <= *radius
+        }),
+        ColliderShape::TriMesh { mesh: other_mesh } => mesh.any_triangle(&bounds, |triangle| {
+            let triangle = to_world_triangle(triangle);
+            let near = Aabb::from_points(triangle.map(|corner| to_local(other_transform, corner)));
+            other_mesh.any_triangle(&near, |other_triangle| {
+                let other_triangle = other_triangle.map(|corner| to_world(other_transform, corner));
+                convex_overlap(triangle_support(triangle), triangle_support(other_triangle))
+            })
+        }),
+        ColliderShape::Box { .. } | ColliderShape::ConvexHull { .. } => mesh.any_triangle(&bounds, |triangle| {
+            convex_overlap(triangle_support(to_world_triangle(triangle)), |direction| other.support(other_transform, direction))
+        }),
+    }
+}
+
+/// Check if a convex hull overlaps a shape other than a triangle mesh
+fn hull_overlaps(hull: &ConvexHull, transform: &Transform, other: &ColliderShape, other_transform: &Transform) -> bool {
+    match other {
+        ColliderShape::Sphere { radius } => {
+            let center = to_local(transform, other_transform.position);
+            hull.closest_point(center).distance(center) <= *radius
+        },
+        ColliderShape::Capsule { height, radius } => {
+            let half_height = Vec3::new(0.0, height * 0.5, 0.0);
+            let start = to_local(transform, other_transform.position - half_height);
+            let end = to_local(transform, other_transform.position + half_height);
+            let distance = |point: Vec3| hull.closest_point(point).distance(point);
+            distance(closest_along_segment(start, end, distance)) <= *radius
+        },
+        _ => convex_overlap(
+            |direction| to_world(transform, hull.support(transform.orientation().inverse() * direction)),
+            |direction| other.support(other_transform, direction),
+        ),
+    }
+}
+
+/// A box collider placed in the world with its transform's rotation
+struct OrientedBox {
+    center: Vec3,
+    axes: [Vec3; 3],   // Local x, y and z axes in world space
+    half_size: Vec3,
+}
+
+impl OrientedBox {
+    fn new(size: Vec3, transform: &Transform) -> Self {
+        let orientation = transform.orientation();
+        Self {
+            center: transform.position,
+            axes: [orientation * Vec3::X, orientation * Vec3::Y, orientation * Vec3::Z],
+            half_size: size * 0.5,
+        }
+    }
+
+    /// A box fitting local-space bounds of a shape placed by `transform`
+    fn around(bounds: &Aabb, transform: &Transform) -> Self {
+        let orientation = transform.orientation();
+        Self {
+            center: to_world(transform, bounds.center()),
+            axes: [orientation * Vec3::X, orientation * Vec3::Y, orientation * Vec3::Z],
+            half_size: bounds.size() * 0.5,
+        }
+    }
+
+    /// Corner of the box furthest in `direction`
+    fn support(&self, direction: Vec3) -> Vec3 {
+        (0..3).fold(self.center, |point, axis| {
+            point + self.axes[axis] * self.half_size[axis] * self.axes[axis].dot(direction).signum()
+        })
+    }
+
+    /// Half the length of the box's shadow on an axis
+    fn projected_radius(&self, axis: Vec3) -> f32 {
+        self.half_size.x * self.axes[0].dot(axis).abs() +
+        self.half_size.y * self.axes[1].dot(axis).abs() +
+        self.half_size.z * self.axes[2].dot(axis).abs()
+    }
+
+    /// World-space bounding box
+    fn bounds(&self) -> (Vec3, Vec3) {
+        let extent = Vec3::new(
+            self.projected_radius(Vec3::X),
+            self.projected_radius(Vec3::Y),
+            self.projected_radius(Vec3::Z),
+        );
+        (self.center - extent, self.center + extent)
+    }
+
+    /// Express a world-space point in the box's local axes
+    fn to_local(&self, point: Vec3) -> Vec3 {
+        let offset = point - self.center;
+        Vec3::new(offset.dot(self.axes[0]), offset.dot(self.axes[1]), offset.dot(self.axes[2]))
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.to_local(point).abs().cmple(self.half_size).all()
+    }
+
+    /// Closest point of the box to a world-space point
+    fn closest_point(&self, point: Vec3) -> Vec3 {
+        let local = self.to_local(point).clamp(-self.half_size, self.half_size);
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    /// Push direction and depth for a point inside the box, out through the
+    /// nearest face
+    fn penetration(&self, point: Vec3) -> Option<(Vec3, f32)> {
+        let local = self.to_local(point);
+        let gaps = self.half_size - local.abs();
+        if gaps.min_element() < 0.0 {
+            return None;
+        }
+        let axis = if gaps.x <= gaps.y && gaps.x <= gaps.z { 0 } else if gaps.y <= gaps.z { 1 } else { 2 };
+        let side = if local[axis] < 0.0 { -1.0 } else { 1.0 };
+        Some((self.axes[axis] * side, gaps[axis]))
+    }
+
+    /// World-space corners of the box
+    fn corners(&self) -> [Vec3; 8] {
+        std::array::from_fn(|index| {
+            let sign = |bit: usize| if index & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            self.center +
+                self.axes[0] * self.half_size.x * sign(0) +
+                self.axes[1] * self.half_size.y * sign(1) +
+                self.axes[2] * self.half_size.z * sign(2)
+        })
+    }
+
+    /// Distance from a point to the box, zero inside it
+    fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// Distance from a line segment to the box
+    fn distance_to_segment(&self, start: Vec3, end: Vec3) -> f32 {
+        self.distance_to_point(closest_along_segment(start, end, |point| self.distance_to_point(point)))
+    }
+
+    /// Slab test of a ray against the box, returning the entry distance and
+    /// the normal of the face it enters through
+    fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(f32, Vec3)> {
+        let local_origin = self.to_local(origin);
+        let mut enter = f32::NEG_INFINITY;
+        let mut exit = f32::INFINITY;
+        let mut normal = Vec3::ZERO;
+        for axis in 0..3 {
+            let start = local_origin[axis];
+            let step = direction.dot(self.axes[axis]);
+            let half = self.half_size[axis];
+            if step.abs() <= f32::EPSILON {
+                if start.abs() > half {
+                    return None;
+                }
+                continue;
+            }
+
+            let near = (-half.copysign(step) - start) / step;
+            let far = (half.copysign(step) - start) / step;
+            if near > enter {
+                enter = near;
+                normal = -self.axes[axis] * step.signum();
+            }
+            exit = exit.min(far);
+        }
+        (enter >= 0.0 && enter <= exit).then_some((enter, normal))
+    }
+
+    /// Separating axis test against another box
+    fn overlaps(&self, other: &OrientedBox) -> bool {
+        let offset = other.center - self.center;
+        let mut axes = self.axes.to_vec();
+        axes.extend(other.axes);
+        for axis in self.axes {
+            for other_axis in other.axes {
+                // Parallel edges give no new axis
+                let cross = axis.cross(other_axis);
+                if cross.length_squared() > 1e-6 {
+                    axes.push(cross.normalize());
+                }
+            }
+        }
+        
+        axes.into_iter().all(|axis| {
+            offset.dot(axis).abs() <= self.projected_radius(axis) + other.projected_radius(axis)
+        })
+    }
+}
+
+/// Find the point of a line segment where `distance` is smallest
+///
+/// The distance to a convex shape is convex along the segment, so a ternary
+/// search converges on its minimum.
+pub(super) fn closest_along_segment(start: Vec3, end: Vec3, distance: impl Fn(Vec3) -> f32) -> Vec3 {
+    let distance_at = |t: f32| distance(start.lerp(end, t));
+    let (mut low, mut high) = (0.0_f32, 1.0_f32);
+    for _ in 0..32 {
+        let third = (high - low) / 3.0;
+        if distance_at(low + third) > distance_at(high - third) {
+            low += third;
+        } else {
+            high -= third;
+        }
+    }
+    start.lerp(end, (low + high) * 0.5)
+}
+
+/// Distance along a ray to where it enters a sphere, if the ray starts
+/// outside and hits it
+fn ray_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let offset = origin - center;
+    let outside = offset.length_squared() - radius * radius;
+    let along = offset.dot(direction);
+    if outside < 0.0 || along > 0.0 {
+        return None;
+    }
+    let discriminant = along * along - outside;
+    (discriminant >= 0.0).then(|| -along - discriminant.sqrt())
+}
+
+/// Distance along a ray and normal where it enters an upright capsule
+///
+/// The ray starts outside, so whichever of the side or the two end caps it
+/// enters first is where it enters the capsule.
+fn ray_capsule(origin: Vec3, direction: Vec3, center: Vec3, half_height: f32, radius: f32) -> Option<(f32, Vec3)> {
+    let offset = origin - center;
+    let core_offset = Vec3::new(0.0, offset.y.clamp(-half_height, half_height), 0.0);
+    if offset.distance_squared(core_offset) < radius * radius {
+        return None;
+    }
+
+    let mut best: Option<(f32, Vec3)> = None;
+    let flat_direction = Vec2::new(direction.x, direction.z);
+    let flat_offset = Vec2::new(offset.x, offset.z);
+    let a = flat_direction.length_squared();
+    if a > f32::EPSILON {
+        let b = flat_offset.dot(flat_direction);
+        let discriminant = b * b - a * (flat_offset.length_squared() - radius * radius);
+        if discriminant >= 0.0 {
+            let distance = (-b - discriminant.sqrt()) / a;
+            let height = offset.y + direction.y * distance;
+            if distance >= 0.0 && height.abs() <= half_height {
+                let side = flat_offset + flat_direction * distance;
+                best = Some((distance, Vec3::new(side.x, 0.0, side.y).normalize_or_zero()));
+            }
+        }
+    }
+    for cap in [half_height, -half_height] {
+        let cap_center = center + Vec3::new(0.0, cap, 0.0);
+        if let Some(distance) = ray_sphere(origin, direction, cap_center, radius) {
+            if best.is_none_or(|(best_distance, _)| distance < best_distance) {
+                best = Some((distance, (origin + direction * distance - cap_center).normalize_or_zero()));
+            }
+        }
+    }
+    best
+}
+
+/// Check if an upright capsule overlaps a box
+fn capsule_overlaps_box(position: Vec3, height: f32, radius: f32, oriented_box: &OrientedBox) -> bool {
+    let half_height = Vec3::new(0.0, height * 0.5, 0.0);
+    oriented_box.distance_to_segment(position - half_height, position + half_height) <= radius
+}
+
+/// Check if two upright capsules overlap
+///
+/// The distance between their vertical core segments is the horizontal
+/// distance combined with whatever vertical gap is left between the segments.
+fn capsules_overlap(position1: Vec3, height1: f32, radius1: f32, position2: Vec3, height2: f32, radius2: f32) -> bool {
+    let horizontal = Vec2::new(position1.x - position2.x, position1.z - position2.z).length();
+    let vertical_gap = ((position1.y - position2.y).abs() - (height1 + height2) * 0.5).max(0.0);
+    Vec2::new(horizontal, vertical_gap).length() <= radius1 + radius2
 }
 
 impl ColliderMaterial {
@@ -0,0 +1,701 @@
+//! Triangle mesh and convex hull collision shapes built from mesh geometry
+//!
+//! Both shapes keep their geometry in the collider's local space, already
+//! scaled, and are placed in the world by the collider's transform including
+//! its rotation. Queries here work in local space; `ColliderShape` moves
+//! points in and out of it.
+
+use std::cell::Cell;
+use std::collections::HashSet;
+use macroquad::prelude::*;
+use super::collision::closest_along_segment;
+
+const BVH_LEAF_SIZE: usize = 4;
+
+/// Axis-aligned bounding box in a shape's local space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    fn empty() -> Self {
+        Self { min: Vec3::splat(f32::MAX), max: Vec3::splat(f32::MIN) }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        points.into_iter().fold(Self::empty(), |bounds, point| Self {
+            min: bounds.min.min(point),
+            max: bounds.max.max(point),
+        })
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        (self.max - self.min).max(Vec3::ZERO)
+    }
+
+    fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    /// Lower bound on the distance between anything in this box and anything in `other`
+    fn gap_to(&self, other: &Aabb) -> f32 {
+        (self.min - other.max).max(other.min - self.max).max(Vec3::ZERO).length()
+    }
+
+    /// Distance along a ray to where it enters the box, zero if it starts inside
+    fn ray_entry(&self, origin: Vec3, inverse_direction: Vec3, max_distance: f32) -> Option<f32> {
+        let near = (self.min - origin) * inverse_direction;
+        let far = (self.max - origin) * inverse_direction;
+        let enter = near.min(far).max_element().max(0.0);
+        let exit = near.max(far).min_element().min(max_distance);
+        (enter <= exit).then_some(enter)
+    }
+}
+
+/// Bounding volume hierarchy over a mesh's triangles
+///
+/// Nodes are stored flat with both children of a branch next to each other,
+/// and leaves refer to a run of `order`, the triangles sorted by node.
+#[derive(Debug, Clone)]
+struct Bvh {
+    nodes: Vec<BvhNode>,
+    order: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    bounds: Aabb,
+    first: u32,   // First child for branches, first entry of `order` for leaves
+    count: u32,   // Triangles in a leaf, zero for branches
+}
+
+impl Bvh {
+    /// Build the tree by splitting triangles at the median of their centres
+    /// along the longest axis
+    fn build(triangles: &[[Vec3; 3]]) -> Self {
+        let bounds: Vec<Aabb> = triangles.iter().map(|triangle| Aabb::from_points(*triangle)).collect();
+        let centers: Vec<Vec3> = bounds.iter().map(Aabb::center).collect();
+        let mut order: Vec<u32> = (0..triangles.len() as u32).collect();
+        let mut nodes = vec![BvhNode { bounds: Aabb::empty(), first: 0, count: 0 }];
+
+        let mut pending = vec![(0, 0, order.len())];
+        while let Some((node, start, end)) = pending.pop() {
+            let node_bounds = order[start..end].iter().fold(Aabb::empty(), |merged, &index| {
+                let triangle = bounds[index as usize];
+                Aabb { min: merged.min.min(triangle.min), max: merged.max.max(triangle.max) }
+            });
+            if end - start <= BVH_LEAF_SIZE {
+                nodes[node] = BvhNode { bounds: node_bounds, first: start as u32, count: (end - start) as u32 };
+                continue;
+            }
+
+            let spread = Aabb::from_points(order[start..end].iter().map(|&index| centers[index as usize])).size();
+            let axis = if spread.x >= spread.y && spread.x >= spread.z { 0 } else if spread.y >= spread.z { 1 } else { 2 };
+            let middle = (start + end) / 2;
+            order[start..end].select_nth_unstable_by(middle - start, |&a, &b| {
+                centers[a as usize][axis].total_cmp(&centers[b as usize][axis])
+            });
+
+            let children = nodes.len();
+            nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count: 0 });
+            nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count: 0 });
+            nodes[node] = BvhNode { bounds: node_bounds, first: children as u32, count: 0 };
+            pending.push((children, start, middle));
+            pending.push((children + 1, middle, end));
+        }
+        Self { nodes, order }
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.nodes[0].bounds
+    }
+
+    /// Call `visit` for every triangle in a leaf whose node bounds, and those
+    /// of all its parents, pass `enter`
+    fn traverse(&self, enter: impl Fn(&Aabb) -> bool, mut visit: impl FnMut(usize)) {
+        if self.order.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.first as usize);
+                stack.push(node.first as usize + 1);
+            } else {
+                let first = node.first as usize;
+                for &triangle in &self.order[first..first + node.count as usize] {
+                    visit(triangle as usize);
+                }
+            }
+        }
+    }
+
+    /// Find the triangle with the smallest `distance`, skipping nodes whose
+    /// `lower_bound` is already further than the best found
+    fn nearest(&self, lower_bound: impl Fn(&Aabb) -> f32, distance: impl Fn(usize) -> f32) -> Option<(usize, f32)> {
+        let best: Cell<Option<(usize, f32)>> = Cell::new(None);
+        self.traverse(
+            |bounds| best.get().is_none_or(|(_, best_distance)| lower_bound(bounds) < best_distance),
+            |triangle| {
+                let triangle_distance = distance(triangle);
+                if best.get().is_none_or(|(_, best_distance)| triangle_distance < best_distance) {
+                    best.set(Some((triangle, triangle_distance)));
+                }
+            },
+        );
+        best.get()
+    }
+}
+
+/// A triangle soup collider for static level geometry
+///
+/// Triangles are one-sided: their normals, from the winding of their
+/// corners, face out of the solid. Points behind the nearest triangle count
+/// as inside, so closed meshes and open floors both push things out the
+/// front.
+#[derive(Debug, Clone)]
+pub struct TriMesh {
+    vertices: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    normals: Vec<Vec3>,
+    bvh: Bvh,
+}
+
+impl TriMesh {
+    /// Build a mesh from vertices and triangle indices, dropping triangles
+    /// with no area or out of range indices
+    pub fn new(vertices: Vec<Vec3>, triangles: Vec<[u32; 3]>) -> Self {
+        let triangles: Vec<[u32; 3]> = triangles.into_iter()
+            .filter(|triangle| triangle.iter().all(|&index| (index as usize) < vertices.len()))
+            .filter(|triangle| {
+                let [a, b, c] = triangle.map(|index| vertices[index as usize]);
+                (b - a).cross(c - a).try_normalize().is_some()
+            })
+            .collect();
+        let corners: Vec<[Vec3; 3]> = triangles.iter().map(|triangle| triangle.map(|index| vertices[index as usize])).collect();
+        let normals = corners.iter().map(|[a, b, c]| (*b - *a).cross(*c - *a).normalize()).collect();
+        let bvh = Bvh::build(&corners);
+        Self { vertices, triangles, normals, bvh }
+    }
+
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.bvh.bounds()
+    }
+
+    fn triangle(&self, index: usize) -> [Vec3; 3] {
+        self.triangles[index].map(|vertex| self.vertices[vertex as usize])
+    }
+
+    /// Closest point on the mesh surface and the triangle it lies on
+    fn closest_on_surface(&self, point: Vec3) -> Option<(Vec3, usize)> {
+        let point_bounds = Aabb { min: point, max: point };
+        let (triangle, _) = self.bvh.nearest(
+            |bounds| bounds.gap_to(&point_bounds),
+            |triangle| closest_on_triangle(point, self.triangle(triangle)).distance(point),
+        )?;
+        Some((closest_on_triangle(point, self.triangle(triangle)), triangle))
+    }
+
+    /// Check if a point lies behind the mesh surface
+    pub fn contains(&self, point: Vec3) -> bool {
+        self.penetration(point).is_some()
+    }
+
+    /// Closest point of the mesh to `point`, which is `point` itself when it
+    /// lies inside
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        match self.penetration(point) {
+            Some(_) => point,
+            None => self.closest_on_surface(point).map_or(point, |(closest, _)| closest),
+        }
+    }
+
+    /// Shortest way out of the mesh for a point behind its surface
+    ///
+    /// Where several triangles share the nearest point, as along an edge, the
+    /// one facing the point most directly decides which side it is on.
+    pub fn penetration(&self, point: Vec3) -> Option<(Vec3, f32)> {
+        let (closest, nearest) = self.closest_on_surface(point)?;
+        let distance = closest.distance(point);
+        let search = Aabb { min: point - Vec3::splat(distance + 1e-4), max: point + Vec3::splat(distance + 1e-4) };
+
+        let mut facing: Option<(f32, f32)> = None;  // (alignment, signed distance)
+        self.bvh.traverse(|bounds| bounds.overlaps(&search), |triangle| {
+            let on_triangle = closest_on_triangle(point, self.triangle(triangle));
+            if on_triangle.distance(point) > distance + 1e-5 {
+                return;
+            }
+            let signed = (point - on_triangle).dot(self.normals[triangle]);
+            let alignment = if distance > 1e-6 { signed.abs() / distance } else { 1.0 };
+            if facing.is_none_or(|(best, _)| alignment > best) {
+                facing = Some((alignment, signed));
+            }
+        });
+
+        let (_, signed) = facing?;
+        if signed >= 0.0 || distance <= 0.0 {
+            return None;
+        }
+        let normal = (closest - point).try_normalize().unwrap_or(self.normals[nearest]);
+        Some((normal, distance))
+    }
+
+    /// Closest pair of points between a line segment and the mesh surface, as
+    /// `(on_segment, on_mesh)`
+    pub fn closest_to_segment(&self, start: Vec3, end: Vec3) -> (Vec3, Vec3) {
+        let segment_bounds = Aabb::from_points([start, end]);
+        let pair = |triangle: usize| {
+            let corners = self.triangle(triangle);
+            let on_segment = closest_along_segment(start, end, |point| closest_on_triangle(point, corners).distance(point));
+            (on_segment, closest_on_triangle(on_segment, corners))
+        };
+        match self.bvh.nearest(|bounds| bounds.gap_to(&segment_bounds), |triangle| {
+            let (on_segment, on_mesh) = pair(triangle);
+            on_segment.distance(on_mesh)
+        }) {
+            Some((triangle, _)) => pair(triangle),
+            None => (start, start),
+        }
+    }
+
+    /// Distance along a ray and the normal of the first front face it hits
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<(f32, Vec3)> {
+        let inverse_direction = direction.recip();
+        let best: Cell<Option<(f32, usize)>> = Cell::new(None);
+        self.bvh.traverse(
+            |bounds| {
+                let limit = best.get().map_or(max_distance, |(distance, _)| distance);
+                bounds.ray_entry(origin, inverse_direction, limit).is_some()
+            },
+            |triangle| {
+                if let Some(distance) = ray_triangle(origin, direction, self.triangle(triangle)) {
+                    if distance <= max_distance && best.get().is_none_or(|(best_distance, _)| distance < best_distance) {
+                        best.set(Some((distance, triangle)));
+                    }
+                }
+            },
+        );
+        best.get().map(|(distance, triangle)| (distance, self.normals[triangle]))
+    }
+
+    /// Check if any triangle inside `bounds` passes `touches`
+    pub fn any_triangle(&self, bounds: &Aabb, touches: impl Fn([Vec3; 3]) -> bool) -> bool {
+        let found = Cell::new(false);
+        self.bvh.traverse(|node| !found.get() && node.overlaps(bounds), |triangle| {
+            if !found.get() && touches(self.triangle(triangle)) {
+                found.set(true);
+            }
+        });
+        found.get()
+    }
+
+    /// Volume enclosed by the mesh, meaningful for closed meshes only
+    pub fn volume(&self) -> f32 {
+        (0..self.triangles.len())
+            .map(|triangle| {
+                let [a, b, c] = self.triangle(triangle);
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum::<f32>()
+            .abs()
+    }
+}
+
+/// The smallest convex shape around a set of points
+///
+/// Cheaper and more robust to query than a `TriMesh`, and solid all the way
+/// through, at the cost of filling in any hollows.
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+    vertices: Vec<Vec3>,
+    faces: Vec<[u32; 3]>,       // Wound so their normals face outwards
+    planes: Vec<(Vec3, f32)>,   // Face normal and its distance from the origin
+    bounds: Aabb,
+}
+
+impl ConvexHull {
+    /// Build the hull of `points`, or `None` if they all lie in one plane
+    pub fn new(points: &[Vec3]) -> Option<Self> {
+        let bounds = Aabb::from_points(points.iter().copied());
+        let epsilon = bounds.size().max_element() * 1e-5;
+        let (vertices, faces) = quickhull(points, epsilon)?;
+        Some(Self::from_faces(vertices, faces))
+    }
+
+    /// Rebuild a hull from vertices and outward-wound faces that are already convex
+    pub fn from_faces(vertices: Vec<Vec3>, faces: Vec<[u32; 3]>) -> Self {
+        let planes = faces.iter()
+            .map(|face| {
+                let [a, b, c] = face.map(|index| vertices[index as usize]);
+                let normal = (b - a).cross(c - a).normalize_or_zero();
+                (normal, normal.dot(a))
+            })
+            .collect();
+        let bounds = Aabb::from_points(vertices.iter().copied());
+        Self { vertices, faces, planes, bounds }
+    }
+
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    pub fn faces(&self) -> &[[u32; 3]] {
+        &self.faces
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn face(&self, index: usize) -> [Vec3; 3] {
+        self.faces[index].map(|vertex| self.vertices[vertex as usize])
+    }
+
+    /// Furthest vertex in `direction`
+    pub fn support(&self, direction: Vec3) -> Vec3 {
+        self.vertices.iter().copied()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap_or(Vec3::ZERO)
+    }
+
+    /// Check if a point lies inside the hull or on its surface
+    pub fn contains(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|(normal, offset)| normal.dot(point) <= *offset)
+    }
+
+    /// Closest point of the hull to `point`, which is `point` itself when it
+    /// lies inside
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        if self.contains(point) {
+            return point;
+        }
+        (0..self.faces.len())
+            .map(|face| closest_on_triangle(point, self.face(face)))
+            .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+            .unwrap_or(point)
+    }
+
+    /// Shortest way out of the hull for a point inside it, through the nearest face
+    pub fn penetration(&self, point: Vec3) -> Option<(Vec3, f32)> {
+        self.planes.iter()
+            .map(|(normal, offset)| (*normal, offset - normal.dot(point)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, depth)| *depth > 0.0)
+    }
+
+    /// Distance along a ray and the normal where it enters the hull, clipping
+    /// the ray against each face plane
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(f32, Vec3)> {
+        let mut enter = f32::NEG_INFINITY;
+        let mut exit = f32::INFINITY;
+        let mut normal = Vec3::ZERO;
+        for (face_normal, offset) in &self.planes {
+            let height = face_normal.dot(origin) - offset;
+            let step = face_normal.dot(direction);
+            if step.abs() <= f32::EPSILON {
+                if height > 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let distance = -height / step;
+            if step < 0.0 {
+                if distance > enter {
+                    enter = distance;
+                    normal = *face_normal;
+                }
+            } else {
+                exit = exit.min(distance);
+            }
+        }
+        (enter >= 0.0 && enter <= exit).then_some((enter, normal))
+    }
+
+    /// Volume enclosed by the hull
+    pub fn volume(&self) -> f32 {
+        (0..self.faces.len())
+            .map(|face| {
+                let [a, b, c] = self.face(face);
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum::<f32>()
+            .abs()
+    }
+}
+
+/// Incremental 3D convex hull: start from a tetrahedron of extreme points and
+/// replace the faces each outside point can see with a fan to that point
+fn quickhull(points: &[Vec3], epsilon: f32) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
+    let furthest = |score: &dyn Fn(Vec3) -> f32| {
+        (0..points.len()).max_by(|&a, &b| score(points[a]).total_cmp(&score(points[b])))
+    };
+    let p0 = furthest(&|point| -point.x)?;
+    let p1 = furthest(&|point| point.distance_squared(points[p0]))?;
+    let axis = (points[p1] - points[p0]).try_normalize()?;
+    let p2 = furthest(&|point| (point - points[p0]).cross(axis).length_squared())?;
+    let plane_normal = (points[p1] - points[p0]).cross(points[p2] - points[p0]).try_normalize()?;
+    let p3 = furthest(&|point| (point - points[p0]).dot(plane_normal).abs())?;
+    if (points[p3] - points[p0]).dot(plane_normal).abs() <= epsilon {
+        return None;
+    }
+
+    let plane = |face: &[usize; 3]| {
+        let [a, b, c] = face.map(|index| points[index]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        (normal, normal.dot(a))
+    };
+    let centroid = (points[p0] + points[p1] + points[p2] + points[p3]) * 0.25;
+    let mut faces: Vec<[usize; 3]> = [[p0, p1, p2], [p0, p3, p1], [p1, p3, p2], [p2, p3, p0]]
+        .into_iter()
+        .map(|face| {
+            let (normal, offset) = plane(&face);
+            if normal.dot(centroid) > offset { [face[0], face[2], face[1]] } else { face }
+        })
+        .collect();
+
+    for (index, &point) in points.iter().enumerate() {
+        let visible: Vec<bool> = faces.iter()
+            .map(|face| {
+                let (normal, offset) = plane(face);
+                normal.dot(point) - offset > epsilon
+            })
+            .collect();
+        if !visible.contains(&true) {
+            continue;
+        }
+
+        let visible_edges: HashSet<(usize, usize)> = faces.iter().zip(&visible)
+            .filter(|(_, &seen)| seen)
+            .flat_map(|(face, _)| [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])])
+            .collect();
+        let horizon: Vec<(usize, usize)> = visible_edges.iter()
+            .copied()
+            .filter(|&(a, b)| !visible_edges.contains(&(b, a)))
+            .collect();
+
+        let mut kept: Vec<[usize; 3]> = faces.iter().zip(&visible)
+            .filter(|(_, &seen)| !seen)
+            .map(|(face, _)| *face)
+            .collect();
+        kept.extend(horizon.into_iter().map(|(a, b)| [a, b, index]));
+        faces = kept;
+    }
+
+    // Keep only the points the hull uses, renumbered
+    let mut remap = vec![u32::MAX; points.len()];
+    let mut vertices = Vec::new();
+    let faces = faces.into_iter()
+        .map(|face| face.map(|index| {
+            if remap[index] == u32::MAX {
+                remap[index] = vertices.len() as u32;
+                vertices.push(points[index]);
+            }
+            remap[index]
+        }))
+        .collect();
+    Some((vertices, faces))
+}
+
+/// Closest point on a triangle to `point`, from Ericson's Real-Time Collision Detection
+pub fn closest_on_triangle(point: Vec3, [a, b, c]: [Vec3; 3]) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
+}
+
+/// Distance along a ray to a triangle it hits from the front (Möller–Trumbore)
+fn ray_triangle(origin: Vec3, direction: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let ab = b - a;
+    let ac = c - a;
+    let p = direction.cross(ac);
+    let determinant = ab.dot(p);
+    if determinant <= f32::EPSILON {
+        return None;
+    }
+    let inverse = 1.0 / determinant;
+    let offset = origin - a;
+    let u = offset.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = offset.cross(ab);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = ac.dot(q) * inverse;
+    (distance >= 0.0).then_some(distance)
+}
+
+/// Check if two convex shapes overlap, given their support functions, with GJK
+///
+/// Shapes that only touch count as overlapping.
+pub fn convex_overlap(support_a: impl Fn(Vec3) -> Vec3, support_b: impl Fn(Vec3) -> Vec3) -> bool {
+    let support = |direction: Vec3| support_a(direction) - support_b(-direction);
+
+    let first = support(Vec3::X);
+    let mut simplex = vec![first];  // Newest point first
+    let mut direction = -first;
+    for _ in 0..64 {
+        if direction.length_squared() <= 1e-12 {
+            return true;
+        }
+        let point = support(direction);
+        if point.dot(direction) < 0.0 {
+            return false;
+        }
+        simplex.insert(0, point);
+        if enclose_origin(&mut simplex, &mut direction) {
+            return true;
+        }
+    }
+    // Only shapes touching exactly fail to converge
+    true
+}
+
+/// Reduce the GJK simplex to the part nearest the origin and point
+/// `direction` at the origin from it, returning true once it encloses the origin
+fn enclose_origin(simplex: &mut Vec<Vec3>, direction: &mut Vec3) -> bool {
+    let same_direction = |a: Vec3, b: Vec3| a.dot(b) > 0.0;
+    let line = |simplex: &mut Vec<Vec3>, direction: &mut Vec3| {
+        let (a, b) = (simplex[0], simplex[1]);
+        let ab = b - a;
+        if same_direction(ab, -a) {
+            *simplex = vec![a, b];
+            *direction = ab.cross(-a).cross(ab);
+            // The origin lies on the segment
+            direction.length_squared() <= 1e-12
+        } else {
+            *simplex = vec![a];
+            *direction = -a;
+            false
+        }
+    };
+    let triangle = |simplex: &mut Vec<Vec3>, direction: &mut Vec3| {
+        let (a, b, c) = (simplex[0], simplex[1], simplex[2]);
+        let (ab, ac, ao) = (b - a, c - a, -a);
+        let abc = ab.cross(ac);
+        if same_direction(abc.cross(ac), ao) {
+            if same_direction(ac, ao) {
+                *simplex = vec![a, c];
+                *direction = ac.cross(ao).cross(ac);
+                return direction.length_squared() <= 1e-12;
+            }
+            *simplex = vec![a, b];
+            return line(simplex, direction);
+        }
+        if same_direction(ab.cross(abc), ao) {
+            *simplex = vec![a, b];
+            return line(simplex, direction);
+        }
+        if same_direction(abc, ao) {
+            *direction = abc;
+        } else {
+            *simplex = vec![a, c, b];
+            *direction = -abc;
+        }
+        false
+    };
+
+    match simplex.len() {
+        2 => line(simplex, direction),
+        3 => triangle(simplex, direction),
+        _ => {
+            let (a, b, c, d) = (simplex[0], simplex[1], simplex[2], simplex[3]);
+            let (ab, ac, ad, ao) = (b - a, c - a, d - a, -a);
+            if same_direction(ab.cross(ac), ao) {
+                *simplex = vec![a, b, c];
+                return triangle(simplex, direction);
+            }
+            if same_direction(ac.cross(ad), ao) {
+                *simplex = vec![a, c, d];
+                return triangle(simplex, direction);
+            }
+            if same_direction(ad.cross(ab), ao) {
+                *simplex = vec![a, d, b];
+                return triangle(simplex, direction);
+            }
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::collision::fixtures::cube_hull;
+
+    #[test]
+    fn test_convex_hull_construction() {
+        let hull = cube_hull(2.0);
+        assert_eq!(hull.vertices().len(), 8);
+        assert_eq!(hull.faces().len(), 12);
+        assert!((hull.volume() - 8.0).abs() < 1e-4);
+
+        // Interior points don't end up on the hull
+        let mut points: Vec<Vec3> = hull.vertices().to_vec();
+        points.extend([Vec3::ZERO, Vec3::new(0.5, -0.2, 0.9), Vec3::new(-0.99, 0.99, 0.0)]);
+        let rebuilt = ConvexHull::new(&points).unwrap();
+        assert_eq!(rebuilt.vertices().len(), 8);
+        assert!(points.iter().all(|&point| rebuilt.contains(point)));
+        assert!(!rebuilt.contains(Vec3::new(1.1, 0.0, 0.0)));
+
+        // Flat point sets have no hull
+        assert!(ConvexHull::new(&[Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::ONE.with_y(0.0)]).is_none());
+    }
+}
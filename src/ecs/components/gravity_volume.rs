@@ -0,0 +1,99 @@
+//! Gravity volume component for zero-g zones and local gravity
+
+use macroquad::prelude::*;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
+
+/// The gravity a `GravityVolume` sets inside it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GravityOverride {
+    /// This acceleration, whatever the world gravity is
+    Vector(Vec3),
+    /// The world gravity multiplied by this, 0 for zero-g
+    Scale(f32),
+}
+
+/// Replaces the world `Gravity` inside this entity's `Collider`
+///
+/// Usually paired with a trigger collider. Where volumes overlap the one with
+/// the highest `priority` wins.
+#[derive(Debug, Clone)]
+pub struct GravityVolume {
+    pub gravity: GravityOverride,
+    pub priority: u32,
+    pub enabled: bool,
+}
+
+impl GravityVolume {
+    pub fn new(gravity: GravityOverride) -> Self {
+        Self {
+            gravity,
+            priority: 0,
+            enabled: true,
+        }
+    }
+
+    /// A volume with no gravity at all
+    // Levels build volumes from their config; only the tests use this and `with_enabled`
+    #[allow(dead_code)]
+    pub fn zero_g() -> Self {
+        Self::new(GravityOverride::Vector(Vec3::ZERO))
+    }
+
+    /// A volume where gravity is `scale` times the world gravity
+    pub fn scaled(scale: f32) -> Self {
+        Self::new(GravityOverride::Scale(scale))
+    }
+
+    /// A volume pulling with `gravity` in its own direction
+    pub fn directional(gravity: Vec3) -> Self {
+        Self::new(GravityOverride::Vector(gravity))
+    }
+
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Check if this component is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Gravity inside the volume when the world gravity is `world_gravity`
+    pub fn gravity(&self, world_gravity: Vec3) -> Vec3 {
+        match self.gravity {
+            GravityOverride::Vector(gravity) => gravity,
+            GravityOverride::Scale(scale) => world_gravity * scale,
+        }
+    }
+}
+
+impl Component for GravityVolume {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+reflect_component!(GravityVolume {
+    priority: u32,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<GravityVolume>("GravityVolume")
+}
@@ -0,0 +1,114 @@
+//! Parent/child hierarchy components and world-space transforms
+
+use std::ops::Deref;
+use macroquad::prelude::*;
+use crate::ecs::{Component, Entity};
+use super::Transform;
+
+/// The entity this entity is attached to
+///
+/// The entity's `Transform` is then relative to the parent. Use
+/// `World::set_parent` rather than adding this directly so the parent's
+/// `Children` stay in sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parent(pub Entity);
+
+impl Component for Parent {}
+
+/// Entities attached to this entity, maintained by `World::set_parent`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Children(pub Vec<Entity>);
+
+impl Component for Children {}
+
+/// World-space transform computed by `World::propagate_transforms`
+///
+/// Equal to the entity's `Transform` for root entities, and the parent's
+/// global transform combined with the local `Transform` for children.
+/// Renderers, colliders and lights read this instead of `Transform`.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalTransform(Transform);
+
+impl GlobalTransform {
+    /// Build a global transform from an affine matrix
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, position) = matrix.to_scale_rotation_translation();
+        let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+        Self(Transform::new(position)
+            .with_rotation(Vec3::new(x, y, z))
+            .with_scale(scale))
+    }
+
+    /// Combine with a child's local transform
+    pub fn mul_transform(&self, local: &Transform) -> Self {
+        let mut global = Self::from_matrix(self.0.matrix() * local.matrix());
+        global.0.enabled = local.enabled;
+        global
+    }
+
+    /// The world-space transform
+    pub fn transform(&self) -> &Transform {
+        &self.0
+    }
+}
+
+impl From<Transform> for GlobalTransform {
+    fn from(transform: Transform) -> Self {
+        Self(transform)
+    }
+}
+
+impl Deref for GlobalTransform {
+    type Target = Transform;
+
+    fn deref(&self) -> &Transform {
+        &self.0
+    }
+}
+
+impl Component for GlobalTransform {
+    fn is_enabled(&self) -> bool {
+        self.0.enabled
+    }
+}
+
+/// World-space transform to draw with, blended between the last two
+/// simulation ticks by `World::interpolate_transforms`
+///
+/// Only entities with a `PreviousTransform`, and their descendants, have
+/// one; everything else is drawn at its `GlobalTransform`.
+#[derive(Debug, Clone, Default)]
+pub struct RenderTransform(Transform);
+
+impl RenderTransform {
+    /// The world-space transform
+    pub fn transform(&self) -> &Transform {
+        &self.0
+    }
+
+    /// Where to draw an entity: its `RenderTransform` if it has one,
+    /// otherwise its `GlobalTransform`
+    pub fn resolve<'a>(render: Option<&'a RenderTransform>, global: &'a GlobalTransform) -> &'a Transform {
+        render.map_or(global.transform(), RenderTransform::transform)
+    }
+}
+
+impl From<GlobalTransform> for RenderTransform {
+    fn from(global: GlobalTransform) -> Self {
+        Self(global.0)
+    }
+}
+
+impl Deref for RenderTransform {
+    type Target = Transform;
+
+    fn deref(&self) -> &Transform {
+        &self.0
+    }
+}
+
+impl Component for RenderTransform {
+    fn is_enabled(&self) -> bool {
+        self.0.enabled
+    }
+}
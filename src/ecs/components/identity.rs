@@ -0,0 +1,108 @@
+//! Name and tag components for addressing entities without keeping handles
+
+use std::collections::HashMap;
+use crate::ecs::{Component, Entity};
+
+/// Human-readable entity name, e.g. "Wall_North" or "MainLight_Center"
+///
+/// Names are indexed by the world for `World::find_by_name`. Rename entities
+/// with `World::set_name`; edits made through `get_mut` aren't seen by the index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name(pub String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+impl Component for Name {}
+
+/// Free-form labels shared by groups of entities, e.g. "pillar" or "light"
+///
+/// Tags are indexed by the world for `World::query_tagged`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tags(pub Vec<String>);
+
+impl Tags {
+    pub fn new<S: Into<String>>(tags: impl IntoIterator<Item = S>) -> Self {
+        let mut result = Self::default();
+        for tag in tags {
+            result.insert(tag);
+        }
+        result
+    }
+
+    /// Add a tag unless it is already present
+    pub fn insert(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.contains(&tag) {
+            self.0.push(tag);
+        }
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|existing| existing == tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+impl Component for Tags {}
+
+/// Entities by name and by tag, maintained by the world as `Name` and `Tags`
+/// components are added and removed
+#[derive(Debug, Default)]
+pub(crate) struct IdentityIndex {
+    names: HashMap<String, Vec<Entity>>,
+    tags: HashMap<String, Vec<Entity>>,
+}
+
+impl IdentityIndex {
+    pub fn insert_name(&mut self, entity: Entity, name: &Name) {
+        self.names.entry(name.0.clone()).or_default().push(entity);
+    }
+
+    pub fn remove_name(&mut self, entity: Entity, name: &Name) {
+        Self::remove_entry(&mut self.names, &name.0, entity);
+    }
+
+    pub fn insert_tags(&mut self, entity: Entity, tags: &Tags) {
+        for tag in tags.iter() {
+            self.tags.entry(tag.to_string()).or_default().push(entity);
+        }
+    }
+
+    pub fn remove_tags(&mut self, entity: Entity, tags: &Tags) {
+        for tag in tags.iter() {
+            Self::remove_entry(&mut self.tags, tag, entity);
+        }
+    }
+
+    /// Entities registered under a name, oldest first
+    pub fn named(&self, name: &str) -> &[Entity] {
+        self.names.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Entities carrying a tag, oldest first
+    pub fn tagged(&self, tag: &str) -> &[Entity] {
+        self.tags.get(tag).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    #[allow(dead_code)] // Reached only from `World::clear`
+    pub fn clear(&mut self) {
+        self.names.clear();
+        self.tags.clear();
+    }
+
+    fn remove_entry(map: &mut HashMap<String, Vec<Entity>>, key: &str, entity: Entity) {
+        if let Some(entities) = map.get_mut(key) {
+            entities.retain(|&existing| existing != entity);
+            if entities.is_empty() {
+                map.remove(key);
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Kinematic body component for colliders moved along scripted paths
+
+use macroquad::prelude::*;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
+
+/// What a `KinematicBody` does when it reaches the end of its path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    /// Stop at the last waypoint, or the first when travelling backwards
+    Once,
+    /// Turn around at either end
+    PingPong,
+    /// Carry on from the last waypoint back to the first
+    Loop,
+}
+
+/// Moves an entity through a list of waypoints at a fixed speed
+///
+/// Waypoints are positions for the entity's `Transform`. Paired with a static
+/// solid `Collider` this makes doors, lifts and moving platforms: characters
+/// standing on the body ride along, and characters in its way are pushed
+/// aside. A body that would crush a character against something solid waits
+/// until it can move.
+#[derive(Debug, Clone)]
+pub struct KinematicBody {
+    pub waypoints: Vec<Vec3>,
+    pub speed: f32,                 // Units per second
+    pub mode: PathMode,
+    pub pause: f32,                 // Seconds to wait at each waypoint
+    pub is_moving: bool,            // Travelling along the path rather than stopped
+    pub next: usize,                // Waypoint being travelled to
+    pub reversing: bool,            // Travelling from the last waypoint towards the first
+    pub stop_at: Option<usize>,     // Waypoint to stop at, set by `move_to`
+    pub wait_timer: f32,            // Seconds left to wait at the current waypoint
+    pub enabled: bool,
+}
+
+impl KinematicBody {
+    /// Create a body that starts moving from the first waypoint
+    pub fn new(waypoints: Vec<Vec3>, speed: f32) -> Self {
+        Self {
+            waypoints,
+            speed,
+            mode: PathMode::PingPong,
+            pause: 0.0,
+            is_moving: true,
+            next: 0,
+            reversing: false,
+            stop_at: None,
+            wait_timer: 0.0,
+            enabled: true,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: PathMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_pause(mut self, pause: f32) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Start the body stopped at its first waypoint, waiting for `move_to` or `start`
+    pub fn stopped(mut self) -> Self {
+        self.is_moving = false;
+        self
+    }
+
+    /// Check if this component is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Carry on along the path
+    // Triggers only send bodies to waypoints for now
+    #[allow(dead_code)]
+    pub fn start(&mut self) {
+        self.is_moving = !self.waypoints.is_empty();
+    }
+
+    /// Stop where the body is
+    #[allow(dead_code)]
+    pub fn stop(&mut self) {
+        self.is_moving = false;
+    }
+
+    /// Travel along the path to `waypoint` and stop there, turning around if
+    /// it lies behind the body
+    ///
+    /// Looping paths always go forwards, round the loop if need be.
+    pub fn move_to(&mut self, waypoint: usize) {
+        if self.waypoints.is_empty() {
+            return;
+        }
+        let waypoint = waypoint.min(self.waypoints.len() - 1);
+        if self.mode != PathMode::Loop {
+            // The body is somewhere between the waypoint it came from and `next`
+            if !self.reversing && waypoint < self.next {
+                self.reversing = true;
+                self.next -= 1;
+            } else if self.reversing && waypoint > self.next {
+                self.reversing = false;
+                self.next += 1;
+            }
+        }
+        self.stop_at = Some(waypoint);
+        self.wait_timer = 0.0;
+        self.is_moving = true;
+    }
+
+    /// Move from `position` along the path for `delta_time`, returning where
+    /// the body ends up
+    pub fn advance(&mut self, position: Vec3, delta_time: f32) -> Vec3 {
+        if !self.is_moving || self.waypoints.is_empty() {
+            return position;
+        }
+        if self.wait_timer > 0.0 {
+            self.wait_timer -= delta_time;
+            return position;
+        }
+
+        let mut position = position;
+        let mut remaining = self.speed * delta_time;
+        while remaining > 0.0 {
+            let target = self.waypoints[self.next.min(self.waypoints.len() - 1)];
+            let distance = position.distance(target);
+            if distance > remaining {
+                position += (target - position) / distance * remaining;
+                break;
+            }
+            position = target;
+            remaining -= distance;
+            if !self.arrive() {
+                break;
+            }
+            if self.pause > 0.0 {
+                self.wait_timer = self.pause;
+                break;
+            }
+        }
+        position
+    }
+
+    /// Pick the waypoint after `next` once the body reaches it, returning
+    /// false if the body stops there
+    fn arrive(&mut self) -> bool {
+        let last = self.waypoints.len() - 1;
+        if self.stop_at == Some(self.next) || last == 0 {
+            self.stop_at = None;
+            self.is_moving = false;
+            return false;
+        }
+        let at_end = if self.reversing { self.next == 0 } else { self.next == last };
+        match (self.mode, at_end) {
+            (PathMode::Once, true) => {
+                self.is_moving = false;
+                return false;
+            }
+            (PathMode::PingPong, true) => self.reversing = !self.reversing,
+            (PathMode::Loop, true) => {
+                self.next = if self.reversing { last } else { 0 };
+                return true;
+            }
+            _ => {}
+        }
+        self.next = if self.reversing { self.next - 1 } else { self.next + 1 };
+        true
+    }
+}
+
+impl Component for KinematicBody {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+reflect_component!(KinematicBody {
+    speed: f32,
+    pause: f32,
+    is_moving: bool,
+    reversing: bool,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<KinematicBody>("KinematicBody")
+}
@@ -1,7 +1,8 @@
 //! Lighting components
 
 use macroquad::prelude::*;
-use crate::ecs::Component;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
 
 /// Light source component for dynamic lighting effects
 #[derive(Clone, Debug)]
@@ -133,6 +134,17 @@ impl Component for LightSource {
     }
 }
 
+reflect_component!(LightSource {
+    color: Color,
+    intensity: f32,
+    radius: f32,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<LightSource>("LightSource")
+}
+
 /// Component that receives lighting from nearby light sources
 #[derive(Clone, Debug)]
 pub struct LightReceiver {
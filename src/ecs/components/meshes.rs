@@ -2,6 +2,7 @@
 
 use macroquad::prelude::*;
 use crate::ecs::{Component, component::{AutoUpdatable, ComponentRegistration}};
+use crate::ecs::reflect::{reflect_component, ReflectFns};
 // Forward declaration - will be resolved by mod.rs re-exports
 
 /// Auto-register Renderer component
@@ -11,6 +12,13 @@ inventory::submit! {
         updater: |world, delta_time| {
             world.update_component_type::<Renderer>(delta_time);
         },
+        pre_updater: |world, delta_time| {
+            world.pre_update_component_type::<Renderer>(delta_time);
+        },
+        phase_updater: |world, delta_time| {
+            world.phase_update_component_type::<Renderer>(delta_time);
+        },
+        reflect: Some(ReflectFns::of::<Renderer>()),
     }
 }
 
@@ -21,6 +29,13 @@ inventory::submit! {
         updater: |world, delta_time| {
             world.update_component_type::<StaticMesh>(delta_time);
         },
+        pre_updater: |world, delta_time| {
+            world.pre_update_component_type::<StaticMesh>(delta_time);
+        },
+        phase_updater: |world, delta_time| {
+            world.phase_update_component_type::<StaticMesh>(delta_time);
+        },
+        reflect: None,
     }
 }
 
@@ -205,6 +220,14 @@ impl Component for Renderer {
     }
 }
 
+reflect_component!(Renderer {
+    color = material.color: Color,
+    texture_name = material.texture_name: Option<String>,
+    visible = material.visible: bool,
+    custom_mesh_path: Option<String>,
+    enabled: bool,
+});
+
 impl AutoUpdatable for Renderer {
     fn auto_update(&mut self, _entity: crate::ecs::Entity, _delta_time: f32) {
         // Renderer components don't need per-frame updates
@@ -1,9 +1,17 @@
 //! Component modules
 
 pub mod transform;
+pub mod hierarchy;
+pub mod identity;
 pub mod velocity;
 pub mod player;
 pub mod collision;
+pub mod collision_mesh;
+pub mod character;
+pub mod trigger;
+pub mod rigid_body;
+pub mod kinematic_body;
+pub mod gravity_volume;
 pub mod entities;
 pub mod pathfinding;
 pub mod meshes;
@@ -11,10 +19,18 @@ pub mod lighting;
 pub mod rendering;
 
 // Re-export all components
-pub use transform::Transform;
+pub use transform::{Transform, PreviousTransform};
+pub use hierarchy::{Parent, Children, GlobalTransform, RenderTransform};
+pub use identity::{Name, Tags};
 pub use velocity::Velocity;
 pub use player::Player;
 pub use collision::{Collider, ColliderShape, ColliderMaterial};
+pub use collision_mesh::{TriMesh, ConvexHull};
+pub use character::CharacterController;
+pub use trigger::{TriggerAction, TriggerActions};
+pub use rigid_body::RigidBody;
+pub use kinematic_body::{KinematicBody, PathMode};
+pub use gravity_volume::{GravityVolume, GravityOverride};
 pub use entities::{Wall, Floor, Ceiling, Prop};
 pub use pathfinding::{TestWaypoint, Pathfinder, TestBot};
 pub use meshes::{StaticMesh, StaticMeshType, Renderer, RenderMode, RenderMaterial};
@@ -2,6 +2,7 @@
 
 use macroquad::prelude::*;
 use crate::ecs::{Component, component::{AutoUpdatable, ComponentRegistration}};
+use crate::ecs::reflect::{reflect_component, ReflectFns};
 
 // Auto-register TestBot component
 inventory::submit! {
@@ -10,6 +11,13 @@ inventory::submit! {
         updater: |world, delta_time| {
             world.update_component_type::<TestBot>(delta_time);
         },
+        pre_updater: |world, delta_time| {
+            world.pre_update_component_type::<TestBot>(delta_time);
+        },
+        phase_updater: |world, delta_time| {
+            world.phase_update_component_type::<TestBot>(delta_time);
+        },
+        reflect: Some(ReflectFns::of::<TestBot>()),
     }
 }
 
@@ -20,6 +28,13 @@ inventory::submit! {
         updater: |world, delta_time| {
             world.update_component_type::<Pathfinder>(delta_time);
         },
+        pre_updater: |world, delta_time| {
+            world.pre_update_component_type::<Pathfinder>(delta_time);
+        },
+        phase_updater: |world, delta_time| {
+            world.phase_update_component_type::<Pathfinder>(delta_time);
+        },
+        reflect: Some(ReflectFns::of::<Pathfinder>()),
     }
 }
 
@@ -187,6 +202,11 @@ impl Component for TestBot {
     }
 }
 
+reflect_component!(TestBot {
+    current_waypoint: usize,
+    enabled: bool,
+});
+
 impl AutoUpdatable for TestBot {
     fn auto_update(&mut self, _entity: crate::ecs::Entity, _delta_time: f32) {
         // Check if test bot is finished
@@ -317,6 +337,17 @@ impl Component for Pathfinder {
     }
 }
 
+reflect_component!(Pathfinder {
+    target: Option<Vec2>,
+    path_index: usize,
+    movement_speed: f32,
+    rotation_speed: f32,
+    stuck_time: f32,
+    needs_recalculation: bool,
+    arrival_threshold: f32,
+    enabled: bool,
+});
+
 impl AutoUpdatable for Pathfinder {
     fn auto_update(&mut self, _entity: crate::ecs::Entity, delta_time: f32) {
         // Update internal timers and state
@@ -1,6 +1,7 @@
 //! Player component
 
-use crate::ecs::Component;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
 
 #[derive(Debug, Clone)]
 pub struct Player {
@@ -13,13 +14,16 @@ pub struct Player {
 }
 
 impl Player {
+    /// Upward speed of a jump before the `jump_strength` setting from config.ini
+    pub const DEFAULT_JUMP_STRENGTH: f32 = 4.5;
+
     pub fn new() -> Self {
         Self {
             health: 100.0,
             max_health: 100.0,
             is_grounded: true,
             move_speed: 5.0,
-            jump_strength: 8.0,
+            jump_strength: Self::DEFAULT_JUMP_STRENGTH,
             enabled: true,
         }
     }
@@ -63,4 +67,17 @@ impl Component for Player {
     fn disable(&mut self) {
         self.enabled = false;
     }
-} 
\ No newline at end of file
+}
+
+reflect_component!(Player {
+    health: f32,
+    max_health: f32,
+    is_grounded: bool,
+    move_speed: f32,
+    jump_strength: f32,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<Player>("Player")
+}
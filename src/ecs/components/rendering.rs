@@ -69,16 +69,6 @@ impl StaticRenderer {
         self
     }
 
-    /// Enable this component
-    pub fn enable(&mut self) {
-        self.enabled = true;
-    }
-
-    /// Disable this component
-    pub fn disable(&mut self) {
-        self.enabled = false;
-    }
-
     /// Check if this component is enabled
     pub fn is_enabled(&self) -> bool {
         self.enabled
@@ -0,0 +1,128 @@
+//! Rigid body component for simulated dynamic objects
+
+use macroquad::prelude::*;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
+use super::{Collider, ColliderShape};
+
+/// Simulated motion for an entity with a dynamic `Collider` and a `Velocity`
+///
+/// Bodies fall under gravity, bounce and slide off static colliders using
+/// their `ColliderMaterial`, and fall asleep once they have come to rest.
+/// Impulses are collected here and applied on the next physics step.
+#[derive(Debug, Clone)]
+pub struct RigidBody {
+    pub mass: f32,
+    pub gravity_scale: f32,         // Multiplier for world gravity, 0 to float
+    pub linear_damping: f32,        // Fraction of linear velocity lost per second
+    pub angular_damping: f32,       // Fraction of angular velocity lost per second
+    pub is_sleeping: bool,          // At rest and skipped until woken
+    pub sleep_timer: f32,           // Seconds spent nearly still
+    pub pending_impulse: Vec3,      // Linear impulse waiting for the next step
+    pub pending_angular_impulse: Vec3,
+    pub enabled: bool,
+}
+
+impl RigidBody {
+    pub fn new(mass: f32) -> Self {
+        Self {
+            mass: mass.max(f32::EPSILON),
+            gravity_scale: 1.0,
+            linear_damping: 0.2,
+            angular_damping: 0.5,
+            is_sleeping: false,
+            sleep_timer: 0.0,
+            pending_impulse: Vec3::ZERO,
+            pending_angular_impulse: Vec3::ZERO,
+            enabled: true,
+        }
+    }
+
+    /// Create a body whose mass comes from the collider's volume and material density
+    pub fn from_collider(collider: &Collider) -> Self {
+        Self::new(collider.shape.volume() * collider.material.density)
+    }
+
+    /// Check if this component is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Push the body through its centre of mass, waking it
+    // Kicks resolve inside the physics step; these are for scripted pushes
+    #[allow(dead_code)]
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.pending_impulse += impulse;
+        self.wake();
+    }
+
+    /// Push the body at `offset` from its centre of mass, which also spins it
+    #[allow(dead_code)]
+    pub fn apply_impulse_at(&mut self, impulse: Vec3, offset: Vec3) {
+        self.pending_angular_impulse += offset.cross(impulse);
+        self.apply_impulse(impulse);
+    }
+
+    /// Resume simulating a sleeping body
+    pub fn wake(&mut self) {
+        self.is_sleeping = false;
+        self.sleep_timer = 0.0;
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        1.0 / self.mass
+    }
+
+    /// Inverse of the body's moment of inertia about its centre, treating the
+    /// shape as equally hard to spin about every axis
+    pub fn inverse_inertia(&self, shape: &ColliderShape) -> f32 {
+        let inertia = match shape {
+            ColliderShape::Box { size } => self.mass * size.length_squared() / 18.0,
+            // Meshes spin like the box around them
+            ColliderShape::TriMesh { mesh } => self.mass * mesh.bounds().size().length_squared() / 18.0,
+            ColliderShape::ConvexHull { hull } => self.mass * hull.bounds().size().length_squared() / 18.0,
+            ColliderShape::Sphere { radius } => 0.4 * self.mass * radius * radius,
+            ColliderShape::Capsule { height, radius } => {
+                // Averaged over the axes of a cylinder as tall as the whole capsule
+                let length = height + 2.0 * radius;
+                let across = self.mass * (3.0 * radius * radius + length * length) / 12.0;
+                let along = 0.5 * self.mass * radius * radius;
+                (2.0 * across + along) / 3.0
+            }
+        };
+        1.0 / inertia.max(f32::EPSILON)
+    }
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl Component for RigidBody {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+reflect_component!(RigidBody {
+    mass: f32,
+    gravity_scale: f32,
+    linear_damping: f32,
+    angular_damping: f32,
+    is_sleeping: bool,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<RigidBody>("RigidBody")
+}
@@ -1,7 +1,10 @@
 //! Transform component for position, rotation, and scale in 3D space
 
 use macroquad::prelude::*;
-use crate::ecs::Component;
+use crate::ecs::{Component, Entity};
+use crate::ecs::component::{ComponentHooks, ComponentRegistration};
+use super::{CharacterController, KinematicBody, RigidBody};
+use crate::ecs::reflect::reflect_component;
 
 /// Position, rotation, and scale in 3D space
 #[derive(Debug, Clone)]
@@ -80,13 +83,31 @@ impl Transform {
         self.position += delta;
     }
 
+    /// Blend from `previous` toward this transform for drawing between
+    /// simulation ticks
+    ///
+    /// Each Euler angle turns the short way round, so a yaw wrapping past
+    /// ±π doesn't spin the whole way back.
+    pub fn interpolated(&self, previous: &Transform, alpha: f32) -> Transform {
+        let turn = (self.rotation - previous.rotation + Vec3::splat(std::f32::consts::PI))
+            .rem_euclid(Vec3::splat(std::f32::consts::TAU))
+            - Vec3::splat(std::f32::consts::PI);
+        Transform {
+            position: previous.position.lerp(self.position, alpha),
+            rotation: previous.rotation + turn * alpha,
+            scale: previous.scale.lerp(self.scale, alpha),
+            enabled: self.enabled,
+        }
+    }
+
+    /// Get the rotation as a quaternion, applying the Euler angles in XYZ order
+    pub fn orientation(&self) -> Quat {
+        Quat::from_euler(EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z)
+    }
+
     /// Get transformation matrix
     pub fn matrix(&self) -> Mat4 {
-        Mat4::from_scale_rotation_translation(
-            self.scale,
-            Quat::from_euler(EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z),
-            self.position,
-        )
+        Mat4::from_scale_rotation_translation(self.scale, self.orientation(), self.position)
     }
 }
 
@@ -108,4 +129,65 @@ impl Component for Transform {
     fn disable(&mut self) {
         self.enabled = false;
     }
-} 
\ No newline at end of file
+}
+
+reflect_component!(Transform {
+    position: Vec3,
+    rotation: Vec3,
+    scale: Vec3,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<Transform>("Transform")
+}
+
+/// Transform as it was before the latest simulation tick, recorded for
+/// entities that should be interpolated when rendered
+#[derive(Debug, Clone, Default)]
+pub struct PreviousTransform(pub Transform);
+
+impl Component for PreviousTransform {}
+
+/// Start recording the transform history of a simulated body, which moves
+/// once per tick and so is drawn interpolated
+fn track_previous_transform(world: &mut crate::ecs::World, entity: Entity) {
+    let simulated = world.has::<RigidBody>(entity)
+        || world.has::<KinematicBody>(entity)
+        || world.has::<CharacterController>(entity);
+    if !simulated || world.has::<PreviousTransform>(entity) {
+        return;
+    }
+    // Components may be added in any order, so wait for the Transform
+    if let Some(transform) = world.get::<Transform>(entity).cloned() {
+        world.add(entity, PreviousTransform(transform));
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(track_previous_transform),
+        ..ComponentHooks::new::<Transform>("Transform")
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(track_previous_transform),
+        ..ComponentHooks::new::<RigidBody>("RigidBody")
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(track_previous_transform),
+        ..ComponentHooks::new::<KinematicBody>("KinematicBody")
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(track_previous_transform),
+        ..ComponentHooks::new::<CharacterController>("CharacterController")
+    }
+}
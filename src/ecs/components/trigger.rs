@@ -0,0 +1,91 @@
+//! Trigger action component for level-scripted trigger volumes
+
+use macroquad::prelude::*;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
+
+/// Something a trigger does when an entity enters or leaves it
+///
+/// Targets are looked up by name first and then by tag, so one action can
+/// address a single entity or a whole group.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerAction {
+    /// Move the entity that set off the trigger
+    Teleport { position: Vec3 },
+    /// Activate the target entities, making them visible and solid again
+    Enable { target: String },
+    /// Deactivate the target entities
+    Disable { target: String },
+    /// Switch the target light sources on or off
+    ToggleLights { target: String },
+    /// Send the target kinematic bodies along their paths to a waypoint
+    MoveTo { target: String, waypoint: usize },
+    /// Replace the current level with another level file
+    LoadLevel { path: String },
+    /// Print a message to the console
+    Message { text: String },
+}
+
+/// Actions run when entities enter or leave this entity's trigger collider
+#[derive(Debug, Clone)]
+pub struct TriggerActions {
+    pub on_enter: Vec<TriggerAction>,
+    pub on_exit: Vec<TriggerAction>,
+    pub enabled: bool,
+}
+
+impl TriggerActions {
+    pub fn new() -> Self {
+        Self {
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    // Levels fill the action lists directly; only the tests chain these
+    #[allow(dead_code)]
+    pub fn on_enter(mut self, action: TriggerAction) -> Self {
+        self.on_enter.push(action);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn on_exit(mut self, action: TriggerAction) -> Self {
+        self.on_exit.push(action);
+        self
+    }
+
+    /// Check if this component is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+impl Default for TriggerActions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for TriggerActions {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+reflect_component!(TriggerActions {
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<TriggerActions>("TriggerActions")
+}
@@ -1,7 +1,8 @@
 //! Velocity component for linear and angular velocity
 
 use macroquad::prelude::*;
-use crate::ecs::Component;
+use crate::ecs::{Component, component::ComponentRegistration};
+use crate::ecs::reflect::reflect_component;
 
 /// Linear and angular velocity
 #[derive(Debug, Clone)]
@@ -67,4 +68,14 @@ impl Component for Velocity {
     fn disable(&mut self) {
         self.enabled = false;
     }
-} 
\ No newline at end of file
+}
+
+reflect_component!(Velocity {
+    linear: Vec3,
+    angular: Vec3,
+    enabled: bool,
+});
+
+inventory::submit! {
+    ComponentRegistration::reflected::<Velocity>("Velocity")
+}
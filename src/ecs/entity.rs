@@ -1,22 +1,26 @@
 //! Entity management with safe handles and generation counters
 
 use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 /// Unique identifier for entities
 pub type EntityId = u32;
 
 /// Entity handle with generation for safe access
+///
+/// Handles are plain ids; per-entity state such as whether the entity is
+/// active lives in the `EntityManager`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Entity {
     pub id: EntityId,
     pub generation: u32,
-    pub enabled: bool,
 }
 
 impl Entity {
     /// Create a new entity with given id and generation
     pub fn new(id: EntityId, generation: u32) -> Self {
-        Self { id, generation, enabled: true }
+        Self { id, generation }
     }
     
     /// Check if this entity handle is valid
@@ -30,10 +34,17 @@ impl Entity {
 pub struct EntityManager {
     /// Current generation for each entity slot
     generations: Vec<u32>,
+    /// Whether each entity slot is currently in use
+    alive: Vec<bool>,
+    /// Whether each entity was itself set active
+    active_self: Vec<bool>,
+    /// Whether each entity and all of its ancestors are active
+    active: Vec<bool>,
     /// Free entity IDs that can be reused
     free_entities: VecDeque<EntityId>,
-    /// Next entity ID to allocate if no free ones available
-    next_id: EntityId,
+    /// Next entity ID to allocate if no free ones available, shared with
+    /// `EntityReserver`s so deferred spawns get ids up front
+    next_id: Arc<AtomicU32>,
 }
 
 impl EntityManager {
@@ -41,8 +52,11 @@ impl EntityManager {
     pub fn new() -> Self {
         Self {
             generations: Vec::new(),
+            alive: Vec::new(),
+            active_self: Vec::new(),
+            active: Vec::new(),
             free_entities: VecDeque::new(),
-            next_id: 0,
+            next_id: Arc::new(AtomicU32::new(0)),
         }
     }
     
@@ -51,20 +65,47 @@ impl EntityManager {
         if let Some(id) = self.free_entities.pop_front() {
             // Reuse a free entity ID
             let generation = self.generations[id as usize];
+            self.alive[id as usize] = true;
+            self.active_self[id as usize] = true;
+            self.active[id as usize] = true;
             Entity::new(id, generation)
         } else {
             // Allocate a new entity ID
-            let id = self.next_id;
-            self.next_id += 1;
-            
-            // Ensure we have space in arrays
-            if id as usize >= self.generations.len() {
-                self.generations.resize(id as usize + 1, 0);
-            }
-            
-            self.generations[id as usize] = 1; // Start at generation 1
-            Entity::new(id, 1)
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let entity = Entity::new(id, 1); // Start at generation 1
+            self.materialize(entity);
+            entity
+        }
+    }
+    
+    /// Get a handle that can reserve entity ids without access to the manager
+    pub fn reserver(&self) -> EntityReserver {
+        EntityReserver { next_id: Arc::clone(&self.next_id) }
+    }
+    
+    /// Bring an entity reserved through an `EntityReserver` to life
+    ///
+    /// Returns false if the entity's id was never handed out or its slot has
+    /// been used since, e.g. because it is alive or the manager was cleared.
+    pub fn materialize(&mut self, entity: Entity) -> bool {
+        let index = entity.id as usize;
+        if entity.id >= self.next_id.load(Ordering::Relaxed) || self.generations.get(index).is_some_and(|&generation| generation != 0) {
+            return false;
+        }
+        
+        // Ensure we have space in arrays
+        if index >= self.generations.len() {
+            self.generations.resize(index + 1, 0);
+            self.alive.resize(index + 1, false);
+            self.active_self.resize(index + 1, false);
+            self.active.resize(index + 1, false);
         }
+        
+        self.generations[index] = entity.generation;
+        self.alive[index] = true;
+        self.active_self[index] = true;
+        self.active[index] = true;
+        true
     }
     
     /// Destroy an entity (marks it for reuse)
@@ -75,6 +116,7 @@ impl EntityManager {
         
         // Increment generation to invalidate existing handles
         self.generations[entity.id as usize] += 1;
+        self.alive[entity.id as usize] = false;
         
         // Add to free list for reuse
         self.free_entities.push_back(entity.id);
@@ -88,41 +130,108 @@ impl EntityManager {
             return false;
         }
         
-        self.generations[entity.id as usize] == entity.generation
+        self.alive[entity.id as usize] && self.generations[entity.id as usize] == entity.generation
     }
     
-    /// Get the current generation for an entity ID
-    pub fn generation(&self, id: EntityId) -> Option<u32> {
-        self.generations.get(id as usize).copied()
+    /// Check if an entity and all of its ancestors are active
+    ///
+    /// Inactive entities are skipped by queries and systems. Invalid entities
+    /// are never active.
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.is_valid(entity) && self.active[entity.id as usize]
     }
     
-    /// Get total number of entities ever created
+    /// Check if an entity was itself set active, regardless of its ancestors
+    pub fn is_active_self(&self, entity: Entity) -> bool {
+        self.is_valid(entity) && self.active_self[entity.id as usize]
+    }
+    
+    /// Record an entity's own active flag and its effective state
+    ///
+    /// Use `World::set_active`, which works out the effective state from the
+    /// entity's ancestors.
+    pub(crate) fn set_active_state(&mut self, entity: Entity, active_self: bool, active: bool) {
+        if self.is_valid(entity) {
+            self.active_self[entity.id as usize] = active_self;
+            self.active[entity.id as usize] = active;
+        }
+    }
+    
+    /// Get total number of entity ids ever handed out
     pub fn total_created(&self) -> u32 {
-        self.next_id
+        self.next_id.load(Ordering::Relaxed)
     }
     
     /// Get number of currently active entities
     pub fn active_count(&self) -> usize {
-        self.next_id as usize - self.free_entities.len()
+        self.alive.iter().filter(|&&alive| alive).count()
     }
     
-    /// Get all currently valid entities
-    pub fn all_entities(&self) -> Vec<Entity> {
-        let mut entities = Vec::new();
+    /// Destroy every entity. Outstanding reservations become invalid.
+    ///
+    /// Ids are kept and their generations bumped, so handles from before the
+    /// clear never alias entities created after it.
+    #[allow(dead_code)] // Only reached through `World::clear`, which the game never calls
+    pub fn clear(&mut self) {
+        let len = self.next_id.load(Ordering::Relaxed) as usize;
+        self.generations.resize(len, 0);
+        self.alive.clear();
+        self.alive.resize(len, false);
+        self.active_self.clear();
+        self.active_self.resize(len, false);
+        self.active.clear();
+        self.active.resize(len, false);
         
-        for id in 0..self.next_id {
-            if let Some(generation) = self.generation(id) {
-                let entity = Entity::new(id, generation);
-                if self.is_valid(entity) {
-                    entities.push(entity);
-                }
-            }
+        self.free_entities.clear();
+        for (id, generation) in self.generations.iter_mut().enumerate() {
+            // Reserved slots that never materialized still move past the reserved generation
+            *generation = (*generation).max(1) + 1;
+            self.free_entities.push_back(id as EntityId);
         }
-        
-        entities
     }
+    
+    /// Iterate over all currently valid entities without allocating
+    pub fn iter(&self) -> EntityIter<'_> {
+        EntityIter { manager: self, next_id: 0 }
+    }
+}
 
+/// Reserves fresh entity ids for an `EntityManager` from outside it
+///
+/// A reserved entity isn't valid until `EntityManager::materialize` is called
+/// for it, which the command buffer does when it spawns the entity.
+#[derive(Debug, Clone)]
+pub struct EntityReserver {
+    next_id: Arc<AtomicU32>,
+}
 
+impl EntityReserver {
+    /// Reserve a fresh entity id
+    pub fn reserve(&self) -> Entity {
+        Entity::new(self.next_id.fetch_add(1, Ordering::Relaxed), 1)
+    }
+}
+
+/// Iterator over the live entities of an `EntityManager`
+pub struct EntityIter<'a> {
+    manager: &'a EntityManager,
+    next_id: EntityId,
+}
+
+impl Iterator for EntityIter<'_> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        while (self.next_id as usize) < self.manager.alive.len() {
+            let id = self.next_id;
+            self.next_id += 1;
+
+            if self.manager.alive[id as usize] {
+                return Some(Entity::new(id, self.manager.generations[id as usize]));
+            }
+        }
+        None
+    }
 }
 
 impl Default for EntityManager {
@@ -175,4 +284,73 @@ mod tests {
         assert!(!manager.is_valid(entity1)); // Old handle invalid
         assert!(manager.is_valid(entity2));  // New handle valid
     }
+    
+    #[test]
+    fn test_reserved_entities_are_valid_once_materialized() {
+        let mut manager = EntityManager::new();
+        let reserver = manager.reserver();
+        
+        let reserved = reserver.reserve();
+        let created = manager.create();
+        assert_ne!(reserved.id, created.id);
+        assert!(!manager.is_valid(reserved));
+        
+        assert!(manager.materialize(reserved));
+        assert!(!manager.materialize(reserved));
+        assert!(manager.is_valid(reserved));
+        assert_eq!(manager.active_count(), 2);
+    }
+    
+    #[test]
+    fn test_active_state_is_shared_by_all_handles() {
+        let mut manager = EntityManager::new();
+        
+        let entity = manager.create();
+        let copy = entity;
+        assert!(manager.is_active(entity));
+        
+        manager.set_active_state(entity, false, false);
+        assert!(!manager.is_active(copy));
+        
+        // Reused slots start out active again
+        manager.destroy(entity);
+        let reused = manager.create();
+        assert_eq!(reused.id, entity.id);
+        assert!(manager.is_active(reused));
+        assert!(!manager.is_active(entity));
+    }
+    
+    #[test]
+    fn test_clear_invalidates_old_handles() {
+        let mut manager = EntityManager::new();
+        let reserver = manager.reserver();
+        
+        let entity1 = manager.create();
+        let entity2 = manager.create();
+        let reserved = reserver.reserve();
+        manager.clear();
+        assert_eq!(manager.active_count(), 0);
+        assert!(!manager.is_valid(entity1));
+        
+        // Ids are reused with newer generations
+        let created: Vec<Entity> = (0..4).map(|_| manager.create()).collect();
+        assert_eq!(created[0].id, entity1.id);
+        assert!(!manager.is_valid(entity1));
+        assert!(!manager.is_valid(entity2));
+        assert!(created.iter().all(|&entity| manager.is_valid(entity)));
+        assert!(!created.contains(&entity1) && !created.contains(&entity2) && !created.contains(&reserved));
+        assert!(!manager.materialize(reserved));
+    }
+    
+    #[test]
+    fn test_entity_iteration_skips_destroyed() {
+        let mut manager = EntityManager::new();
+        
+        let entity1 = manager.create();
+        let entity2 = manager.create();
+        manager.destroy(entity1);
+        
+        let alive: Vec<Entity> = manager.iter().collect();
+        assert_eq!(alive, vec![entity2]);
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,238 @@
+//! Typed events for communication between systems
+//!
+//! Each event type has its own `Events<T>` channel stored as a world resource.
+//! Channels are double-buffered: an event sent during one frame can be read
+//! during that frame and the next, after which `World::update_events` drops it.
+//! Readers keep their own cursor, so every reader sees each event once.
+//!
+//! ```ignore
+//! world.add_event::<TriggerEntered>();
+//! world.send_event(TriggerEntered { trigger, entity });
+//!
+//! let mut reader = EventReader::<TriggerEntered>::default();
+//! if let Some(events) = world.events::<TriggerEntered>() {
+//!     for event in reader.read(events) { /* ... */ }
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use crate::ecs::{Entity, Resource};
+
+/// Trait for event types
+pub trait Event: 'static + Send + Sync {}
+
+/// Double-buffered channel of events of one type
+pub struct Events<T: Event> {
+    /// Events sent during the previous frame
+    previous: Vec<T>,
+    /// Id of the first event in `previous`
+    previous_start: usize,
+    /// Events sent during the current frame
+    current: Vec<T>,
+    /// Id of the first event in `current`
+    current_start: usize,
+}
+
+impl<T: Event> Events<T> {
+    /// Create an empty channel
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            previous_start: 0,
+            current: Vec::new(),
+            current_start: 0,
+        }
+    }
+
+    /// Send an event
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    /// Total number of events ever sent on this channel
+    pub fn event_count(&self) -> usize {
+        self.current_start + self.current.len()
+    }
+
+    /// Number of events currently buffered
+    #[allow(dead_code)] // Systems read channels through an `EventReader` instead
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Check if no events are buffered
+    #[allow(dead_code)] // Systems read channels through an `EventReader` instead
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Swap buffers, dropping the events sent two frames ago
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+        self.previous_start = self.current_start;
+        self.current_start = self.previous_start + self.previous.len();
+    }
+
+    /// Iterate over buffered events with an id of at least `first_id`
+    fn iter_from(&self, first_id: usize) -> impl Iterator<Item = &T> {
+        let skip_previous = first_id.saturating_sub(self.previous_start);
+        let skip_current = first_id.saturating_sub(self.current_start);
+        self.previous.iter().skip(skip_previous).chain(self.current.iter().skip(skip_current))
+    }
+}
+
+impl<T: Event> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Event> Resource for Events<T> {}
+
+/// Write access to an event channel
+pub struct EventWriter<'w, T: Event> {
+    events: &'w mut Events<T>,
+}
+
+impl<'w, T: Event> EventWriter<'w, T> {
+    pub fn new(events: &'w mut Events<T>) -> Self {
+        Self { events }
+    }
+
+    /// Send an event
+    pub fn send(&mut self, event: T) {
+        self.events.send(event);
+    }
+
+    /// Send every event from an iterator
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        for event in events {
+            self.events.send(event);
+        }
+    }
+}
+
+/// Cursor into an event channel; each reader sees every event once
+///
+/// Systems keep their reader between frames, usually by capturing it in the
+/// system closure.
+pub struct EventReader<T: Event> {
+    last_event_count: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Event> EventReader<T> {
+    /// Create a reader that will see every event still buffered
+    pub fn new() -> Self {
+        Self {
+            last_event_count: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Read the events sent since this reader last read the channel
+    pub fn read<'e>(&mut self, events: &'e Events<T>) -> impl Iterator<Item = &'e T> {
+        let first_id = self.last_event_count;
+        self.last_event_count = events.event_count();
+        events.iter_from(first_id)
+    }
+
+    /// Check if there are events this reader hasn't read yet
+    #[allow(dead_code)] // Systems so far just read whatever is there
+    pub fn has_unread(&self, events: &Events<T>) -> bool {
+        events.iter_from(self.last_event_count).next().is_some()
+    }
+}
+
+impl<T: Event> Default for EventReader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sent when an entity is spawned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntitySpawned {
+    pub entity: Entity,
+}
+
+impl Event for EntitySpawned {}
+
+/// Sent when an entity is despawned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityDespawned {
+    pub entity: Entity,
+}
+
+impl Event for EntityDespawned {}
+
+/// Sent when an entity starts overlapping a trigger collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriggerEntered {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+impl Event for TriggerEntered {}
+
+/// Sent every tick an entity remains inside a trigger collider it entered earlier
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriggerStayed {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+impl Event for TriggerStayed {}
+
+/// Sent when an entity stops overlapping a trigger collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriggerExited {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+impl Event for TriggerExited {}
+
+/// Sent after level data has been (re)applied to the world
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelReloaded {
+    /// True for a full reload, false for a selective hot-reload
+    pub full_reload: bool,
+}
+
+impl Event for LevelReloaded {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Ping(u32);
+
+    impl Event for Ping {}
+
+    #[test]
+    fn test_events_are_double_buffered() {
+        let mut events = Events::new();
+        let mut reader = EventReader::<Ping>::new();
+
+        events.send(Ping(1));
+        events.update();
+        events.send(Ping(2));
+
+        // Events from the previous and current frame are both visible
+        assert_eq!(reader.read(&events).collect::<Vec<_>>(), vec![&Ping(1), &Ping(2)]);
+        assert!(!reader.has_unread(&events));
+
+        events.update();
+        events.send(Ping(3));
+        assert_eq!(reader.read(&events).collect::<Vec<_>>(), vec![&Ping(3)]);
+
+        // A late reader only sees what is still buffered
+        events.update();
+        events.update();
+        let mut late = EventReader::<Ping>::new();
+        assert_eq!(late.read(&events).count(), 0);
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! World gravity and the volumes that change it locally
+//!
+//! The `Gravity` resource holds the level's gravity and `GravityVolume`
+//! components override it inside their colliders. A `GravityField` gathers
+//! both once per step so characters and rigid bodies can look up the
+//! gravity wherever they are.
+
+use std::cmp::Reverse;
+use macroquad::prelude::*;
+use crate::ecs::{Entity, Resource, World};
+use crate::ecs::components::{Collider, ColliderShape, GravityVolume, Transform, GlobalTransform, Parent};
+
+/// Gravity weaker than this counts as zero-g
+const ZERO_G_THRESHOLD: f32 = 0.01;
+
+/// The level's gravity, used wherever no `GravityVolume` applies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gravity(pub Vec3);
+
+impl Gravity {
+    /// Downward pull the game has always used, in units per second squared
+    pub const DEFAULT: Vec3 = Vec3::new(0.0, -12.0, 0.0);
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+impl Resource for Gravity {}
+
+/// Check if `gravity` is too weak to pull anything anywhere
+pub fn is_zero_g(gravity: Vec3) -> bool {
+    gravity.length_squared() < ZERO_G_THRESHOLD * ZERO_G_THRESHOLD
+}
+
+/// A volume's collider in world space and the gravity inside it
+#[derive(Debug, Clone)]
+struct Volume {
+    shape: ColliderShape,
+    transform: Transform,
+    gravity: Vec3,
+    priority: u32,
+}
+
+/// The gravity throughout a world: the `Gravity` resource, overridden inside
+/// enabled `GravityVolume`s
+#[derive(Debug, Clone)]
+pub struct GravityField {
+    gravity: Vec3,
+    volumes: Vec<Volume>,  // Highest priority first
+}
+
+impl GravityField {
+    /// The same gravity everywhere
+    // Systems always gather the field from a world
+    #[allow(dead_code)]
+    pub fn uniform(gravity: Vec3) -> Self {
+        Self { gravity, volumes: Vec::new() }
+    }
+
+    /// Gather the world's gravity and its volumes
+    ///
+    /// Worlds without a `Gravity` resource use `Gravity::DEFAULT`.
+    pub fn from_world(world: &World) -> Self {
+        let gravity = world.resource::<Gravity>().copied().unwrap_or_default().0;
+        let mut volumes: Vec<Volume> = world.query::<(Entity, &GravityVolume, &Collider, &Transform)>()
+            .iter()
+            .filter(|(_, volume, collider, _)| volume.is_enabled() && collider.is_enabled())
+            .filter_map(|(entity, volume, collider, transform)| {
+                // Children are placed by their global transform, like their collision proxies
+                let transform = match world.get::<GlobalTransform>(entity) {
+                    Some(global) => global.transform().clone(),
+                    None if !world.has::<Parent>(entity) => transform.clone(),
+                    None => return None,
+                };
+                Some(Volume {
+                    shape: collider.shape.clone(),
+                    transform,
+                    gravity: volume.gravity(gravity),
+                    priority: volume.priority,
+                })
+            })
+            .collect();
+        volumes.sort_by_key(|volume| Reverse(volume.priority));
+        Self { gravity, volumes }
+    }
+
+    /// Gravity at `point`
+    pub fn at(&self, point: Vec3) -> Vec3 {
+        self.volumes.iter()
+            .find(|volume| volume.shape.contains_point(point, &volume.transform))
+            .map_or(self.gravity, |volume| volume.gravity)
+    }
+}
+
+/// Gravity at `point` in `world`
+pub fn gravity_at(world: &World, point: Vec3) -> Vec3 {
+    GravityField::from_world(world).at(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::GravityOverride;
+
+    #[test]
+    fn test_volumes_override_world_gravity() {
+        let mut world = World::new();
+        assert_eq!(gravity_at(&world, Vec3::ZERO), Gravity::DEFAULT);
+        world.insert_resource(Gravity(Vec3::new(0.0, -4.0, 0.0)));
+
+        world.spawn()
+            .with(Transform::new(Vec3::new(5.0, 0.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Box { size: Vec3::splat(4.0) }))
+            .with(GravityVolume::zero_g())
+            .build();
+        world.spawn()
+            .with(Transform::new(Vec3::new(6.5, 0.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Sphere { radius: 1.0 }))
+            .with(GravityVolume::scaled(0.5).with_priority(1))
+            .build();
+        world.spawn()
+            .with(Transform::new(Vec3::new(-5.0, 0.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Sphere { radius: 1.0 }))
+            .with(GravityVolume::directional(Vec3::X * 3.0).with_enabled(false))
+            .build();
+
+        let field = GravityField::from_world(&world);
+        assert_eq!(field.at(Vec3::ZERO), Vec3::new(0.0, -4.0, 0.0));
+        assert!(is_zero_g(field.at(Vec3::new(4.0, 1.0, 0.0))));
+        // The higher priority volume wins where they overlap
+        assert_eq!(field.at(Vec3::new(6.5, 0.5, 0.0)), Vec3::new(0.0, -2.0, 0.0));
+        assert_eq!(field.at(Vec3::new(-5.0, 0.0, 0.0)), Vec3::new(0.0, -4.0, 0.0));
+
+        let directional = GravityVolume::new(GravityOverride::Vector(Vec3::Z));
+        assert_eq!(directional.gravity(Gravity::DEFAULT), Vec3::Z);
+        assert!(!is_zero_g(GravityField::uniform(Gravity::DEFAULT).at(Vec3::ZERO)));
+    }
+}
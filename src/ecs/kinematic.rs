@@ -0,0 +1,240 @@
+//! Kinematic bodies moving colliders along scripted paths
+//!
+//! Each `KinematicBody` is moved along its waypoints and its proxy in the
+//! `CollisionWorld` follows straight away, so characters moving later in the
+//! tick see it where it is. Characters standing on a body ride along with
+//! it, and characters in its way are pushed aside. If a push would shove a
+//! character into something solid the body stays where it was this tick.
+
+use macroquad::prelude::*;
+use crate::ecs::{Entity, World, With, CollisionWorld};
+use crate::ecs::components::{Collider, CharacterController, KinematicBody, Transform, GlobalTransform, Parent};
+
+/// Advance every enabled kinematic body by `delta_time`
+pub fn step_kinematic_bodies(world: &mut World, delta_time: f32) {
+    if delta_time <= 0.0 {
+        return;
+    }
+    let bodies: Vec<Entity> = world.query_filtered::<Entity, With<KinematicBody>>().into_iter().collect();
+
+    let stepped = world.resource_scope::<CollisionWorld, _>(|world, collision| {
+        for &entity in &bodies {
+            step_body(world, Some(collision), entity, delta_time);
+        }
+    });
+    if stepped.is_none() {
+        for entity in bodies {
+            step_body(world, None, entity, delta_time);
+        }
+    }
+}
+
+/// Move one body along its path, bringing the characters it touches along
+fn step_body(world: &mut World, collision: Option<&mut CollisionWorld>, entity: Entity, delta_time: f32) {
+    let (before, mut body) = match (world.get::<Transform>(entity), world.get::<KinematicBody>(entity)) {
+        (Some(transform), Some(body)) if body.is_enabled() && transform.is_enabled() => (transform.clone(), body.clone()),
+        _ => return,
+    };
+
+    let mut after = before.clone();
+    after.position = body.advance(before.position, delta_time);
+    if after.position != before.position {
+        let moved = match collision {
+            Some(collision) => carry_characters(world, collision, entity, &before, &after),
+            None => true,
+        };
+        if !moved {
+            return;
+        }
+        if let Some(transform) = world.get_mut::<Transform>(entity) {
+            transform.position = after.position;
+        }
+    }
+    if let Some(stored) = world.get_mut::<KinematicBody>(entity) {
+        *stored = body;
+    }
+}
+
+/// Move the body's proxy from `before` to `after`, carrying its riders and
+/// pushing other characters out of the way
+///
+/// Returns false, leaving the proxy and the characters untouched, if a
+/// character could not be pushed without overlapping something solid.
+fn carry_characters(world: &mut World, collision: &mut CollisionWorld, entity: Entity, before: &Transform, after: &Transform) -> bool {
+    let collider = match world.get::<Collider>(entity) {
+        Some(collider) if collider.is_enabled() && !collider.is_trigger => collider.clone(),
+        _ => return true,
+    };
+    let before = world_transform(world, entity, before);
+    let after = world_transform(world, entity, after);
+    let motion = after.position - before.position;
+
+    // Riders are found before the body moves out from under them
+    let characters: Vec<(Entity, Vec3, CharacterController, bool)> = world.query::<(Entity, &Transform, &CharacterController)>()
+        .iter()
+        .filter(|(character, _, controller)| *character != entity && controller.is_enabled())
+        .map(|(character, transform, controller)| {
+            let rides = controller.is_grounded && controller.probe_ground(collision, transform.position)
+                .is_some_and(|ground| ground.entity == entity);
+            (character, transform.position, controller.clone(), rides)
+        })
+        .collect();
+    collision.insert(entity, &collider, after.clone());
+
+    let mut moves = Vec::new();
+    for (character, position, mut controller, rides) in characters {
+        let position = if rides {
+            // Sliding with the body keeps riders out of walls, and snapping to
+            // the ground takes them up or down with it
+            controller.move_and_slide(collision, position, motion)
+        } else {
+            let Some(push) = controller.separation(&collider.shape, &after, position, motion) else {
+                continue;
+            };
+            let pushed = position + push;
+            if controller.is_blocked_at(collision, pushed) {
+                collision.insert(entity, &collider, before);
+                return false;
+            }
+            pushed
+        };
+        moves.push((character, position, controller));
+    }
+
+    for (character, position, controller) in moves {
+        if let (Some(transform), Some(stored)) = world.get_mut_pair::<Transform, CharacterController>(character) {
+            transform.position = position;
+            *stored = controller;
+        }
+    }
+    true
+}
+
+/// World-space transform of an entity whose local transform is `local`
+fn world_transform(world: &World, entity: Entity, local: &Transform) -> Transform {
+    match world.get::<Parent>(entity).and_then(|parent| world.get::<GlobalTransform>(parent.0)) {
+        Some(parent) => parent.mul_transform(local).transform().clone(),
+        None => local.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::collision::fixtures::floored_world;
+    use crate::ecs::components::{ColliderShape, PathMode};
+
+    const TICK: f32 = 1.0 / 60.0;
+
+    fn spawn_character(world: &mut World, position: Vec3) -> Entity {
+        world.spawn()
+            .with(Transform::new(position))
+            .with(CharacterController::default())
+            .build()
+    }
+
+    fn run(world: &mut World, ticks: usize) {
+        for _ in 0..ticks {
+            step_kinematic_bodies(world, TICK);
+        }
+    }
+
+    fn position(world: &World, entity: Entity) -> Vec3 {
+        world.get::<Transform>(entity).unwrap().position
+    }
+
+    #[test]
+    fn test_paths_follow_their_mode() {
+        let waypoints = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0)];
+        let mut once = KinematicBody::new(waypoints.clone(), 1.0).with_mode(PathMode::Once);
+        let mut ping_pong = KinematicBody::new(waypoints.clone(), 1.0);
+        let mut looping = KinematicBody::new(waypoints, 1.0).with_mode(PathMode::Loop);
+
+        // 2.5 units along the path, in small and large steps
+        let mut position = Vec3::ZERO;
+        for _ in 0..5 {
+            position = once.advance(position, 0.5);
+        }
+        assert!(position.abs_diff_eq(Vec3::new(1.0, 0.0, 1.0), 1e-5) && !once.is_moving);
+        let turned = ping_pong.advance(Vec3::ZERO, 2.5);
+        assert!(turned.abs_diff_eq(Vec3::new(1.0, 0.0, 0.5), 1e-5) && ping_pong.reversing);
+        let wrapped = looping.advance(Vec3::ZERO, 2.5);
+        let back = Vec3::new(1.0, 0.0, 1.0) * (1.0 - 0.5 / 2f32.sqrt());
+        assert!(wrapped.abs_diff_eq(back, 1e-5), "looped to {:?}", wrapped);
+
+        // Waiting at waypoints
+        let mut paused = KinematicBody::new(vec![Vec3::ZERO, Vec3::X], 1.0).with_pause(0.5);
+        let arrived = paused.advance(Vec3::ZERO, 1.5);
+        assert_eq!(arrived, Vec3::ZERO);
+        assert_eq!(paused.advance(arrived, 0.3), Vec3::ZERO);
+        assert_eq!(paused.advance(arrived, 0.3), Vec3::ZERO);
+        assert!(paused.advance(arrived, 0.5).abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn test_move_to_turns_around_and_stops() {
+        let mut door = KinematicBody::new(vec![Vec3::ZERO, Vec3::X * 2.0], 1.0).with_mode(PathMode::Once).stopped();
+        assert_eq!(door.advance(Vec3::ZERO, 1.0), Vec3::ZERO);
+
+        door.move_to(1);
+        let half_open = door.advance(Vec3::ZERO, 1.0);
+        assert!(half_open.abs_diff_eq(Vec3::X, 1e-5));
+
+        // Closing again from halfway comes straight back
+        door.move_to(0);
+        let closed = door.advance(half_open, 5.0);
+        assert_eq!(closed, Vec3::ZERO);
+        assert!(!door.is_moving);
+
+        door.move_to(1);
+        assert!(door.advance(closed, 5.0).abs_diff_eq(Vec3::X * 2.0, 1e-5) && !door.is_moving);
+    }
+
+    #[test]
+    fn test_platforms_carry_riders() {
+        let mut world = floored_world();
+        let platform = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 0.5, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(2.0, 0.2, 2.0) }))
+            .with(KinematicBody::new(vec![Vec3::new(0.0, 0.5, 0.0), Vec3::new(3.0, 1.5, 0.0)], 1.0).with_mode(PathMode::Once))
+            .build();
+        let rider = spawn_character(&mut world, Vec3::new(0.5, 0.6 + CharacterController::default().foot_offset, 0.0));
+        let start = position(&world, rider);
+
+        run(&mut world, 240);
+        let end = position(&world, platform);
+        assert!(end.abs_diff_eq(Vec3::new(3.0, 1.5, 0.0), 1e-4), "platform at {:?}", end);
+        let carried = position(&world, rider);
+        assert!(carried.abs_diff_eq(start + Vec3::new(3.0, 1.0, 0.0), 0.01), "rider at {:?}", carried);
+        assert!(world.get::<CharacterController>(rider).unwrap().is_grounded);
+    }
+
+    #[test]
+    fn test_doors_push_characters_or_wait() {
+        let mut world = floored_world();
+        world.spawn()
+            .with(Transform::new(Vec3::new(3.0, 1.5, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(0.2, 3.0, 4.0) }))
+            .build();
+        let door = world.spawn()
+            .with(Transform::new(Vec3::new(-1.0, 1.0, 0.0)))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::new(0.2, 2.0, 2.0) }))
+            .with(KinematicBody::new(vec![Vec3::new(-1.0, 1.0, 0.0), Vec3::new(2.5, 1.0, 0.0)], 2.0).with_mode(PathMode::Once))
+            .build();
+        let character = spawn_character(&mut world, Vec3::new(0.0, CharacterController::default().foot_offset, 0.0));
+
+        // The door sweeps the character along until it is pinned against the wall
+        run(&mut world, 120);
+        let pinned = position(&world, character);
+        let radius = CharacterController::default().radius;
+        assert!(pinned.x > 2.9 - radius - 0.05 && pinned.x < 2.9 - radius, "character at {:?}", pinned);
+        let stopped = position(&world, door);
+        assert!((stopped.x + 0.1 + radius - pinned.x).abs() < 0.02, "door at {:?}", stopped);
+        assert!(world.get::<KinematicBody>(door).unwrap().is_moving);
+
+        // Once the character steps aside the door carries on
+        world.get_mut::<Transform>(character).unwrap().position.z = 3.0;
+        run(&mut world, 60);
+        assert!(position(&world, door).abs_diff_eq(Vec3::new(2.5, 1.0, 0.0), 1e-4));
+    }
+}
@@ -9,12 +9,21 @@
 //! - Efficient queries for entities with specific component combinations
 //! - Easy parallelization
 
+pub mod collision;
+pub mod commands;
 pub mod component;
 pub mod components;
 pub mod entity;
+pub mod event;
+pub mod gravity;
+pub mod kinematic;
 pub mod pathfinding;
+pub mod physics;
 pub mod query;
+pub mod reflect;
 pub mod resource;
+pub mod schedule;
+pub mod snapshot;
 pub mod world;
 
 /// Type alias for component type identification
@@ -22,7 +31,13 @@ pub type ComponentTypeId = std::any::TypeId;
 
 // Re-export core ECS types
 pub use entity::{Entity, EntityManager};
+pub use event::{EventReader, EntitySpawned, EntityDespawned, TriggerEntered, TriggerStayed, TriggerExited, LevelReloaded};
+pub use collision::CollisionWorld;
+pub use gravity::{Gravity, GravityField};
 pub use component::{Component, ComponentStorage, ComponentManager};
+pub use query::With;
+pub use resource::{Resource, Time, FixedTime};
+pub use schedule::{Schedule, Stage};
 pub use world::World;
 
 // Re-export all components from the new module structure
@@ -1,15 +1,52 @@
 //! A* Pathfinding algorithms for ECS entities
+//!
+//! Paths are searched on the map's grid in eight directions. Diagonal steps
+//! cost √2 and may not cut the corner of a blocked cell. The grid path is
+//! then string-pulled: waypoints are dropped wherever the next one further
+//! along can be reached in a straight line, leaving only the turns.
 
 use macroquad::prelude::*;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::cmp::Ordering;
+use std::f32::consts::SQRT_2;
 use crate::game::map::Map;
 use crate::ecs::{World, Collider};
 
-/// A* pathfinding service that can be shared across systems
+/// Straight and diagonal steps between neighbouring cells, with their costs
+const STEPS: [((i32, i32), f32); 8] = [
+    ((0, -1), 1.0), ((0, 1), 1.0), ((-1, 0), 1.0), ((1, 0), 1.0),
+    ((-1, -1), SQRT_2), ((1, -1), SQRT_2), ((-1, 1), SQRT_2), ((1, 1), SQRT_2),
+];
+
+/// Estimate of the remaining cost to the goal used to guide the search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    /// Exact cost of eight-directional moves on an open grid
+    Octile,
+    /// Straight-line distance, which explores more cells than `Octile`
+    // The test bot searches with the default; only the tests pick this
+    #[allow(dead_code)]
+    Euclidean,
+}
+
+impl Heuristic {
+    /// Estimated cost between two cells
+    pub fn estimate(&self, a: (i32, i32), b: (i32, i32)) -> f32 {
+        let dx = (a.0 - b.0).abs() as f32;
+        let dy = (a.1 - b.1).abs() as f32;
+        match self {
+            Heuristic::Octile => dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy),
+            Heuristic::Euclidean => (dx * dx + dy * dy).sqrt(),
+        }
+    }
+}
+
+/// A* pathfinding over a map, typically the world's `Map` resource
 #[derive(Debug)]
-pub struct PathfindingAlgorithms {
-    pub map: Map,
+pub struct PathfindingAlgorithms<'m> {
+    pub map: &'m Map,
+    pub heuristic: Heuristic,
+    pub clearance: f32,  // Room kept between smoothed paths and blocked cells, in world units
 }
 
 /// Node used in A* pathfinding
@@ -45,107 +82,71 @@ pub struct PathfindingResult {
     pub found: bool,
 }
 
-impl PathfindingAlgorithms {
-    pub fn new(map: Map) -> Self {
-        Self { map }
+impl PathfindingResult {
+    fn not_found(explored_nodes: Vec<(i32, i32)>) -> Self {
+        Self {
+            path: Vec::new(),
+            explored_nodes,
+            found: false,
+        }
     }
+}
 
-    /// Find path using A* algorithm with ECS entity collision checking
-    pub fn find_path_with_ecs(&self, start: Vec2, goal: Vec2, world: &World) -> PathfindingResult {
-        let start_grid = self.map.world_to_grid(start.x, start.y);
-        let goal_grid = self.map.world_to_grid(goal.x, goal.y);
+/// Which grid cells can't be walked through, checked once per cell and search
+struct BlockedCells<F: Fn(i32, i32) -> bool> {
+    width: i32,
+    height: i32,
+    known: Vec<Option<bool>>,
+    check: F,
+}
 
-        // Check if start or goal are blocked by static map or enabled ECS entities
-        if self.is_position_blocked(start_grid.0, start_grid.1, world) || 
-           self.is_position_blocked(goal_grid.0, goal_grid.1, world) {
-            return PathfindingResult {
-                path: Vec::new(),
-                explored_nodes: Vec::new(),
-                found: false,
-            };
+impl<F: Fn(i32, i32) -> bool> BlockedCells<F> {
+    fn new(map: &Map, check: F) -> Self {
+        Self {
+            width: map.width as i32,
+            height: map.height as i32,
+            known: vec![None; map.width * map.height],
+            check,
         }
+    }
 
-        let mut open_set = BinaryHeap::new();
-        let mut closed_set = HashSet::new();
-        let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
-        let mut g_score: HashMap<(i32, i32), f32> = HashMap::new();
-        let mut explored_nodes = Vec::new();
-
-        // Initialize start node
-        let start_node = AStarNode {
-            position: start_grid,
-            g_cost: 0.0,
-            h_cost: self.heuristic(start_grid, goal_grid),
-            f_cost: self.heuristic(start_grid, goal_grid),
-            parent: None,
-        };
-
-        open_set.push(start_node);
-        g_score.insert(start_grid, 0.0);
-
-        while let Some(current) = open_set.pop() {
-            let current_pos = current.position;
-
-            // Add to explored nodes for visualization
-            explored_nodes.push(current_pos);
-
-            // Check if we reached the goal
-            if current_pos == goal_grid {
-                let path = self.reconstruct_path(&came_from, current_pos, start, goal);
-                return PathfindingResult {
-                    path,
-                    explored_nodes,
-                    found: true,
-                };
-            }
-
-            closed_set.insert(current_pos);
-
-            // Check all neighbors (4-directional movement only)
-            for neighbor_pos in self.get_neighbors(current_pos) {
-                if closed_set.contains(&neighbor_pos) {
-                    continue;
-                }
-
-                // Skip if neighbor is blocked by static map or enabled ECS entities
-                if self.is_position_blocked(neighbor_pos.0, neighbor_pos.1, world) {
-                    continue;
-                }
-
-                // Calculate movement cost (4-directional only - all moves cost 1.0)
-                let movement_cost = 1.0; // All moves are straight (no diagonals)
-
-                let tentative_g_score = current.g_cost + movement_cost;
-
-                // Check if this path to neighbor is better
-                if let Some(&existing_g) = g_score.get(&neighbor_pos) {
-                    if tentative_g_score >= existing_g {
-                        continue;
-                    }
-                }
+    /// Check if a cell is blocked; everything outside the map is
+    fn contains(&mut self, (x, y): (i32, i32)) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return true;
+        }
+        let index = (y * self.width + x) as usize;
+        let check = &self.check;
+        *self.known[index].get_or_insert_with(|| check(x, y))
+    }
+}
 
-                // This path is the best so far
-                came_from.insert(neighbor_pos, current_pos);
-                g_score.insert(neighbor_pos, tentative_g_score);
+impl<'m> PathfindingAlgorithms<'m> {
+    pub fn new(map: &'m Map) -> Self {
+        Self {
+            map,
+            heuristic: Heuristic::Octile,
+            clearance: 0.25,  // The radius of a default character controller
+        }
+    }
 
-                let neighbor_node = AStarNode {
-                    position: neighbor_pos,
-                    g_cost: tentative_g_score,
-                    h_cost: self.heuristic(neighbor_pos, goal_grid),
-                    f_cost: tentative_g_score + self.heuristic(neighbor_pos, goal_grid),
-                    parent: Some(current_pos),
-                };
+    #[allow(dead_code)]
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
 
-                open_set.push(neighbor_node);
-            }
-        }
+    #[allow(dead_code)]
+    pub fn with_clearance(mut self, clearance: f32) -> Self {
+        self.clearance = clearance;
+        self
+    }
 
-        // No path found
-        PathfindingResult {
-            path: Vec::new(),
-            explored_nodes,
-            found: false,
-        }
+    /// Find path using A* algorithm with ECS entity collision checking
+    pub fn find_path_with_ecs(&self, start: Vec2, goal: Vec2, world: &World) -> PathfindingResult {
+        // Cells are blocked by the static map or enabled ECS entities
+        let blocked = BlockedCells::new(self.map, |x, y| self.is_position_blocked(x, y, world));
+        self.search(start, goal, blocked)
     }
 
     /// Check if a position is blocked by static map or enabled ECS entities
@@ -158,22 +159,24 @@ impl PathfindingAlgorithms {
         // Then check ECS entities with colliders
         // Convert grid position to world position for collision check
         let (world_x, world_z) = self.map.grid_to_world(x, y);
-        
+
         Collider::check_grid_collision(world, world_x, world_z)
     }
 
     /// Find path using A* algorithm (legacy version, doesn't check ECS entities)
     pub fn find_path(&self, start: Vec2, goal: Vec2) -> PathfindingResult {
+        let blocked = BlockedCells::new(self.map, |x, y| self.map.is_wall(x, y));
+        self.search(start, goal, blocked)
+    }
+
+    /// Search the grid from `start` to `goal` and smooth the path found
+    fn search<F: Fn(i32, i32) -> bool>(&self, start: Vec2, goal: Vec2, mut blocked: BlockedCells<F>) -> PathfindingResult {
         let start_grid = self.map.world_to_grid(start.x, start.y);
         let goal_grid = self.map.world_to_grid(goal.x, goal.y);
 
-        // Check if start or goal are in walls
-        if self.map.is_wall(start_grid.0, start_grid.1) || self.map.is_wall(goal_grid.0, goal_grid.1) {
-            return PathfindingResult {
-                path: Vec::new(),
-                explored_nodes: Vec::new(),
-                found: false,
-            };
+        // Check if start or goal are blocked
+        if blocked.contains(start_grid) || blocked.contains(goal_grid) {
+            return PathfindingResult::not_found(Vec::new());
         }
 
         let mut open_set = BinaryHeap::new();
@@ -183,26 +186,30 @@ impl PathfindingAlgorithms {
         let mut explored_nodes = Vec::new();
 
         // Initialize start node
-        let start_node = AStarNode {
+        let start_h = self.heuristic.estimate(start_grid, goal_grid);
+        open_set.push(AStarNode {
             position: start_grid,
             g_cost: 0.0,
-            h_cost: self.heuristic(start_grid, goal_grid),
-            f_cost: self.heuristic(start_grid, goal_grid),
+            h_cost: start_h,
+            f_cost: start_h,
             parent: None,
-        };
-
-        open_set.push(start_node);
+        });
         g_score.insert(start_grid, 0.0);
 
         while let Some(current) = open_set.pop() {
             let current_pos = current.position;
+            // A cell can be queued again with a lower cost; later copies are stale
+            if !closed_set.insert(current_pos) {
+                continue;
+            }
 
             // Add to explored nodes for visualization
             explored_nodes.push(current_pos);
 
             // Check if we reached the goal
             if current_pos == goal_grid {
-                let path = self.reconstruct_path(&came_from, current_pos, start, goal);
+                let cells = self.reconstruct_path(&came_from, current_pos);
+                let path = self.smooth_path(&mut blocked, start, cells, goal);
                 return PathfindingResult {
                     path,
                     explored_nodes,
@@ -210,22 +217,11 @@ impl PathfindingAlgorithms {
                 };
             }
 
-            closed_set.insert(current_pos);
-
-            // Check all neighbors (4-directional movement only)
-            for neighbor_pos in self.get_neighbors(current_pos) {
+            for (neighbor_pos, movement_cost) in self.get_neighbors(current_pos, &mut blocked) {
                 if closed_set.contains(&neighbor_pos) {
                     continue;
                 }
 
-                // Skip if neighbor is a wall
-                if self.map.is_wall(neighbor_pos.0, neighbor_pos.1) {
-                    continue;
-                }
-
-                // Calculate movement cost (4-directional only - all moves cost 1.0)
-                let movement_cost = 1.0; // All moves are straight (no diagonals)
-
                 let tentative_g_score = current.g_cost + movement_cost;
 
                 // Check if this path to neighbor is better
@@ -239,95 +235,251 @@ impl PathfindingAlgorithms {
                 came_from.insert(neighbor_pos, current_pos);
                 g_score.insert(neighbor_pos, tentative_g_score);
 
-                let neighbor_node = AStarNode {
+                let h_cost = self.heuristic.estimate(neighbor_pos, goal_grid);
+                open_set.push(AStarNode {
                     position: neighbor_pos,
                     g_cost: tentative_g_score,
-                    h_cost: self.heuristic(neighbor_pos, goal_grid),
-                    f_cost: tentative_g_score + self.heuristic(neighbor_pos, goal_grid),
+                    h_cost,
+                    f_cost: tentative_g_score + h_cost,
                     parent: Some(current_pos),
-                };
-
-                open_set.push(neighbor_node);
+                });
             }
         }
 
         // No path found
-        PathfindingResult {
-            path: Vec::new(),
-            explored_nodes,
-            found: false,
-        }
+        PathfindingResult::not_found(explored_nodes)
     }
 
-    /// Get all valid neighbors for a position (4-directional only - no diagonals)
-    fn get_neighbors(&self, pos: (i32, i32)) -> Vec<(i32, i32)> {
-        let mut neighbors = Vec::new();
+    /// Open neighbours of a cell with the cost of stepping to each
+    ///
+    /// A diagonal step is only allowed when both cells beside it are open, so
+    /// paths never squeeze past the corner of a wall.
+    fn get_neighbors<F: Fn(i32, i32) -> bool>(&self, pos: (i32, i32), blocked: &mut BlockedCells<F>) -> Vec<((i32, i32), f32)> {
         let (x, y) = pos;
+        STEPS.iter()
+            .filter(|((dx, dy), _)| {
+                !blocked.contains((x + dx, y + dy))
+                    && (*dx == 0 || *dy == 0 || (!blocked.contains((x + dx, y)) && !blocked.contains((x, y + dy))))
+            })
+            .map(|&((dx, dy), cost)| ((x + dx, y + dy), cost))
+            .collect()
+    }
 
-        // 4-directional movement only (up, down, left, right)
-        let directions = [
-            (0, -1), // Up
-            (0, 1),  // Down
-            (-1, 0), // Left
-            (1, 0),  // Right
-        ];
-
-        for (dx, dy) in directions.iter() {
-            let new_x = x + dx;
-            let new_y = y + dy;
-
-            // Check bounds
-            if new_x >= 0 && new_y >= 0 && 
-               new_x < self.map.width as i32 && new_y < self.map.height as i32 {
-                neighbors.push((new_x, new_y));
-            }
+    /// The cells from the start to `current`, without the start cell
+    fn reconstruct_path(&self, came_from: &HashMap<(i32, i32), (i32, i32)>, mut current: (i32, i32)) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        while let Some(&parent) = came_from.get(&current) {
+            cells.push(current);
+            current = parent;
         }
+        cells.reverse();
+        cells
+    }
+
+    /// Turn the cells of a grid path into the fewest waypoints from `start`
+    /// to `goal`
+    ///
+    /// From each waypoint the path heads straight for the furthest point
+    /// along the grid path it can see, with `clearance` to spare. The start
+    /// itself is left out since the entity is already there.
+    fn smooth_path<F: Fn(i32, i32) -> bool>(&self, blocked: &mut BlockedCells<F>, start: Vec2, cells: Vec<(i32, i32)>, goal: Vec2) -> Vec<Vec2> {
+        let mut points: Vec<Vec2> = cells.into_iter()
+            .map(|(x, y)| Vec2::from(self.map.grid_to_world(x, y)))
+            .collect();
+        // The goal replaces the centre of its own cell
+        points.pop();
+        points.push(goal);
 
-        neighbors
+        let mut path = Vec::new();
+        let mut from = start;
+        let mut next = 0;
+        while next < points.len() {
+            // The next point is always reachable along the grid path
+            let furthest = (next + 1..points.len())
+                .rev()
+                .find(|&index| self.has_line_of_sight(blocked, from, points[index]))
+                .unwrap_or(next);
+            from = points[furthest];
+            path.push(from);
+            next = furthest + 1;
+        }
+        path
     }
 
-    /// Heuristic function (Manhattan distance for 4-directional movement)
-    fn heuristic(&self, a: (i32, i32), b: (i32, i32)) -> f32 {
-        let dx = (a.0 - b.0).abs() as f32;
-        let dy = (a.1 - b.1).abs() as f32;
-        
-        // Manhattan distance (perfect for 4-directional movement)
-        dx + dy
+    /// Check if a body `clearance` wide can move in a straight line between
+    /// two world positions without entering a blocked cell
+    fn has_line_of_sight<F: Fn(i32, i32) -> bool>(&self, blocked: &mut BlockedCells<F>, from: Vec2, to: Vec2) -> bool {
+        let side = (to - from).perp().normalize_or_zero() * self.clearance;
+        [Vec2::ZERO, side, -side]
+            .into_iter()
+            .all(|offset| self.is_segment_clear(blocked, from + offset, to + offset))
     }
 
-    /// Reconstruct the path from the came_from map
-    fn reconstruct_path(&self, came_from: &HashMap<(i32, i32), (i32, i32)>, 
-                       mut current: (i32, i32), start: Vec2, goal: Vec2) -> Vec<Vec2> {
-        let mut path = Vec::new();
-        
-        // Add the goal position first
-        path.push(goal);
+    /// Walk the cells a segment passes through, in grid space, checking each
+    /// is open
+    ///
+    /// A segment through the exact corner of four cells needs both cells
+    /// beside the corner open, like a diagonal step.
+    fn is_segment_clear<F: Fn(i32, i32) -> bool>(&self, blocked: &mut BlockedCells<F>, from: Vec2, to: Vec2) -> bool {
+        let cell_size = Vec2::new(
+            (self.map.world_max_x - self.map.world_min_x) / self.map.width as f32,
+            (self.map.world_max_z - self.map.world_min_z) / self.map.height as f32,
+        );
+        let origin = Vec2::new(self.map.world_min_x, self.map.world_min_z);
+        let a = (from - origin) / cell_size;
+        let b = (to - origin) / cell_size;
+        let delta = b - a;
+
+        let step = |d: f32| if d > 0.0 { 1 } else if d < 0.0 { -1 } else { 0 };
+        let (step_x, step_y) = (step(delta.x), step(delta.y));
+        let mut cell = (a.x.floor() as i32, a.y.floor() as i32);
+        let end = (b.x.floor() as i32, b.y.floor() as i32);
+
+        // Fraction of the segment to the next vertical and horizontal cell
+        // edges, and between successive edges
+        let first_edge = |start: f32, cell: i32, step: i32, d: f32| match step {
+            1 => (cell as f32 + 1.0 - start) / d,
+            -1 => (start - cell as f32) / -d,
+            _ => f32::INFINITY,
+        };
+        let mut t_max = Vec2::new(first_edge(a.x, cell.0, step_x, delta.x), first_edge(a.y, cell.1, step_y, delta.y));
+        let t_delta = Vec2::new(1.0 / delta.x.abs(), 1.0 / delta.y.abs());
 
-        // Trace back through the path
-        while let Some(&parent) = came_from.get(&current) {
-            // Convert grid position to world position (center of grid cell)
-            let (world_x, world_z) = self.map.grid_to_world(current.0, current.1);
-            let world_pos = Vec2::new(world_x, world_z);
-            path.push(world_pos);
-            current = parent;
+        if blocked.contains(cell) {
+            return false;
         }
+        let crossings = (end.0 - cell.0).abs() + (end.1 - cell.1).abs();
+        for _ in 0..crossings {
+            if cell == end {
+                break;
+            }
+            if (t_max.x - t_max.y).abs() < 1e-6 {
+                if blocked.contains((cell.0 + step_x, cell.1)) || blocked.contains((cell.0, cell.1 + step_y)) {
+                    return false;
+                }
+                cell = (cell.0 + step_x, cell.1 + step_y);
+                t_max += t_delta;
+            } else if t_max.x < t_max.y {
+                cell.0 += step_x;
+                t_max.x += t_delta.x;
+            } else {
+                cell.1 += step_y;
+                t_max.y += t_delta.y;
+            }
+            if blocked.contains(cell) {
+                return false;
+            }
+        }
+        true
+    }
+}
 
-        // Add start position
-        path.push(start);
-
-        // Reverse to get path from start to goal
-        path.reverse();
-        
-        // Remove the first element (start position) since we're already there
-        if !path.is_empty() {
-            path.remove(0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A map from rows of text, `#` for walls, one world unit per cell
+    fn map_from_rows(rows: &[&str]) -> Map {
+        let tiles: Vec<Vec<u8>> = rows.iter()
+            .map(|row| row.chars().map(|c| if c == '#' { 1 } else { 0 }).collect())
+            .collect();
+        Map {
+            width: tiles[0].len(),
+            height: tiles.len(),
+            world_min_x: 0.0,
+            world_min_z: 0.0,
+            world_max_x: tiles[0].len() as f32,
+            world_max_z: tiles.len() as f32,
+            tiles,
         }
+    }
 
-        path
+    fn center(x: i32, y: i32) -> Vec2 {
+        Vec2::new(x as f32 + 0.5, y as f32 + 0.5)
     }
 
-    /// Update the internal map reference
-    pub fn update_map(&mut self, map: Map) {
-        self.map = map;
+    #[test]
+    fn test_heuristics_never_overestimate() {
+        assert_eq!(Heuristic::Octile.estimate((0, 0), (3, 0)), 3.0);
+        assert!((Heuristic::Octile.estimate((0, 0), (3, 3)) - 3.0 * SQRT_2).abs() < 1e-5);
+        assert!((Heuristic::Euclidean.estimate((0, 0), (3, 4)) - 5.0).abs() < 1e-5);
+        assert!(Heuristic::Euclidean.estimate((0, 0), (3, 1)) < Heuristic::Octile.estimate((0, 0), (3, 1)));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_open_ground_is_crossed_in_a_straight_line() {
+        let map = map_from_rows(&[
+            "......",
+            "......",
+            "......",
+            "......",
+            "......",
+        ]);
+        let result = PathfindingAlgorithms::new(&map).find_path(center(0, 0), center(4, 4));
+        assert!(result.found);
+        assert_eq!(result.path, vec![center(4, 4)]);
+
+        // Octile search heads straight for the goal while the euclidean one wanders more
+        let euclidean = PathfindingAlgorithms::new(&map).with_heuristic(Heuristic::Euclidean).find_path(center(0, 0), center(4, 4));
+        assert_eq!(euclidean.path, vec![center(4, 4)]);
+        assert_eq!(result.explored_nodes.len(), 5);
+        assert!(euclidean.explored_nodes.len() >= result.explored_nodes.len());
+    }
+
+    #[test]
+    fn test_diagonals_do_not_cut_corners() {
+        // The only gap is a diagonal squeeze between two wall corners
+        let map = map_from_rows(&[
+            "...#...",
+            "...#...",
+            "..#....",
+            ".......",
+        ]);
+        let pathfinder = PathfindingAlgorithms::new(&map).with_clearance(0.0);
+        let mut blocked = BlockedCells::new(&map, |x, y| map.is_wall(x, y));
+        let neighbors: Vec<(i32, i32)> = pathfinder.get_neighbors((2, 1), &mut blocked).into_iter().map(|(cell, _)| cell).collect();
+        assert!(!neighbors.contains(&(3, 2)) && !neighbors.contains(&(3, 0)));
+        assert!(neighbors.contains(&(1, 0)));
+        assert!(!pathfinder.has_line_of_sight(&mut blocked, Vec2::new(2.0, 1.0), Vec2::new(4.0, 3.0)));
+
+        // So the path goes round below the lower wall
+        let result = pathfinder.find_path(center(1, 1), center(5, 1));
+        assert!(result.found);
+        assert!(result.path.iter().all(|point| !map.is_wall_world(point.x, point.y)));
+        assert!(result.path.iter().any(|point| point.y > 3.0), "path {:?}", result.path);
+    }
+
+    #[test]
+    fn test_paths_are_smoothed_to_their_turns() {
+        let map = map_from_rows(&[
+            "........",
+            ".######.",
+            ".#......",
+            ".#......",
+            "........",
+        ]);
+        let start = center(7, 0);
+        let goal = Vec2::new(4.2, 3.3);
+        let pathfinder = PathfindingAlgorithms::new(&map);
+        let result = pathfinder.find_path(start, goal);
+        assert!(result.found);
+        assert_eq!(result.path.last(), Some(&goal));
+        assert!(result.path.len() <= 3, "path {:?}", result.path);
+
+        // Every leg keeps clear of the walls
+        let mut blocked = BlockedCells::new(&map, |x, y| map.is_wall(x, y));
+        let mut from = start;
+        for &point in &result.path {
+            assert!(pathfinder.has_line_of_sight(&mut blocked, from, point), "{:?} to {:?}", from, point);
+            from = point;
+        }
+
+        // Walled off goals have no path
+        let closed = map_from_rows(&[
+            "..#..",
+            "..#..",
+            "..#..",
+        ]);
+        assert!(!PathfindingAlgorithms::new(&closed).find_path(center(0, 1), center(4, 1)).found);
+    }
+}
@@ -0,0 +1,414 @@
+//! Rigid body simulation for dynamic colliders
+//!
+//! Bodies are integrated one at a time against the static colliders in the
+//! `CollisionWorld` and the capsules of character controllers. Characters
+//! push bodies out of their way as if they had infinite mass, which is what
+//! lets the player kick a ball around; bodies do not collide with each other.
+//! Each body falls under the gravity of the `GravityField` where it is.
+
+use macroquad::prelude::*;
+use crate::ecs::{Entity, World, CollisionWorld, GravityField};
+use crate::ecs::components::collision::ContactPoint;
+use crate::ecs::components::{
+    Collider, ColliderShape, ColliderMaterial, CharacterController, RigidBody, Transform, PreviousTransform, Velocity,
+};
+
+const SOLVER_ITERATIONS: usize = 4;
+const MAX_SUBSTEPS: usize = 8;
+const PENETRATION_SLOP: f32 = 0.005;   // Overlap left alone so resting contacts persist
+const POSITION_CORRECTION: f32 = 0.8;  // Fraction of the remaining overlap removed per step
+const RESTING_SPEED: f32 = 0.3;        // Impacts slower than this do not bounce
+const SLEEP_SPEED: f32 = 0.05;
+const SLEEP_DELAY: f32 = 0.5;          // Seconds a body must move slower than `SLEEP_SPEED`
+
+/// A character capsule that pushes rigid bodies this step
+struct Pusher {
+    entity: Entity,
+    shape: ColliderShape,
+    transform: Transform,
+    layer: u32,
+    mask: u32,
+    material: ColliderMaterial,
+    velocity: Vec3,
+}
+
+/// A contact being resolved, relative to the body's centre of mass
+struct Contact {
+    offset: Vec3,             // From the body's centre to the contact point
+    normal: Vec3,             // Pushes the body out of the obstacle
+    depth: f32,
+    obstacle_velocity: Vec3,
+    friction: f32,
+    restitution: f32,
+    bounce_speed: f32,        // Separating speed the solver aims for along the normal
+    normal_impulse: f32,      // Accumulated over the solver iterations
+    friction_impulse: Vec3,
+}
+
+/// Advance every enabled rigid body by `delta_time` under the world's
+/// gravity and gravity volumes
+///
+/// Expects the `CollisionWorld` to hold the static colliders; in a world
+/// without one, bodies fall freely and only characters push them.
+pub fn step_rigid_bodies(world: &mut World, delta_time: f32) {
+    if delta_time <= 0.0 {
+        return;
+    }
+    let pushers = collect_pushers(world, delta_time);
+    let field = GravityField::from_world(world);
+
+    let stepped = world.resource_scope::<CollisionWorld, _>(|world, collision| {
+        step_bodies(world, Some(collision), &pushers, &field, delta_time);
+    });
+    if stepped.is_none() {
+        step_bodies(world, None, &pushers, &field, delta_time);
+    }
+}
+
+fn step_bodies(world: &mut World, collision: Option<&CollisionWorld>, pushers: &[Pusher], field: &GravityField, delta_time: f32) {
+    for (entity, transform, velocity, body, collider) in world.query_mut::<(Entity, &mut Transform, &mut Velocity, &mut RigidBody, &Collider)>() {
+        if !body.is_enabled() || !collider.is_enabled() || collider.is_static || collider.is_trigger {
+            continue;
+        }
+        let gravity = field.at(transform.position) * body.gravity_scale;
+        step_body(collision, pushers, entity, transform, velocity, body, collider, gravity, delta_time);
+    }
+}
+
+/// Gather enabled characters with their capsules and this step's velocity
+fn collect_pushers(world: &World, delta_time: f32) -> Vec<Pusher> {
+    world.query::<(Entity, &Transform, &CharacterController, &Collider, Option<&PreviousTransform>)>()
+        .into_iter()
+        .filter(|(_, _, controller, collider, _)| controller.is_enabled() && collider.is_enabled())
+        .map(|(entity, transform, controller, collider, previous)| {
+            let (center, height) = controller.capsule(transform.position);
+            let velocity = previous
+                .map(|previous| (transform.position - previous.0.position) / delta_time)
+                .unwrap_or(Vec3::ZERO);
+            Pusher {
+                entity,
+                shape: ColliderShape::Capsule { height, radius: controller.radius },
+                transform: Transform::new(center),
+                layer: collider.layer,
+                mask: collider.mask,
+                material: collider.material.clone(),
+                velocity,
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn step_body(
+    collision: Option<&CollisionWorld>,
+    pushers: &[Pusher],
+    entity: Entity,
+    transform: &mut Transform,
+    velocity: &mut Velocity,
+    body: &mut RigidBody,
+    collider: &Collider,
+    gravity: Vec3,
+    delta_time: f32,
+) {
+    let inverse_mass = body.inverse_mass();
+    let inverse_inertia = body.inverse_inertia(&collider.shape);
+    velocity.linear += body.pending_impulse * inverse_mass;
+    velocity.angular += body.pending_angular_impulse * inverse_inertia;
+    body.pending_impulse = Vec3::ZERO;
+    body.pending_angular_impulse = Vec3::ZERO;
+
+    if body.is_sleeping {
+        // Stay asleep while resting on something and nothing is pushing
+        let contacts = find_contacts(collision, pushers, entity, transform, collider);
+        if !contacts.is_empty() && contacts.iter().all(|contact| contact.obstacle_velocity == Vec3::ZERO) {
+            return;
+        }
+        body.wake();
+    }
+
+    // Split fast moves so a body cannot pass through thin walls in one step
+    let travel = (velocity.linear.length() + gravity.length() * delta_time) * delta_time;
+    let substeps = ((travel / (smallest_extent(&collider.shape) * 0.5)).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+    let step = delta_time / substeps as f32;
+
+    for _ in 0..substeps {
+        // Bounces are measured before gravity so the step spent sinking into
+        // the floor does not speed up the rebound
+        let mut contacts = find_contacts(collision, pushers, entity, transform, collider);
+        set_bounce_speeds(&mut contacts, velocity);
+
+        velocity.linear += gravity * step;
+        velocity.linear *= (1.0 - body.linear_damping * step).max(0.0);
+        velocity.angular *= (1.0 - body.angular_damping * step).max(0.0);
+        solve_velocities(&mut contacts, velocity, inverse_mass, inverse_inertia);
+
+        transform.position += velocity.linear * step;
+        if velocity.angular != Vec3::ZERO {
+            let orientation = (Quat::from_scaled_axis(velocity.angular * step) * transform.orientation()).normalize();
+            let (x, y, z) = orientation.to_euler(EulerRot::XYZ);
+            transform.rotation = Vec3::new(x, y, z);
+        }
+        transform.position += position_correction(&contacts);
+    }
+
+    // Spin is compared by how fast the body's surface moves, so small balls
+    // rolling to a stop can sleep
+    let surface_speed = velocity.angular.length() * smallest_extent(&collider.shape);
+    if velocity.linear.length() < SLEEP_SPEED && surface_speed < SLEEP_SPEED {
+        body.sleep_timer += delta_time;
+        if body.sleep_timer >= SLEEP_DELAY {
+            body.is_sleeping = true;
+            velocity.linear = Vec3::ZERO;
+            velocity.angular = Vec3::ZERO;
+        }
+    } else {
+        body.sleep_timer = 0.0;
+    }
+}
+
+/// Contacts between the body and the static colliders and characters it overlaps
+fn find_contacts(collision: Option<&CollisionWorld>, pushers: &[Pusher], entity: Entity, transform: &Transform, collider: &Collider) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut add = |points: Vec<ContactPoint>, material: &ColliderMaterial, obstacle_velocity: Vec3| {
+        contacts.extend(points.into_iter().map(|contact| Contact {
+            offset: contact.point - transform.position,
+            normal: contact.normal,
+            depth: contact.depth,
+            obstacle_velocity,
+            friction: (collider.material.friction * material.friction).sqrt(),
+            restitution: collider.material.restitution.max(material.restitution),
+            bounce_speed: 0.0,
+            normal_impulse: 0.0,
+            friction_impulse: Vec3::ZERO,
+        }));
+    };
+
+    if let Some(collision) = collision {
+        for other in collision.overlap_transformed(&collider.shape, transform) {
+            if other == entity {
+                continue;
+            }
+            let proxy = collision.get(other).expect("overlapping entity has a proxy");
+            if !proxy.is_static || !proxy.is_solid() || !proxy.interacts_with(collider) {
+                continue;
+            }
+            add(collider.shape.contacts_with(transform, &proxy.shape, &proxy.transform), &proxy.material, Vec3::ZERO);
+        }
+    }
+
+    for pusher in pushers {
+        let interacts = collider.layer & pusher.mask != 0 && pusher.layer & collider.mask != 0;
+        if pusher.entity == entity || !interacts {
+            continue;
+        }
+        add(collider.shape.contacts_with(transform, &pusher.shape, &pusher.transform), &pusher.material, pusher.velocity);
+    }
+    contacts
+}
+
+fn relative_velocity(velocity: &Velocity, contact: &Contact) -> Vec3 {
+    velocity.linear + velocity.angular.cross(contact.offset) - contact.obstacle_velocity
+}
+
+/// Aim fast impacts at a rebound scaled by the contact's restitution
+fn set_bounce_speeds(contacts: &mut [Contact], velocity: &Velocity) {
+    for contact in contacts.iter_mut() {
+        let closing_speed = -relative_velocity(velocity, contact).dot(contact.normal);
+        if closing_speed > RESTING_SPEED {
+            contact.bounce_speed = closing_speed * contact.restitution;
+        }
+    }
+}
+
+/// Sequential impulses: stop each contact from closing, bounce fast impacts
+/// and apply friction up to the Coulomb limit
+fn solve_velocities(contacts: &mut [Contact], velocity: &mut Velocity, inverse_mass: f32, inverse_inertia: f32) {
+    let effective_mass = |offset: Vec3, direction: Vec3| {
+        1.0 / (inverse_mass + inverse_inertia * offset.cross(direction).length_squared())
+    };
+    let apply = |velocity: &mut Velocity, offset: Vec3, impulse: Vec3| {
+        velocity.linear += impulse * inverse_mass;
+        velocity.angular += offset.cross(impulse) * inverse_inertia;
+    };
+
+    for _ in 0..SOLVER_ITERATIONS {
+        for contact in contacts.iter_mut() {
+            let normal_speed = relative_velocity(velocity, contact).dot(contact.normal);
+            let impulse = (contact.bounce_speed - normal_speed) * effective_mass(contact.offset, contact.normal);
+            let accumulated = (contact.normal_impulse + impulse).max(0.0);
+            apply(velocity, contact.offset, contact.normal * (accumulated - contact.normal_impulse));
+            contact.normal_impulse = accumulated;
+
+            let sliding = relative_velocity(velocity, contact);
+            let sliding = sliding - contact.normal * sliding.dot(contact.normal);
+            let speed = sliding.length();
+            if speed < 1e-6 {
+                continue;
+            }
+            let direction = sliding / speed;
+            let impulse = -direction * speed * effective_mass(contact.offset, direction);
+            let accumulated = (contact.friction_impulse + impulse).clamp_length_max(contact.friction * contact.normal_impulse);
+            apply(velocity, contact.offset, accumulated - contact.friction_impulse);
+            contact.friction_impulse = accumulated;
+        }
+    }
+}
+
+/// Move the body out of the obstacles it overlaps, counting contacts that
+/// share a normal, like the corners of a box on a floor, only once
+///
+/// Bouncing contacts are left to separate under their own velocity, since
+/// lifting them as well would add energy to every bounce.
+fn position_correction(contacts: &[Contact]) -> Vec3 {
+    contacts.iter().filter(|contact| contact.bounce_speed == 0.0).fold(Vec3::ZERO, |correction, contact| {
+        let needed = (contact.depth - PENETRATION_SLOP) * POSITION_CORRECTION - correction.dot(contact.normal);
+        if needed > 0.0 {
+            correction + contact.normal * needed
+        } else {
+            correction
+        }
+    })
+}
+
+/// Half the thinnest dimension of a shape
+fn smallest_extent(shape: &ColliderShape) -> f32 {
+    match shape {
+        ColliderShape::Box { size } => size.min_element() * 0.5,
+        ColliderShape::Sphere { radius } | ColliderShape::Capsule { radius, .. } => *radius,
+        ColliderShape::TriMesh { mesh } => mesh.bounds().size().min_element() * 0.5,
+        ColliderShape::ConvexHull { hull } => hull.bounds().size().min_element() * 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Gravity;
+    use crate::ecs::collision::fixtures::floored_world;
+    use crate::ecs::components::GravityVolume;
+
+    fn spawn_ball(world: &mut World, position: Vec3, material: ColliderMaterial) -> Entity {
+        let collider = Collider::dynamic_solid(ColliderShape::Sphere { radius: 0.1 }).with_material(material);
+        world.spawn()
+            .with(Transform::new(position))
+            .with(Velocity::default())
+            .with(RigidBody::from_collider(&collider))
+            .with(collider)
+            .build()
+    }
+
+    fn simulate(world: &mut World, seconds: f32) {
+        for _ in 0..(seconds * 60.0) as usize {
+            step_rigid_bodies(world, 1.0 / 60.0);
+        }
+    }
+
+    #[test]
+    fn test_rigid_body_bounces_then_sleeps_on_the_floor() {
+        let mut world = floored_world();
+        let ball = spawn_ball(&mut world, Vec3::new(0.0, 1.0, 0.0), ColliderMaterial::bouncy());
+
+        // Falls onto the floor and bounces back up
+        let mut lowest = f32::MAX;
+        let mut bounced = false;
+        for _ in 0..60 {
+            simulate(&mut world, 1.0 / 60.0);
+            let height = world.get::<Transform>(ball).unwrap().position.y;
+            if height > lowest + 0.05 {
+                bounced = true;
+                break;
+            }
+            lowest = lowest.min(height);
+        }
+        assert!(bounced);
+        assert!(lowest > 0.0, "ball fell through the floor");
+
+        simulate(&mut world, 6.0);
+        let height = world.get::<Transform>(ball).unwrap().position.y;
+        assert!((height - 0.1).abs() < 0.02, "ball should rest on the floor, at {}", height);
+        assert!(world.get::<RigidBody>(ball).unwrap().is_sleeping);
+    }
+
+    #[test]
+    fn test_rigid_body_impulses_wake_and_friction_stops_sliding() {
+        let mut world = floored_world();
+        let ball = spawn_ball(&mut world, Vec3::new(0.0, 0.1, 0.0), ColliderMaterial::standard());
+        simulate(&mut world, 2.0);
+        assert!(world.get::<RigidBody>(ball).unwrap().is_sleeping);
+
+        let mass = world.get::<RigidBody>(ball).unwrap().mass;
+        world.get_mut::<RigidBody>(ball).unwrap().apply_impulse(Vec3::new(mass * 3.0, 0.0, 0.0));
+        simulate(&mut world, 0.5);
+        let moving = world.get::<Transform>(ball).unwrap().position;
+        assert!(moving.x > 0.5);
+        assert!(world.get::<Velocity>(ball).unwrap().angular.z < 0.0, "friction should set the ball rolling");
+
+        simulate(&mut world, 20.0);
+        assert!(world.get::<RigidBody>(ball).unwrap().is_sleeping);
+        assert!(world.get::<Transform>(ball).unwrap().position.y > 0.08);
+    }
+
+    #[test]
+    fn test_characters_push_rigid_bodies() {
+        let mut world = floored_world();
+        let ball = spawn_ball(&mut world, Vec3::new(0.0, 0.1, 0.0), ColliderMaterial::bouncy());
+        simulate(&mut world, 2.0);
+
+        // A character walking through the ball this step kicks it along
+        let controller = CharacterController::new(1.8, 0.25);
+        let feet = Vec3::new(-0.3, controller.foot_offset, 0.0);
+        world.spawn()
+            .with(Transform::new(feet))
+            .with(PreviousTransform(Transform::new(feet - Vec3::new(0.1, 0.0, 0.0))))
+            .with(Collider::dynamic_solid(ColliderShape::Capsule { height: 1.3, radius: 0.25 }))
+            .with(controller)
+            .build();
+
+        simulate(&mut world, 1.0 / 60.0);
+        let velocity = world.get::<Velocity>(ball).unwrap().linear;
+        assert!(velocity.x > 3.0, "ball should be kicked forward, got {:?}", velocity);
+        assert!(!world.get::<RigidBody>(ball).unwrap().is_sleeping);
+    }
+
+    #[test]
+    fn test_rigid_bodies_follow_local_gravity() {
+        let mut world = floored_world();
+        world.insert_resource(Gravity(Vec3::new(0.0, -6.0, 0.0)));
+        world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 3.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Box { size: Vec3::splat(4.0) }))
+            .with(GravityVolume::zero_g())
+            .build();
+        world.spawn()
+            .with(Transform::new(Vec3::new(10.0, 3.0, 0.0)))
+            .with(Collider::static_trigger(ColliderShape::Box { size: Vec3::splat(4.0) }))
+            .with(GravityVolume::directional(Vec3::new(0.0, 0.0, 4.0)))
+            .build();
+        let floating = spawn_ball(&mut world, Vec3::new(0.0, 3.0, 0.0), ColliderMaterial::standard());
+        world.get_mut::<Velocity>(floating).unwrap().linear = Vec3::new(0.5, 0.0, 0.0);
+        let pulled = spawn_ball(&mut world, Vec3::new(10.0, 3.0, 0.0), ColliderMaterial::standard());
+        let falling = spawn_ball(&mut world, Vec3::new(-5.0, 3.0, 0.0), ColliderMaterial::standard());
+
+        simulate(&mut world, 0.5);
+        let drifted = world.get::<Transform>(floating).unwrap().position;
+        assert!((drifted.y - 3.0).abs() < 1e-4 && drifted.x > 0.2, "floating ball at {:?}", drifted);
+        let sideways = world.get::<Transform>(pulled).unwrap().position;
+        assert!((sideways.y - 3.0).abs() < 1e-4 && sideways.z > 0.35, "pulled ball at {:?}", sideways);
+        // Half a second at the world's gentler gravity
+        let fallen = world.get::<Transform>(falling).unwrap().position;
+        assert!(fallen.y < 2.4 && fallen.y > 2.2, "falling ball at {:?}", fallen);
+    }
+
+    #[test]
+    fn test_rigid_bodies_fall_freely_without_a_collision_world() {
+        let mut world = World::new();
+        let ball = spawn_ball(&mut world, Vec3::new(0.0, 1.0, 0.0), ColliderMaterial::standard());
+
+        // Nothing to land on, so the ball keeps falling through where a floor would be
+        simulate(&mut world, 1.0);
+        let fallen = world.get::<Transform>(ball).unwrap().position;
+        let speed = world.get::<Velocity>(ball).unwrap().linear.y;
+        assert!(fallen.y < -4.0, "ball at {:?}", fallen);
+        assert!(speed < Gravity::DEFAULT.y * 0.85, "ball falling at {}", speed);
+    }
+}
@@ -1,20 +1,634 @@
 //! Query system for efficient component access
-//! 
-//! This module will contain the query system for type-safe,
-//! efficient iteration over entities with specific components.
+//!
+//! Queries iterate over every entity that matches a combination of components
+//! without allocating. The smallest storage a query requires drives the
+//! iteration and every other component is looked up through its sparse array.
+//!
+//! ```ignore
+//! for (entity, transform, velocity) in world.query_mut::<(Entity, &Transform, &mut Velocity)>() {
+//!     // ...
+//! }
+//!
+//! for (entity, transform) in world.query_filtered::<(Entity, &Transform), (With<Wall>, Without<LightSource>)>() {
+//!     // ...
+//! }
+//!
+//! // Only entities whose transform was touched since the last `World::clear_trackers`
+//! for entity in world.query_filtered::<Entity, Changed<Transform>>() {
+//!     // ...
+//! }
+//! ```
+//!
+//! Entities made inactive with `World::set_active` are skipped unless the
+//! filter includes `IncludeInactive`.
 
-use crate::ecs::World;
+use std::any::{type_name, TypeId};
+use std::marker::PhantomData;
+use crate::ecs::{Component, ComponentManager, Entity, EntityManager};
+use crate::ecs::component::TypedComponentStorage;
+use crate::ecs::entity::EntityIter;
 
-/// Query trait for accessing components
-pub trait Query<'w> {
-    type Item;
-    
-    /// Execute the query and return an iterator
-    fn query(world: &'w World) -> impl Iterator<Item = Self::Item>;
+/// Raw view of a storage's dense entity array
+#[derive(Clone, Copy)]
+pub struct EntitySlice {
+    ptr: *const Entity,
+    len: usize,
 }
 
-// TODO: Implement query macros and types for:
-// - Query<(&Transform, &Velocity)>
-// - Query<(&mut Transform, &Velocity)>
-// - Query<(Entity, &Transform, &mut Velocity)>
-// - With/Without filters 
\ No newline at end of file
+impl EntitySlice {
+    fn of<T: Component>(storage: *const TypedComponentStorage<T>) -> Self {
+        // SAFETY: storage pointers handed to queries always point to live storages
+        let entities = unsafe { (*storage).entities() };
+        Self { ptr: entities.as_ptr(), len: entities.len() }
+    }
+
+    /// Pick the shorter of two optional candidate lists
+    fn smallest(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if b.len < a.len { b } else { a }),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+/// Component access declared by a query, used to reject aliasing borrows
+#[derive(Default)]
+pub struct Access {
+    entries: Vec<(TypeId, &'static str, bool)>,
+}
+
+impl Access {
+    /// Record a read of component `T`
+    pub fn read<T: Component>(&mut self) {
+        self.entries.push((TypeId::of::<T>(), type_name::<T>(), false));
+    }
+
+    /// Record a write of component `T`
+    pub fn write<T: Component>(&mut self) {
+        self.entries.push((TypeId::of::<T>(), type_name::<T>(), true));
+    }
+
+    /// Panic if any component is borrowed mutably alongside another borrow of the same type
+    fn validate(&self) {
+        for (index, (type_id, name, write)) in self.entries.iter().enumerate() {
+            for (other_id, _, other_write) in &self.entries[index + 1..] {
+                if type_id == other_id && (*write || *other_write) {
+                    panic!("Query accesses {} mutably while also borrowing it elsewhere in the same query", name);
+                }
+            }
+        }
+    }
+}
+
+/// Data fetched for each entity matched by a query
+///
+/// Implemented for `Entity`, `&T`, `&mut T`, `Option<&T>`, `Option<&mut T>`
+/// and tuples of those.
+///
+/// # Safety
+/// Implementors must declare every component they touch in `add_access` and
+/// only fetch entities for which `matches` returned true.
+pub unsafe trait QueryData {
+    /// The item yielded for each matching entity
+    type Item<'w>;
+    /// Cached storage pointers for the duration of the query
+    type State: Copy;
+
+    /// Look up the storages this data needs. Returns `None` when a required
+    /// component has never been stored, meaning nothing can match.
+    ///
+    /// # Safety
+    /// `components` must be valid for the lifetime of the query, and mutably
+    /// borrowed if this data contains `&mut T`.
+    unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State>;
+
+    /// Entities of the smallest storage this data requires, if any
+    fn candidates(state: &Self::State) -> Option<EntitySlice>;
+
+    /// Check whether an entity has everything this data requires
+    fn matches(state: &Self::State, entity: Entity) -> bool;
+
+    /// Fetch the data for a matching entity
+    ///
+    /// # Safety
+    /// `matches` must have returned true for `entity`, and no other live
+    /// reference may alias the mutable components being fetched.
+    unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w>;
+
+    /// Declare the components this data reads and writes
+    fn add_access(access: &mut Access);
+}
+
+/// Query data that only reads components
+///
+/// # Safety
+/// Implementors must never write through their fetched items.
+pub unsafe trait ReadOnlyQueryData: QueryData {
+    /// Look up the storages this data needs through a shared borrow
+    fn init_state_read_only(components: &ComponentManager) -> Option<Self::State>;
+}
+
+/// Filter restricting which entities a query matches without fetching data
+///
+/// # Safety
+/// `candidates` must only return entities that could satisfy `matches`.
+pub unsafe trait QueryFilter {
+    /// Cached storage pointers for the duration of the query
+    type State: Copy;
+
+    /// Whether the query also yields inactive entities
+    const INCLUDE_INACTIVE: bool = false;
+
+    /// Look up the storages this filter needs
+    fn init_state(components: &ComponentManager) -> Self::State;
+
+    /// Entities of the smallest storage this filter requires, if any
+    fn candidates(_state: &Self::State) -> Option<EntitySlice> {
+        None
+    }
+
+    /// Check whether an entity passes the filter
+    fn matches(state: &Self::State, entity: Entity) -> bool;
+}
+
+/// Filter matching entities that have component `T`
+pub struct With<T: Component>(PhantomData<fn() -> T>);
+
+/// Filter matching entities that do not have component `T`
+pub struct Without<T: Component>(PhantomData<fn() -> T>);
+
+/// Filter matching entities whose component `T` was added since the last
+/// `World::clear_trackers`
+#[allow(dead_code)] // The game reacts to new components through hooks instead
+pub struct Added<T: Component>(PhantomData<fn() -> T>);
+
+/// Filter matching entities whose component `T` was added or mutably accessed
+/// since the last `World::clear_trackers`
+pub struct Changed<T: Component>(PhantomData<fn() -> T>);
+
+/// Filter that lets a query see inactive entities as well as active ones
+pub struct IncludeInactive;
+
+unsafe impl QueryData for Entity {
+    type Item<'w> = Entity;
+    type State = ();
+
+    unsafe fn init_state(_components: *mut ComponentManager) -> Option<()> {
+        Some(())
+    }
+
+    fn candidates(_state: &()) -> Option<EntitySlice> {
+        None
+    }
+
+    fn matches(_state: &(), _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(_state: &(), entity: Entity) -> Self::Item<'w> {
+        entity
+    }
+
+    fn add_access(_access: &mut Access) {}
+}
+
+unsafe impl ReadOnlyQueryData for Entity {
+    fn init_state_read_only(_components: &ComponentManager) -> Option<()> {
+        Some(())
+    }
+}
+
+unsafe impl<T: Component> QueryData for &T {
+    type Item<'w> = &'w T;
+    type State = *const TypedComponentStorage<T>;
+
+    unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State> {
+        Self::init_state_read_only(&*components)
+    }
+
+    fn candidates(state: &Self::State) -> Option<EntitySlice> {
+        Some(EntitySlice::of(*state))
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        unsafe { (**state).has(entity) }
+    }
+
+    unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w> {
+        (**state).get(entity).expect("query fetched unmatched entity")
+    }
+
+    fn add_access(access: &mut Access) {
+        access.read::<T>();
+    }
+}
+
+unsafe impl<T: Component> ReadOnlyQueryData for &T {
+    fn init_state_read_only(components: &ComponentManager) -> Option<Self::State> {
+        components.storage::<T>().map(|storage| storage as *const _)
+    }
+}
+
+unsafe impl<T: Component> QueryData for &mut T {
+    type Item<'w> = &'w mut T;
+    type State = *mut TypedComponentStorage<T>;
+
+    unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State> {
+        (*components).existing_storage_mut::<T>().map(|storage| storage as *mut _)
+    }
+
+    fn candidates(state: &Self::State) -> Option<EntitySlice> {
+        Some(EntitySlice::of(*state))
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        unsafe { (**state).has(entity) }
+    }
+
+    unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w> {
+        &mut *TypedComponentStorage::get_ptr_mut(*state, entity).expect("query fetched unmatched entity")
+    }
+
+    fn add_access(access: &mut Access) {
+        access.write::<T>();
+    }
+}
+
+unsafe impl<T: Component> QueryData for Option<&T> {
+    type Item<'w> = Option<&'w T>;
+    type State = Option<*const TypedComponentStorage<T>>;
+
+    unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State> {
+        Self::init_state_read_only(&*components)
+    }
+
+    fn candidates(_state: &Self::State) -> Option<EntitySlice> {
+        None
+    }
+
+    fn matches(_state: &Self::State, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w> {
+        (*state)?.as_ref()?.get(entity)
+    }
+
+    fn add_access(access: &mut Access) {
+        access.read::<T>();
+    }
+}
+
+unsafe impl<T: Component> ReadOnlyQueryData for Option<&T> {
+    fn init_state_read_only(components: &ComponentManager) -> Option<Self::State> {
+        Some(components.storage::<T>().map(|storage| storage as *const _))
+    }
+}
+
+unsafe impl<T: Component> QueryData for Option<&mut T> {
+    type Item<'w> = Option<&'w mut T>;
+    type State = Option<*mut TypedComponentStorage<T>>;
+
+    unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State> {
+        Some((*components).existing_storage_mut::<T>().map(|storage| storage as *mut _))
+    }
+
+    fn candidates(_state: &Self::State) -> Option<EntitySlice> {
+        None
+    }
+
+    fn matches(_state: &Self::State, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w> {
+        let storage = (*state)?;
+        TypedComponentStorage::get_ptr_mut(storage, entity).map(|component| &mut *component)
+    }
+
+    fn add_access(access: &mut Access) {
+        access.write::<T>();
+    }
+}
+
+unsafe impl<T: Component> QueryFilter for With<T> {
+    type State = Option<*const TypedComponentStorage<T>>;
+
+    fn init_state(components: &ComponentManager) -> Self::State {
+        components.storage::<T>().map(|storage| storage as *const _)
+    }
+
+    fn candidates(state: &Self::State) -> Option<EntitySlice> {
+        // A missing storage means nothing matches, which an empty slice expresses
+        Some(state.map(EntitySlice::of).unwrap_or(EntitySlice { ptr: std::ptr::NonNull::dangling().as_ptr(), len: 0 }))
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        state.is_some_and(|storage| unsafe { (*storage).has(entity) })
+    }
+}
+
+unsafe impl<T: Component> QueryFilter for Without<T> {
+    type State = Option<*const TypedComponentStorage<T>>;
+
+    fn init_state(components: &ComponentManager) -> Self::State {
+        components.storage::<T>().map(|storage| storage as *const _)
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        !state.is_some_and(|storage| unsafe { (*storage).has(entity) })
+    }
+}
+
+unsafe impl<T: Component> QueryFilter for Added<T> {
+    type State = Option<*const TypedComponentStorage<T>>;
+
+    fn init_state(components: &ComponentManager) -> Self::State {
+        components.storage::<T>().map(|storage| storage as *const _)
+    }
+
+    fn candidates(state: &Self::State) -> Option<EntitySlice> {
+        With::<T>::candidates(state)
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        state.is_some_and(|storage| unsafe { (*storage).is_added(entity) })
+    }
+}
+
+unsafe impl<T: Component> QueryFilter for Changed<T> {
+    type State = Option<*const TypedComponentStorage<T>>;
+
+    fn init_state(components: &ComponentManager) -> Self::State {
+        components.storage::<T>().map(|storage| storage as *const _)
+    }
+
+    fn candidates(state: &Self::State) -> Option<EntitySlice> {
+        With::<T>::candidates(state)
+    }
+
+    fn matches(state: &Self::State, entity: Entity) -> bool {
+        state.is_some_and(|storage| unsafe { (*storage).is_changed(entity) })
+    }
+}
+
+unsafe impl QueryFilter for () {
+    type State = ();
+
+    fn init_state(_components: &ComponentManager) {}
+
+    fn matches(_state: &(), _entity: Entity) -> bool {
+        true
+    }
+}
+
+unsafe impl QueryFilter for IncludeInactive {
+    type State = ();
+
+    const INCLUDE_INACTIVE: bool = true;
+
+    fn init_state(_components: &ComponentManager) {}
+
+    fn matches(_state: &(), _entity: Entity) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($(($name:ident, $index:tt)),+) => {
+        unsafe impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type State = ($($name::State,)+);
+
+            unsafe fn init_state(components: *mut ComponentManager) -> Option<Self::State> {
+                Some(($($name::init_state(components)?,)+))
+            }
+
+            fn candidates(state: &Self::State) -> Option<EntitySlice> {
+                let mut smallest = None;
+                $(smallest = EntitySlice::smallest(smallest, $name::candidates(&state.$index));)+
+                smallest
+            }
+
+            fn matches(state: &Self::State, entity: Entity) -> bool {
+                $($name::matches(&state.$index, entity))&&+
+            }
+
+            unsafe fn fetch<'w>(state: &Self::State, entity: Entity) -> Self::Item<'w> {
+                ($($name::fetch(&state.$index, entity),)+)
+            }
+
+            fn add_access(access: &mut Access) {
+                $($name::add_access(access);)+
+            }
+        }
+
+        unsafe impl<$($name: ReadOnlyQueryData),+> ReadOnlyQueryData for ($($name,)+) {
+            fn init_state_read_only(components: &ComponentManager) -> Option<Self::State> {
+                Some(($($name::init_state_read_only(components)?,)+))
+            }
+        }
+
+        unsafe impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            type State = ($($name::State,)+);
+
+            const INCLUDE_INACTIVE: bool = $($name::INCLUDE_INACTIVE)||+;
+
+            fn init_state(components: &ComponentManager) -> Self::State {
+                ($($name::init_state(components),)+)
+            }
+
+            fn candidates(state: &Self::State) -> Option<EntitySlice> {
+                let mut smallest = None;
+                $(smallest = EntitySlice::smallest(smallest, $name::candidates(&state.$index));)+
+                smallest
+            }
+
+            fn matches(state: &Self::State, entity: Entity) -> bool {
+                $($name::matches(&state.$index, entity))&&+
+            }
+        }
+    };
+}
+
+impl_query_tuple!((A, 0));
+impl_query_tuple!((A, 0), (B, 1));
+impl_query_tuple!((A, 0), (B, 1), (C, 2));
+impl_query_tuple!((A, 0), (B, 1), (C, 2), (D, 3));
+impl_query_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4));
+impl_query_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5));
+
+/// A typed query over the world, created by `World::query` and friends
+pub struct Query<'w, Q: QueryData, F: QueryFilter = ()> {
+    data: Option<Q::State>,
+    filter: F::State,
+    entities: &'w EntityManager,
+    _marker: PhantomData<&'w mut ComponentManager>,
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> Query<'w, Q, F> {
+    /// Build a query over the given managers
+    ///
+    /// # Safety
+    /// `components` must be valid for `'w`, and exclusively borrowed for `'w`
+    /// unless `Q` is read-only.
+    pub(crate) unsafe fn new(entities: &'w EntityManager, components: *mut ComponentManager) -> Self {
+        Self::from_state(entities, Q::init_state(components), F::init_state(&*components))
+    }
+
+    fn from_state(entities: &'w EntityManager, data: Option<Q::State>, filter: F::State) -> Self {
+        let mut access = Access::default();
+        Q::add_access(&mut access);
+        access.validate();
+
+        Self { data, filter, entities, _marker: PhantomData }
+    }
+
+    /// Iterate over matching entities, allowing mutable access
+    #[allow(dead_code)] // Systems so far consume their queries with `into_iter`
+    pub fn iter_mut(&mut self) -> QueryIter<'_, Q, F> {
+        QueryIter::new(self.data, self.filter, self.entities)
+    }
+
+    /// Fetch the query data for a single entity, allowing mutable access
+    #[allow(dead_code)] // Systems so far look single entities up with `into_item`
+    pub fn get_mut(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        self.fetch_entity(entity)
+    }
+
+    /// Consume the query and fetch the data for a single entity, keeping the world borrow
+    pub fn into_item(self, entity: Entity) -> Option<Q::Item<'w>> {
+        self.fetch_entity(entity)
+    }
+
+    /// Count matching entities without fetching their components
+    pub fn count(&self) -> usize {
+        match &self.data {
+            Some(data) => Candidates::new::<Q, F>(data, &self.filter, self.entities)
+                .filter(|&entity| self.contains(entity))
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Check whether a specific entity matches this query
+    pub fn contains(&self, entity: Entity) -> bool {
+        is_visible::<F>(self.entities, entity) && self.matches(entity)
+    }
+
+    fn matches(&self, entity: Entity) -> bool {
+        self.data.as_ref().is_some_and(|data| Q::matches(data, entity)) && F::matches(&self.filter, entity)
+    }
+
+    fn fetch_entity<'a>(&self, entity: Entity) -> Option<Q::Item<'a>> {
+        if !self.contains(entity) {
+            return None;
+        }
+        // SAFETY: the entity matches and the caller holds the borrow that rules out aliasing
+        self.data.as_ref().map(|data| unsafe { Q::fetch(data, entity) })
+    }
+}
+
+impl<'w, Q: ReadOnlyQueryData, F: QueryFilter> Query<'w, Q, F> {
+    /// Build a read-only query over the given managers
+    pub(crate) fn new_read_only(entities: &'w EntityManager, components: &'w ComponentManager) -> Self {
+        Self::from_state(entities, Q::init_state_read_only(components), F::init_state(components))
+    }
+
+    /// Iterate over matching entities
+    pub fn iter(&self) -> QueryIter<'_, Q, F> {
+        QueryIter::new(self.data, self.filter, self.entities)
+    }
+
+    /// Fetch the query data for a single entity
+    #[allow(dead_code)] // Systems so far look single entities up with `World::get`
+    pub fn get(&self, entity: Entity) -> Option<Q::Item<'_>> {
+        self.fetch_entity(entity)
+    }
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> IntoIterator for Query<'w, Q, F> {
+    type Item = Q::Item<'w>;
+    type IntoIter = QueryIter<'w, Q, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        QueryIter::new(self.data, self.filter, self.entities)
+    }
+}
+
+/// Entities a query walks before checking the remaining components
+enum Candidates<'w> {
+    Storage(std::slice::Iter<'w, Entity>),
+    All(EntityIter<'w>),
+    Empty,
+}
+
+impl<'w> Candidates<'w> {
+    /// Walk the smallest storage the query data and filter require, or every entity
+    fn new<Q: QueryData, F: QueryFilter>(data: &Q::State, filter: &F::State, entities: &'w EntityManager) -> Self {
+        match EntitySlice::smallest(Q::candidates(data), F::candidates(filter)) {
+            // SAFETY: the slice points into a storage that outlives the query borrow
+            Some(slice) => Candidates::Storage(unsafe { std::slice::from_raw_parts(slice.ptr, slice.len) }.iter()),
+            None => Candidates::All(entities.iter()),
+        }
+    }
+}
+
+impl Iterator for Candidates<'_> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        match self {
+            Candidates::Storage(iter) => iter.next().copied(),
+            Candidates::All(iter) => iter.next(),
+            Candidates::Empty => None,
+        }
+    }
+}
+
+/// Check whether a query with filter `F` may yield an entity at all
+fn is_visible<F: QueryFilter>(entities: &EntityManager, entity: Entity) -> bool {
+    if F::INCLUDE_INACTIVE {
+        entities.is_valid(entity)
+    } else {
+        entities.is_active(entity)
+    }
+}
+
+/// Iterator over the items of a query
+pub struct QueryIter<'w, Q: QueryData, F: QueryFilter> {
+    data: Option<Q::State>,
+    filter: F::State,
+    candidates: Candidates<'w>,
+    entities: &'w EntityManager,
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> QueryIter<'w, Q, F> {
+    fn new(data: Option<Q::State>, filter: F::State, entities: &'w EntityManager) -> Self {
+        let candidates = match &data {
+            None => Candidates::Empty,
+            Some(state) => Candidates::new::<Q, F>(state, &filter, entities),
+        };
+
+        Self { data, filter, candidates, entities }
+    }
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> Iterator for QueryIter<'w, Q, F> {
+    type Item = Q::Item<'w>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.data.as_ref()?;
+        for entity in self.candidates.by_ref() {
+            if (F::INCLUDE_INACTIVE || self.entities.is_active(entity))
+                && Q::matches(data, entity)
+                && F::matches(&self.filter, entity)
+            {
+                // SAFETY: each entity is yielded once, so mutable items never alias
+                return Some(unsafe { Q::fetch(data, entity) });
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,411 @@
+//! Field-level reflection for components
+//!
+//! Components that implement `Reflect` expose their fields by name, so debug
+//! tools, a console or an editor panel can read and edit them without knowing
+//! the concrete type. Values are exchanged as JSON: vectors and colors are
+//! arrays, options are `null` or the value.
+//!
+//! Implement it with `reflect_component!` next to the component, and put
+//! `ReflectFns` on the type's `ComponentRegistration` so `World::reflect` can
+//! find it on any entity:
+//!
+//! ```ignore
+//! reflect_component!(Transform {
+//!     position: Vec3,
+//!     rotation: Vec3,
+//!     enabled: bool,
+//! });
+//!
+//! inventory::submit! {
+//!     ComponentRegistration::reflected::<Transform>("Transform")
+//! }
+//!
+//! // Fields that live in a nested struct are mapped with `= path`
+//! reflect_component!(Renderer {
+//!     color = material.color: Color,
+//! });
+//!
+//! let transform = world.reflect_component_mut(player, "Transform").unwrap();
+//! transform.set_field_str("position", "[5.0, 0.6, 5.0]")?;
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use macroquad::prelude::*;
+use serde_json::{json, Value};
+use crate::ecs::{Component, Entity, World};
+use crate::ecs::components::{ColliderShape, ColliderMaterial, TriMesh, ConvexHull};
+
+/// Name and type of a reflected field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub type_name: &'static str,
+}
+
+/// Why a reflected field couldn't be set
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReflectError {
+    /// The component has no field with this name
+    UnknownField { component: &'static str, field: String },
+    /// The value doesn't have the shape the field expects
+    InvalidValue { field: String, expected: &'static str, value: String },
+}
+
+impl fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReflectError::UnknownField { component, field } => {
+                write!(f, "{} has no field '{}'", component, field)
+            }
+            ReflectError::InvalidValue { field, expected, value } => {
+                write!(f, "field '{}' expects {}, got {}", field, expected, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectError {}
+
+/// A type whose fields can be read and written by name
+pub trait Reflect {
+    /// Name of the reflected type, e.g. "Transform"
+    fn type_name(&self) -> &'static str;
+
+    /// Every reflected field, in declaration order
+    fn fields(&self) -> &'static [FieldInfo];
+
+    /// Read a field as a JSON value
+    fn field(&self, name: &str) -> Option<Value>;
+
+    /// Write a field from a JSON value
+    #[allow(dead_code)] // The game only reads fields so far, for its debug output
+    fn set_field(&mut self, name: &str, value: Value) -> Result<(), ReflectError>;
+
+    /// Write a field from text, as typed into a console
+    ///
+    /// The text is parsed as JSON, so `2.5`, `true` and `[1, 2, 3]` work.
+    /// Anything that isn't valid JSON is taken as a plain string.
+    #[allow(dead_code)]
+    fn set_field_str(&mut self, name: &str, text: &str) -> Result<(), ReflectError> {
+        let value = serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()));
+        self.set_field(name, value)
+    }
+
+    /// Every field as `name: value` pairs, for debug output
+    fn describe(&self) -> String {
+        let fields: Vec<String> = self.fields().iter()
+            .map(|info| format!("{}: {}", info.name, self.field(info.name).unwrap_or(Value::Null)))
+            .collect();
+        format!("{} {{ {} }}", self.type_name(), fields.join(", "))
+    }
+}
+
+/// A field type that can be converted to and from JSON
+pub trait ReflectValue: Sized {
+    /// Human-readable description of the expected JSON, for error messages
+    const EXPECTED: &'static str;
+
+    fn to_value(&self) -> Value;
+
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+macro_rules! impl_reflect_value_serde {
+    ($($type:ty => $expected:literal),* $(,)?) => {
+        $(
+            impl ReflectValue for $type {
+                const EXPECTED: &'static str = $expected;
+
+                fn to_value(&self) -> Value {
+                    json!(self)
+                }
+
+                fn from_value(value: &Value) -> Option<Self> {
+                    serde_json::from_value(value.clone()).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_value_serde! {
+    f32 => "a number",
+    u32 => "a non-negative integer",
+    usize => "a non-negative integer",
+    bool => "true or false",
+    String => "a string",
+}
+
+impl ReflectValue for Vec2 {
+    const EXPECTED: &'static str = "[x, y]";
+
+    fn to_value(&self) -> Value {
+        json!([self.x, self.y])
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        <[f32; 2]>::deserialize_value(value).map(Vec2::from_array)
+    }
+}
+
+impl ReflectValue for Vec3 {
+    const EXPECTED: &'static str = "[x, y, z]";
+
+    fn to_value(&self) -> Value {
+        json!([self.x, self.y, self.z])
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        <[f32; 3]>::deserialize_value(value).map(Vec3::from_array)
+    }
+}
+
+impl ReflectValue for Color {
+    const EXPECTED: &'static str = "[r, g, b, a]";
+
+    fn to_value(&self) -> Value {
+        json!([self.r, self.g, self.b, self.a])
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        <[f32; 4]>::deserialize_value(value).map(|[r, g, b, a]| Color::new(r, g, b, a))
+    }
+}
+
+impl<T: ReflectValue> ReflectValue for Option<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+
+    fn to_value(&self) -> Value {
+        self.as_ref().map(T::to_value).unwrap_or(Value::Null)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        if value.is_null() {
+            Some(None)
+        } else {
+            T::from_value(value).map(Some)
+        }
+    }
+}
+
+impl ReflectValue for ColliderShape {
+    const EXPECTED: &'static str = "{\"Box\": {\"size\": [x, y, z]}}, {\"Sphere\": {\"radius\": r}}, {\"Capsule\": {\"height\": h, \"radius\": r}}, {\"TriMesh\": {\"vertices\": [[x, y, z], ..], \"triangles\": [[a, b, c], ..]}} or {\"ConvexHull\": {\"vertices\": [[x, y, z], ..], \"faces\": [[a, b, c], ..]}}";
+
+    fn to_value(&self) -> Value {
+        match self {
+            ColliderShape::Box { size } => json!({ "Box": { "size": size.to_value() } }),
+            ColliderShape::Sphere { radius } => json!({ "Sphere": { "radius": radius } }),
+            ColliderShape::Capsule { height, radius } => json!({ "Capsule": { "height": height, "radius": radius } }),
+            ColliderShape::TriMesh { mesh } => json!({ "TriMesh": {
+                "vertices": mesh.vertices().iter().map(Vec3::to_value).collect::<Vec<_>>(),
+                "triangles": mesh.triangles(),
+            } }),
+            ColliderShape::ConvexHull { hull } => json!({ "ConvexHull": {
+                "vertices": hull.vertices().iter().map(Vec3::to_value).collect::<Vec<_>>(),
+                "faces": hull.faces(),
+            } }),
+        }
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(shape) = value.get("Box") {
+            Some(ColliderShape::Box { size: Vec3::from_value(shape.get("size")?)? })
+        } else if let Some(shape) = value.get("Sphere") {
+            Some(ColliderShape::Sphere { radius: f32::from_value(shape.get("radius")?)? })
+        } else if let Some(shape) = value.get("Capsule") {
+            Some(ColliderShape::Capsule {
+                height: f32::from_value(shape.get("height")?)?,
+                radius: f32::from_value(shape.get("radius")?)?,
+            })
+        } else if let Some(shape) = value.get("TriMesh") {
+            let vertices = mesh_vertices(shape.get("vertices")?)?;
+            let triangles = serde_json::from_value(shape.get("triangles")?.clone()).ok()?;
+            Some(ColliderShape::TriMesh { mesh: Arc::new(TriMesh::new(vertices, triangles)) })
+        } else if let Some(shape) = value.get("ConvexHull") {
+            let vertices = mesh_vertices(shape.get("vertices")?)?;
+            let faces: Vec<[u32; 3]> = serde_json::from_value(shape.get("faces")?.clone()).ok()?;
+            if faces.iter().flatten().any(|&index| index as usize >= vertices.len()) {
+                return None;
+            }
+            Some(ColliderShape::ConvexHull { hull: Arc::new(ConvexHull::from_faces(vertices, faces)) })
+        } else {
+            None
+        }
+    }
+}
+
+/// Read a list of `[x, y, z]` arrays into mesh vertices
+fn mesh_vertices(value: &Value) -> Option<Vec<Vec3>> {
+    value.as_array()?.iter().map(Vec3::from_value).collect()
+}
+
+impl ReflectValue for ColliderMaterial {
+    const EXPECTED: &'static str = "{\"friction\": f, \"restitution\": r, \"density\": d}";
+
+    fn to_value(&self) -> Value {
+        json!({ "friction": self.friction, "restitution": self.restitution, "density": self.density })
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(ColliderMaterial {
+            friction: f32::from_value(value.get("friction")?)?,
+            restitution: f32::from_value(value.get("restitution")?)?,
+            density: f32::from_value(value.get("density")?)?,
+        })
+    }
+}
+
+/// Fixed-size float arrays, the JSON form of vectors and colors
+trait DeserializeArray: Sized {
+    fn deserialize_value(value: &Value) -> Option<Self>;
+}
+
+impl<const N: usize> DeserializeArray for [f32; N] {
+    fn deserialize_value(value: &Value) -> Option<Self> {
+        let items = value.as_array()?;
+        if items.len() != N {
+            return None;
+        }
+        let mut array = [0.0; N];
+        for (slot, item) in array.iter_mut().zip(items) {
+            *slot = item.as_f64()? as f32;
+        }
+        Some(array)
+    }
+}
+
+/// Lookup functions for a reflected component type
+///
+/// Carried by the type's `ComponentRegistration`, so `World::reflect` finds it
+/// alongside the rest of the type's registration.
+#[derive(Clone, Copy)]
+pub struct ReflectFns {
+    pub get: fn(&World, Entity) -> Option<&dyn Reflect>,
+    pub get_mut: fn(&mut World, Entity) -> Option<&mut dyn Reflect>,
+}
+
+impl ReflectFns {
+    /// Lookup functions for the component type `T`
+    pub const fn of<T: Component + Reflect>() -> Self {
+        Self {
+            get: |world, entity| world.get::<T>(entity).map(|component| component as &dyn Reflect),
+            get_mut: |world, entity| world.get_mut::<T>(entity).map(|component| component as &mut dyn Reflect),
+        }
+    }
+}
+
+/// Implement `Reflect` for a component
+macro_rules! reflect_component {
+    ($type:ident { $($field:ident $(= $($path:ident).+)? : $field_type:ty),* $(,)? }) => {
+        impl $crate::ecs::reflect::Reflect for $type {
+            fn type_name(&self) -> &'static str {
+                stringify!($type)
+            }
+
+            fn fields(&self) -> &'static [$crate::ecs::reflect::FieldInfo] {
+                const FIELDS: &[$crate::ecs::reflect::FieldInfo] = &[
+                    $($crate::ecs::reflect::FieldInfo {
+                        name: stringify!($field),
+                        type_name: stringify!($field_type),
+                    }),*
+                ];
+                FIELDS
+            }
+
+            fn field(&self, name: &str) -> Option<serde_json::Value> {
+                use $crate::ecs::reflect::ReflectValue;
+                match name {
+                    $(stringify!($field) => Some(
+                        $crate::ecs::reflect::reflect_field!(self, $field $(, $($path).+)?).to_value()
+                    ),)*
+                    _ => None,
+                }
+            }
+
+            fn set_field(&mut self, name: &str, value: serde_json::Value) -> Result<(), $crate::ecs::reflect::ReflectError> {
+                use $crate::ecs::reflect::{ReflectValue, ReflectError};
+                match name {
+                    $(stringify!($field) => {
+                        let parsed = <$field_type as ReflectValue>::from_value(&value).ok_or_else(|| {
+                            ReflectError::InvalidValue {
+                                field: name.to_string(),
+                                expected: <$field_type as ReflectValue>::EXPECTED,
+                                value: value.to_string(),
+                            }
+                        })?;
+                        $crate::ecs::reflect::reflect_field!(self, $field $(, $($path).+)?) = parsed;
+                        Ok(())
+                    })*
+                    _ => Err(ReflectError::UnknownField {
+                        component: stringify!($type),
+                        field: name.to_string(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+/// The place a reflected field is stored: the field itself, or a mapped path
+macro_rules! reflect_field {
+    ($target:expr, $field:ident) => {
+        $target.$field
+    };
+    ($target:expr, $field:ident, $($path:ident).+) => {
+        $target.$($path).+
+    };
+}
+
+pub(crate) use reflect_component;
+pub(crate) use reflect_field;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{Transform, Renderer, Collider};
+
+    #[test]
+    fn test_reflect_fields_by_name() {
+        let mut transform = Transform::new(Vec3::new(1.0, 2.0, 3.0));
+        let names: Vec<&str> = transform.fields().iter().map(|info| info.name).collect();
+        assert_eq!(names, vec!["position", "rotation", "scale", "enabled"]);
+        assert_eq!(transform.field("position"), Some(json!([1.0, 2.0, 3.0])));
+
+        transform.set_field_str("position", "[4, 5, 6]").unwrap();
+        transform.set_field("enabled", json!(false)).unwrap();
+        assert_eq!(transform.position, Vec3::new(4.0, 5.0, 6.0));
+        assert!(!transform.enabled);
+
+        assert!(matches!(transform.set_field_str("scale", "2.0"), Err(ReflectError::InvalidValue { .. })));
+        assert!(matches!(transform.set_field("mass", json!(1.0)), Err(ReflectError::UnknownField { .. })));
+
+        // Mapped paths and plain-string fallbacks
+        let mut renderer = Renderer::cube(Vec3::ONE);
+        renderer.set_field_str("texture_name", "floor.png").unwrap();
+        assert_eq!(renderer.material.texture_name.as_deref(), Some("floor.png"));
+        renderer.set_field_str("texture_name", "null").unwrap();
+        assert_eq!(renderer.material.texture_name, None);
+    }
+
+    #[test]
+    fn test_world_reflects_registered_components() {
+        let mut world = World::new();
+        let entity = world.spawn()
+            .with(Transform::new(Vec3::ZERO))
+            .with(Collider::static_solid(ColliderShape::Box { size: Vec3::ONE }))
+            .with(Renderer::cube(Vec3::ONE))
+            .build();
+
+        // Updated and data-only components are each found once
+        let mut names: Vec<&str> = world.reflect(entity).iter().map(|component| component.type_name()).collect();
+        names.sort();
+        assert_eq!(names, vec!["Collider", "Renderer", "Transform"]);
+        assert!(world.reflect_component(entity, "StaticMesh").is_none());
+
+        let collider = world.reflect_component_mut(entity, "Collider").unwrap();
+        collider.set_field_str("shape", r#"{"Sphere": {"radius": 0.5}}"#).unwrap();
+        assert!(matches!(world.get::<Collider>(entity).unwrap().shape, ColliderShape::Sphere { radius } if radius == 0.5));
+        assert!(world.reflect_component(entity, "Velocity").is_none());
+    }
+}
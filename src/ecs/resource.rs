@@ -9,6 +9,116 @@ use std::collections::HashMap;
 /// Trait for resources (global singletons)
 pub trait Resource: 'static + Send + Sync {}
 
+/// Frame timing, advanced once per frame before systems run
+#[derive(Debug, Clone, Default)]
+pub struct Time {
+    /// Seconds elapsed since the previous frame
+    pub delta_seconds: f32,
+    /// Seconds elapsed since the world started updating
+    pub elapsed_seconds: f32,
+    /// Number of frames updated so far
+    pub frame_count: u32,
+}
+
+impl Time {
+    /// Advance to the next frame
+    pub fn advance(&mut self, delta_seconds: f32) {
+        self.delta_seconds = delta_seconds;
+        self.elapsed_seconds += delta_seconds;
+        self.frame_count += 1;
+    }
+}
+
+impl Resource for Time {}
+
+/// Clock for the fixed-rate simulation tick
+///
+/// Frame time is added to an accumulator and the simulation stages run once
+/// for every whole `timestep` it holds, so movement and physics behave the
+/// same at any frame rate. The time left over is exposed as `alpha` for
+/// interpolating transforms between the last two ticks when rendering.
+#[derive(Debug, Clone)]
+pub struct FixedTime {
+    /// Simulated seconds per tick
+    pub timestep: f32,
+    /// Most ticks run in one frame; time beyond that is dropped so a slow
+    /// frame can't snowball into ever longer ones
+    pub max_steps_per_frame: u32,
+    /// Multiplier applied to frame time, below 1.0 for slow motion
+    pub time_scale: f32,
+    /// While paused no time accumulates and ticks only run through `step`
+    pub paused: bool,
+    /// Number of ticks run so far
+    pub tick_count: u64,
+    accumulator: f32,
+    requested_steps: u32,
+}
+
+impl FixedTime {
+    /// Default tick rate of 60 Hz
+    pub const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+    
+    pub fn new(timestep: f32) -> Self {
+        Self {
+            timestep,
+            max_steps_per_frame: 5,
+            time_scale: 1.0,
+            paused: false,
+            tick_count: 0,
+            accumulator: 0.0,
+            requested_steps: 0,
+        }
+    }
+    
+    /// Add a frame's time and return how many ticks to run for it
+    pub fn accumulate(&mut self, frame_seconds: f32) -> u32 {
+        if self.paused {
+            return std::mem::take(&mut self.requested_steps);
+        }
+        
+        self.accumulator += frame_seconds.max(0.0) * self.time_scale;
+        let steps = (self.accumulator / self.timestep) as u32;
+        self.accumulator -= steps as f32 * self.timestep;
+        
+        if steps > self.max_steps_per_frame {
+            self.accumulator = 0.0;
+            self.max_steps_per_frame
+        } else {
+            steps
+        }
+    }
+    
+    /// Record that a tick ran
+    pub fn tick(&mut self) {
+        self.tick_count += 1;
+    }
+    
+    /// Run exactly one tick on the next frame while paused
+    pub fn step(&mut self) {
+        if self.paused {
+            self.requested_steps += 1;
+        }
+    }
+    
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.requested_steps = 0;
+    }
+    
+    /// How far the current frame lies between the last tick and the next, 0..1
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.timestep).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TIMESTEP)
+    }
+}
+
+impl Resource for FixedTime {}
+
 /// Manager for global resources
 #[derive(Default)]
 pub struct ResourceManager {
@@ -53,4 +163,31 @@ impl ResourceManager {
         let type_id = TypeId::of::<R>();
         self.resources.contains_key(&type_id)
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_time_accumulates_whole_ticks() {
+        let mut time = FixedTime::new(0.25);
+        
+        assert_eq!(time.accumulate(0.625), 2);
+        assert_eq!(time.alpha(), 0.5);
+        assert_eq!(time.accumulate(0.125), 1);
+        
+        // A long hitch is clamped instead of replayed
+        assert_eq!(time.accumulate(10.0), time.max_steps_per_frame);
+        assert_eq!(time.alpha(), 0.0);
+        
+        time.time_scale = 0.5;
+        assert_eq!(time.accumulate(0.5), 1);
+        
+        time.toggle_pause();
+        assert_eq!(time.accumulate(1.0), 0);
+        time.step();
+        assert_eq!(time.accumulate(1.0), 1);
+        assert_eq!(time.accumulate(1.0), 0);
+    }
+}
@@ -0,0 +1,265 @@
+//! System scheduling
+//!
+//! A `Schedule` runs named systems in fixed stages once per frame. Within a
+//! stage, systems run in the order they were added unless `before`/`after`
+//! constraints say otherwise, and a system with run criteria is skipped on
+//! frames where its criteria return false. An optional stage-end hook runs
+//! after every stage, which is where deferred commands get applied.
+//!
+//! `run` executes every stage once. Callers stepping a fixed-rate simulation
+//! instead run `Input` and `RenderPrep` once per frame with `run_stage` and
+//! the `Stage::SIMULATION` stages once per tick.
+//!
+//! ```ignore
+//! schedule.add_system(Stage::Update, "player_movement", player_movement_system)
+//!     .run_if(|state| !state.has_test_bot());
+//! schedule.add_system(Stage::Update, "player_physics", player_physics_system)
+//!     .after("player_movement");
+//! ```
+
+use std::collections::HashMap;
+
+/// The stages of a frame, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// React to input and other frame-start events
+    Input,
+    /// Read-only decisions (`Component::pre_update`)
+    PreUpdate,
+    /// Main simulation (`Component::update`, gameplay systems)
+    Update,
+    /// Work that depends on the simulation results
+    PostUpdate,
+    /// Prepare data for rendering
+    RenderPrep,
+}
+
+impl Stage {
+    /// All stages in execution order
+    pub const ALL: [Stage; 5] = [Stage::Input, Stage::PreUpdate, Stage::Update, Stage::PostUpdate, Stage::RenderPrep];
+    
+    /// Stages that advance the simulation, run once per fixed tick
+    pub const SIMULATION: [Stage; 3] = [Stage::PreUpdate, Stage::Update, Stage::PostUpdate];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+type SystemFn<C> = Box<dyn FnMut(&mut C)>;
+type RunCriteria<C> = Box<dyn Fn(&C) -> bool>;
+
+/// A registered system with its ordering constraints and run criteria
+struct SystemEntry<C> {
+    name: &'static str,
+    run: SystemFn<C>,
+    run_if: Vec<RunCriteria<C>>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+/// Systems of one stage, sorted lazily when constraints change
+struct StageSystems<C> {
+    systems: Vec<SystemEntry<C>>,
+    order: Vec<usize>,
+    dirty: bool,
+}
+
+impl<C> Default for StageSystems<C> {
+    fn default() -> Self {
+        Self { systems: Vec::new(), order: Vec::new(), dirty: false }
+    }
+}
+
+impl<C> StageSystems<C> {
+    /// Topologically sort systems by their constraints, keeping insertion order otherwise
+    fn sort(&mut self, stage: Stage) {
+        let indices: HashMap<&'static str, usize> = self.systems.iter()
+            .enumerate()
+            .map(|(index, system)| (system.name, index))
+            .collect();
+
+        let lookup = |system: &str, other: &str| -> usize {
+            *indices.get(other).unwrap_or_else(|| {
+                panic!("System '{}' in stage {:?} is ordered against unknown system '{}'", system, stage, other)
+            })
+        };
+
+        // Edges point from a system to the systems that must run after it
+        let mut successors = vec![Vec::new(); self.systems.len()];
+        let mut pending = vec![0usize; self.systems.len()];
+        for (index, system) in self.systems.iter().enumerate() {
+            for &name in &system.before {
+                let other = lookup(system.name, name);
+                successors[index].push(other);
+                pending[other] += 1;
+            }
+            for &name in &system.after {
+                let other = lookup(system.name, name);
+                successors[other].push(index);
+                pending[index] += 1;
+            }
+        }
+
+        self.order.clear();
+        let mut scheduled = vec![false; self.systems.len()];
+        while self.order.len() < self.systems.len() {
+            let next = (0..self.systems.len())
+                .find(|&index| !scheduled[index] && pending[index] == 0)
+                .unwrap_or_else(|| panic!("System ordering in stage {:?} contains a cycle", stage));
+
+            scheduled[next] = true;
+            self.order.push(next);
+            for &successor in &successors[next] {
+                pending[successor] -= 1;
+            }
+        }
+
+        self.dirty = false;
+    }
+}
+
+/// Ordered collection of systems operating on a context `C`
+pub struct Schedule<C> {
+    stages: Vec<StageSystems<C>>,
+    stage_end: Option<SystemFn<C>>,
+}
+
+impl<C> Schedule<C> {
+    /// Create an empty schedule
+    pub fn new() -> Self {
+        Self {
+            stages: Stage::ALL.iter().map(|_| StageSystems::default()).collect(),
+            stage_end: None,
+        }
+    }
+    
+    /// Run `hook` at the end of every stage, e.g. `World::apply_commands`
+    pub fn on_stage_end<F>(&mut self, hook: F)
+    where
+        F: FnMut(&mut C) + 'static,
+    {
+        self.stage_end = Some(Box::new(hook));
+    }
+
+    /// Add a named system to a stage
+    ///
+    /// Panics if a system with the same name already exists in that stage.
+    pub fn add_system<F>(&mut self, stage: Stage, name: &'static str, system: F) -> SystemConfig<'_, C>
+    where
+        F: FnMut(&mut C) + 'static,
+    {
+        let stage_systems = &mut self.stages[stage.index()];
+        if stage_systems.systems.iter().any(|existing| existing.name == name) {
+            panic!("System '{}' is already registered in stage {:?}", name, stage);
+        }
+
+        stage_systems.systems.push(SystemEntry {
+            name,
+            run: Box::new(system),
+            run_if: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+        });
+        stage_systems.dirty = true;
+
+        SystemConfig { entry: stage_systems.systems.last_mut().unwrap() }
+    }
+
+    /// Run the systems of a single stage
+    pub fn run_stage(&mut self, stage: Stage, context: &mut C) {
+        let stage_systems = &mut self.stages[stage.index()];
+        if stage_systems.dirty {
+            stage_systems.sort(stage);
+        }
+
+        for &index in &stage_systems.order {
+            let system = &mut stage_systems.systems[index];
+            if system.run_if.iter().all(|criteria| criteria(context)) {
+                (system.run)(context);
+            }
+        }
+        
+        if let Some(stage_end) = &mut self.stage_end {
+            stage_end(context);
+        }
+    }
+
+    /// Run every stage in order
+    #[allow(dead_code)] // The game runs its stages one at a time around the fixed timestep
+    pub fn run(&mut self, context: &mut C) {
+        for stage in Stage::ALL {
+            self.run_stage(stage, context);
+        }
+    }
+}
+
+impl<C> Default for Schedule<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder returned by `Schedule::add_system` for ordering and run criteria
+pub struct SystemConfig<'s, C> {
+    entry: &'s mut SystemEntry<C>,
+}
+
+impl<C> SystemConfig<'_, C> {
+    /// Run this system before another system in the same stage
+    #[allow(dead_code)] // The game's systems all order themselves with `after`
+    pub fn before(self, name: &'static str) -> Self {
+        self.entry.before.push(name);
+        self
+    }
+
+    /// Run this system after another system in the same stage
+    pub fn after(self, name: &'static str) -> Self {
+        self.entry.after.push(name);
+        self
+    }
+
+    /// Only run this system on frames where `criteria` returns true
+    pub fn run_if<F>(self, criteria: F) -> Self
+    where
+        F: Fn(&C) -> bool + 'static,
+    {
+        self.entry.run_if.push(Box::new(criteria));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systems_respect_ordering_and_run_criteria() {
+        let mut schedule: Schedule<Vec<&'static str>> = Schedule::new();
+
+        schedule.add_system(Stage::Update, "physics", |log| log.push("physics"))
+            .after("movement");
+        schedule.add_system(Stage::Update, "movement", |log| log.push("movement"));
+        schedule.add_system(Stage::Update, "animation", |log| log.push("animation"))
+            .before("movement");
+        schedule.add_system(Stage::Input, "input", |log| log.push("input"));
+        schedule.add_system(Stage::Update, "skipped", |log| log.push("skipped"))
+            .run_if(|log| log.is_empty());
+
+        let mut log = Vec::new();
+        schedule.run(&mut log);
+
+        assert_eq!(log, vec!["input", "animation", "movement", "physics"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cyclic_ordering_panics() {
+        let mut schedule: Schedule<()> = Schedule::new();
+
+        schedule.add_system(Stage::Update, "a", |_| {}).after("b");
+        schedule.add_system(Stage::Update, "b", |_| {}).after("a");
+
+        schedule.run(&mut ());
+    }
+}
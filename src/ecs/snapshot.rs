@@ -0,0 +1,1002 @@
+//! World snapshots for save games, bug reports and deterministic tests
+//!
+//! Only components registered with a `SnapshotRegistry` are saved; everything
+//! else (meshes, textures, caches) is rebuilt by the game after loading.
+//! A snapshot can be written as JSON for humans or in a compact binary form.
+//!
+//! ```ignore
+//! let registry = SnapshotRegistry::standard();
+//! let json = registry.save_json(&world)?;
+//!
+//! world.clear();
+//! let entities = registry.load_json(&mut world, &json)?;
+//! ```
+//!
+//! Entities are spawned fresh when a snapshot is loaded, so their handles
+//! differ from the saved ones. The returned `EntityMap` maps saved ids to the
+//! new entities, and components that refer to other entities are remapped
+//! through it.
+
+// Nothing in the game saves or loads a world yet
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use crate::ecs::{Component, Entity, World};
+use crate::ecs::components::{
+    Transform, Velocity, Player, Collider, ColliderShape, ColliderMaterial, TriMesh, ConvexHull, CharacterController, RigidBody, KinematicBody, PathMode, GravityVolume, GravityOverride, LightSource, LightSourceType,
+    Renderer, RenderMode, RenderMaterial, Pathfinder, TestBot, Parent, Name, Tags,
+};
+
+/// Bumped whenever the snapshot layout changes incompatibly
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+type SnapshotResult<T> = Result<T, Box<dyn Error>>;
+
+/// Saved entity ids mapped to the entities they were loaded as
+#[derive(Debug, Clone, Default)]
+pub struct EntityMap {
+    entities: HashMap<u32, Entity>,
+}
+
+impl EntityMap {
+    /// The loaded entity for a saved entity id
+    pub fn get(&self, saved_id: u32) -> Option<Entity> {
+        self.entities.get(&saved_id).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// A component that can be stored in a world snapshot
+pub trait SnapshotComponent: Component + Sized {
+    /// Key the component is stored under; must be unique within a registry
+    const NAME: &'static str;
+
+    /// Serializable form of the component
+    type Data: Serialize + DeserializeOwned;
+
+    fn to_snapshot(&self) -> Self::Data;
+
+    fn from_snapshot(data: Self::Data, entities: &EntityMap) -> Self;
+
+    /// Add the loaded component to its entity
+    ///
+    /// Components kept in sync by world methods, like `Parent`, override this.
+    fn insert(self, world: &mut World, entity: Entity) {
+        world.add(entity, self);
+    }
+}
+
+/// A whole world, with component data in a format-specific encoding
+#[derive(Debug, Serialize, Deserialize)]
+struct WorldSnapshot<V> {
+    version: u32,
+    entities: Vec<EntitySnapshot<V>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntitySnapshot<V> {
+    id: u32,
+    /// The entity's own active flag; descendants are recomputed on load
+    #[serde(default = "default_active")]
+    active: bool,
+    components: BTreeMap<String, V>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Save and load functions for one registered component type
+struct SnapshotEntry {
+    name: &'static str,
+    save_json: fn(&World, Entity) -> Option<SnapshotResult<serde_json::Value>>,
+    load_json: fn(&mut World, Entity, serde_json::Value, &EntityMap) -> SnapshotResult<()>,
+    save_binary: fn(&World, Entity) -> Option<SnapshotResult<Vec<u8>>>,
+    load_binary: fn(&mut World, Entity, &[u8], &EntityMap) -> SnapshotResult<()>,
+}
+
+/// The set of component types included in snapshots
+pub struct SnapshotRegistry {
+    entries: Vec<SnapshotEntry>,
+}
+
+impl SnapshotRegistry {
+    /// Create a registry that saves no components
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Create a registry with every component the game knows how to snapshot
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register::<Transform>()
+            .register::<Velocity>()
+            .register::<Player>()
+            .register::<Collider>()
+            .register::<CharacterController>()
+            .register::<RigidBody>()
+            .register::<KinematicBody>()
+            .register::<GravityVolume>()
+            .register::<LightSource>()
+            .register::<Renderer>()
+            .register::<Pathfinder>()
+            .register::<TestBot>()
+            .register::<Parent>()
+            .register::<Name>()
+            .register::<Tags>();
+        registry
+    }
+
+    /// Include a component type in snapshots
+    pub fn register<T: SnapshotComponent>(&mut self) -> &mut Self {
+        assert!(
+            !self.is_registered(T::NAME),
+            "snapshot component '{}' registered twice", T::NAME
+        );
+
+        self.entries.push(SnapshotEntry {
+            name: T::NAME,
+            save_json: |world, entity| {
+                world.get::<T>(entity).map(|component| Ok(serde_json::to_value(component.to_snapshot())?))
+            },
+            load_json: |world, entity, value, entities| {
+                let data: T::Data = serde_json::from_value(value)?;
+                T::from_snapshot(data, entities).insert(world, entity);
+                Ok(())
+            },
+            save_binary: |world, entity| {
+                world.get::<T>(entity).map(|component| Ok(bincode::serialize(&component.to_snapshot())?))
+            },
+            load_binary: |world, entity, bytes, entities| {
+                let data: T::Data = bincode::deserialize(bytes)?;
+                T::from_snapshot(data, entities).insert(world, entity);
+                Ok(())
+            },
+        });
+        self
+    }
+
+    /// Check if a component is included in snapshots
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    fn entry(&self, name: &str) -> Option<&SnapshotEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Write every entity with at least one registered component as JSON
+    pub fn save_json(&self, world: &World) -> SnapshotResult<String> {
+        let snapshot = self.capture(world, |entry, entity| (entry.save_json)(world, entity))?;
+        Ok(serde_json::to_string_pretty(&snapshot)?)
+    }
+
+    /// Spawn the entities of a JSON snapshot into `world`
+    pub fn load_json(&self, world: &mut World, json: &str) -> SnapshotResult<EntityMap> {
+        let snapshot: WorldSnapshot<serde_json::Value> = serde_json::from_str(json)?;
+        self.restore(world, snapshot, |entry, world, entity, value, entities| {
+            (entry.load_json)(world, entity, value, entities)
+        })
+    }
+
+    /// Write every entity with at least one registered component in binary form
+    pub fn save_binary(&self, world: &World) -> SnapshotResult<Vec<u8>> {
+        let snapshot = self.capture(world, |entry, entity| (entry.save_binary)(world, entity))?;
+        Ok(bincode::serialize(&snapshot)?)
+    }
+
+    /// Spawn the entities of a binary snapshot into `world`
+    pub fn load_binary(&self, world: &mut World, bytes: &[u8]) -> SnapshotResult<EntityMap> {
+        let snapshot: WorldSnapshot<Vec<u8>> = bincode::deserialize(bytes)?;
+        self.restore(world, snapshot, |entry, world, entity, bytes, entities| {
+            (entry.load_binary)(world, entity, &bytes, entities)
+        })
+    }
+
+    fn capture<V>(
+        &self,
+        world: &World,
+        save: impl Fn(&SnapshotEntry, Entity) -> Option<SnapshotResult<V>>,
+    ) -> SnapshotResult<WorldSnapshot<V>> {
+        let mut entities = Vec::new();
+        for entity in world.entities().iter() {
+            let mut components = BTreeMap::new();
+            for entry in &self.entries {
+                if let Some(data) = save(entry, entity) {
+                    components.insert(entry.name.to_string(), data?);
+                }
+            }
+
+            if !components.is_empty() {
+                let active = world.is_active_self(entity);
+                entities.push(EntitySnapshot { id: entity.id, active, components });
+            }
+        }
+
+        Ok(WorldSnapshot { version: SNAPSHOT_VERSION, entities })
+    }
+
+    fn restore<V>(
+        &self,
+        world: &mut World,
+        snapshot: WorldSnapshot<V>,
+        load: impl Fn(&SnapshotEntry, &mut World, Entity, V, &EntityMap) -> SnapshotResult<()>,
+    ) -> SnapshotResult<EntityMap> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(format!(
+                "unsupported snapshot version {} (expected {})", snapshot.version, SNAPSHOT_VERSION
+            ).into());
+        }
+
+        // Spawn every entity first so components can refer to any of them
+        let mut entities = EntityMap::default();
+        for saved in &snapshot.entities {
+            let entity = world.spawn().build();
+            entities.entities.insert(saved.id, entity);
+        }
+
+        let mut inactive = Vec::new();
+        for saved in snapshot.entities {
+            let entity = entities.entities[&saved.id];
+            if !saved.active {
+                inactive.push(entity);
+            }
+            for (name, data) in saved.components {
+                match self.entry(&name) {
+                    Some(entry) => load(entry, world, entity, data, &entities)?,
+                    None => println!("⚠️ Skipping unregistered snapshot component '{}'", name),
+                }
+            }
+        }
+
+        // Deactivate once the hierarchy is rebuilt so children inherit the state
+        for entity in inactive {
+            world.set_active(entity, false);
+        }
+
+        world.propagate_transforms();
+        Ok(entities)
+    }
+}
+
+impl Default for SnapshotRegistry {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+fn vec2_to_array(v: Vec2) -> [f32; 2] {
+    v.to_array()
+}
+
+fn color_to_array(color: Color) -> [f32; 4] {
+    [color.r, color.g, color.b, color.a]
+}
+
+fn array_to_color([r, g, b, a]: [f32; 4]) -> Color {
+    Color::new(r, g, b, a)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransformData {
+    pub position: [f32; 3],
+    pub rotation: [f32; 3],
+    pub scale: [f32; 3],
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Transform {
+    const NAME: &'static str = "Transform";
+    type Data = TransformData;
+
+    fn to_snapshot(&self) -> TransformData {
+        TransformData {
+            position: self.position.to_array(),
+            rotation: self.rotation.to_array(),
+            scale: self.scale.to_array(),
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: TransformData, _entities: &EntityMap) -> Self {
+        Transform::new(Vec3::from_array(data.position))
+            .with_rotation(Vec3::from_array(data.rotation))
+            .with_scale(Vec3::from_array(data.scale))
+            .with_enabled(data.enabled)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VelocityData {
+    pub linear: [f32; 3],
+    pub angular: [f32; 3],
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Velocity {
+    const NAME: &'static str = "Velocity";
+    type Data = VelocityData;
+
+    fn to_snapshot(&self) -> VelocityData {
+        VelocityData {
+            linear: self.linear.to_array(),
+            angular: self.angular.to_array(),
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: VelocityData, _entities: &EntityMap) -> Self {
+        Velocity {
+            linear: Vec3::from_array(data.linear),
+            angular: Vec3::from_array(data.angular),
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerData {
+    pub health: f32,
+    pub max_health: f32,
+    pub is_grounded: bool,
+    pub move_speed: f32,
+    pub jump_strength: f32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Player {
+    const NAME: &'static str = "Player";
+    type Data = PlayerData;
+
+    fn to_snapshot(&self) -> PlayerData {
+        PlayerData {
+            health: self.health,
+            max_health: self.max_health,
+            is_grounded: self.is_grounded,
+            move_speed: self.move_speed,
+            jump_strength: self.jump_strength,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: PlayerData, _entities: &EntityMap) -> Self {
+        Player {
+            health: data.health,
+            max_health: data.max_health,
+            is_grounded: data.is_grounded,
+            move_speed: data.move_speed,
+            jump_strength: data.jump_strength,
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ColliderShapeData {
+    Box { size: [f32; 3] },
+    Sphere { radius: f32 },
+    Capsule { height: f32, radius: f32 },
+    TriMesh { vertices: Vec<[f32; 3]>, triangles: Vec<[u32; 3]> },
+    ConvexHull { vertices: Vec<[f32; 3]>, faces: Vec<[u32; 3]> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColliderData {
+    pub shape: ColliderShapeData,
+    pub is_static: bool,
+    pub is_trigger: bool,
+    pub friction: f32,
+    pub restitution: f32,
+    pub density: f32,
+    pub layer: u32,
+    pub mask: u32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Collider {
+    const NAME: &'static str = "Collider";
+    type Data = ColliderData;
+
+    fn to_snapshot(&self) -> ColliderData {
+        let shape = match &self.shape {
+            ColliderShape::Box { size } => ColliderShapeData::Box { size: size.to_array() },
+            ColliderShape::Sphere { radius } => ColliderShapeData::Sphere { radius: *radius },
+            ColliderShape::Capsule { height, radius } => ColliderShapeData::Capsule { height: *height, radius: *radius },
+            ColliderShape::TriMesh { mesh } => ColliderShapeData::TriMesh {
+                vertices: mesh.vertices().iter().map(|vertex| vertex.to_array()).collect(),
+                triangles: mesh.triangles().to_vec(),
+            },
+            ColliderShape::ConvexHull { hull } => ColliderShapeData::ConvexHull {
+                vertices: hull.vertices().iter().map(|vertex| vertex.to_array()).collect(),
+                faces: hull.faces().to_vec(),
+            },
+        };
+
+        ColliderData {
+            shape,
+            is_static: self.is_static,
+            is_trigger: self.is_trigger,
+            friction: self.material.friction,
+            restitution: self.material.restitution,
+            density: self.material.density,
+            layer: self.layer,
+            mask: self.mask,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: ColliderData, _entities: &EntityMap) -> Self {
+        let shape = match data.shape {
+            ColliderShapeData::Box { size } => ColliderShape::Box { size: Vec3::from_array(size) },
+            ColliderShapeData::Sphere { radius } => ColliderShape::Sphere { radius },
+            ColliderShapeData::Capsule { height, radius } => ColliderShape::Capsule { height, radius },
+            ColliderShapeData::TriMesh { vertices, triangles } => ColliderShape::TriMesh {
+                mesh: Arc::new(TriMesh::new(vertices.into_iter().map(Vec3::from_array).collect(), triangles)),
+            },
+            ColliderShapeData::ConvexHull { vertices, faces } => ColliderShape::ConvexHull {
+                hull: Arc::new(ConvexHull::from_faces(vertices.into_iter().map(Vec3::from_array).collect(), faces)),
+            },
+        };
+
+        Collider::new(shape, data.is_static, data.is_trigger)
+            .with_material(ColliderMaterial {
+                friction: data.friction,
+                restitution: data.restitution,
+                density: data.density,
+            })
+            .with_layers(data.layer, data.mask)
+            .with_enabled(data.enabled)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CharacterControllerData {
+    pub height: f32,
+    pub radius: f32,
+    pub foot_offset: f32,
+    pub step_height: f32,
+    pub max_slope: f32,
+    pub coyote_time: f32,
+    pub is_grounded: bool,
+    pub air_time: f32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for CharacterController {
+    const NAME: &'static str = "CharacterController";
+    type Data = CharacterControllerData;
+
+    fn to_snapshot(&self) -> CharacterControllerData {
+        CharacterControllerData {
+            height: self.height,
+            radius: self.radius,
+            foot_offset: self.foot_offset,
+            step_height: self.step_height,
+            max_slope: self.max_slope,
+            coyote_time: self.coyote_time,
+            is_grounded: self.is_grounded,
+            air_time: self.air_time,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: CharacterControllerData, _entities: &EntityMap) -> Self {
+        CharacterController {
+            height: data.height,
+            radius: data.radius,
+            foot_offset: data.foot_offset,
+            step_height: data.step_height,
+            max_slope: data.max_slope,
+            coyote_time: data.coyote_time,
+            is_grounded: data.is_grounded,
+            air_time: data.air_time,
+            ground: None,  // Found again by the next move
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RigidBodyData {
+    pub mass: f32,
+    pub gravity_scale: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub is_sleeping: bool,
+    pub sleep_timer: f32,
+    pub pending_impulse: [f32; 3],
+    pub pending_angular_impulse: [f32; 3],
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for RigidBody {
+    const NAME: &'static str = "RigidBody";
+    type Data = RigidBodyData;
+
+    fn to_snapshot(&self) -> RigidBodyData {
+        RigidBodyData {
+            mass: self.mass,
+            gravity_scale: self.gravity_scale,
+            linear_damping: self.linear_damping,
+            angular_damping: self.angular_damping,
+            is_sleeping: self.is_sleeping,
+            sleep_timer: self.sleep_timer,
+            pending_impulse: self.pending_impulse.to_array(),
+            pending_angular_impulse: self.pending_angular_impulse.to_array(),
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: RigidBodyData, _entities: &EntityMap) -> Self {
+        RigidBody {
+            mass: data.mass,
+            gravity_scale: data.gravity_scale,
+            linear_damping: data.linear_damping,
+            angular_damping: data.angular_damping,
+            is_sleeping: data.is_sleeping,
+            sleep_timer: data.sleep_timer,
+            pending_impulse: Vec3::from_array(data.pending_impulse),
+            pending_angular_impulse: Vec3::from_array(data.pending_angular_impulse),
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PathModeData {
+    Once,
+    PingPong,
+    Loop,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KinematicBodyData {
+    pub waypoints: Vec<[f32; 3]>,
+    pub speed: f32,
+    pub mode: PathModeData,
+    pub pause: f32,
+    pub is_moving: bool,
+    pub next: usize,
+    pub reversing: bool,
+    pub stop_at: Option<usize>,
+    pub wait_timer: f32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for KinematicBody {
+    const NAME: &'static str = "KinematicBody";
+    type Data = KinematicBodyData;
+
+    fn to_snapshot(&self) -> KinematicBodyData {
+        KinematicBodyData {
+            waypoints: self.waypoints.iter().map(|waypoint| waypoint.to_array()).collect(),
+            speed: self.speed,
+            mode: match self.mode {
+                PathMode::Once => PathModeData::Once,
+                PathMode::PingPong => PathModeData::PingPong,
+                PathMode::Loop => PathModeData::Loop,
+            },
+            pause: self.pause,
+            is_moving: self.is_moving,
+            next: self.next,
+            reversing: self.reversing,
+            stop_at: self.stop_at,
+            wait_timer: self.wait_timer,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: KinematicBodyData, _entities: &EntityMap) -> Self {
+        KinematicBody {
+            waypoints: data.waypoints.into_iter().map(Vec3::from_array).collect(),
+            speed: data.speed,
+            mode: match data.mode {
+                PathModeData::Once => PathMode::Once,
+                PathModeData::PingPong => PathMode::PingPong,
+                PathModeData::Loop => PathMode::Loop,
+            },
+            pause: data.pause,
+            is_moving: data.is_moving,
+            next: data.next,
+            reversing: data.reversing,
+            stop_at: data.stop_at,
+            wait_timer: data.wait_timer,
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum GravityOverrideData {
+    Vector([f32; 3]),
+    Scale(f32),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GravityVolumeData {
+    pub gravity: GravityOverrideData,
+    pub priority: u32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for GravityVolume {
+    const NAME: &'static str = "GravityVolume";
+    type Data = GravityVolumeData;
+
+    fn to_snapshot(&self) -> GravityVolumeData {
+        GravityVolumeData {
+            gravity: match self.gravity {
+                GravityOverride::Vector(gravity) => GravityOverrideData::Vector(gravity.to_array()),
+                GravityOverride::Scale(scale) => GravityOverrideData::Scale(scale),
+            },
+            priority: self.priority,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: GravityVolumeData, _entities: &EntityMap) -> Self {
+        GravityVolume {
+            gravity: match data.gravity {
+                GravityOverrideData::Vector(gravity) => GravityOverride::Vector(Vec3::from_array(gravity)),
+                GravityOverrideData::Scale(scale) => GravityOverride::Scale(scale),
+            },
+            priority: data.priority,
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum LightSourceTypeData {
+    Warning { pulse_speed: f32 },
+    Energy { flow_speed: f32 },
+    Control { flicker_speed: f32 },
+    Ambient,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LightSourceData {
+    pub color: [f32; 4],
+    pub intensity: f32,
+    pub radius: f32,
+    pub light_type: LightSourceTypeData,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for LightSource {
+    const NAME: &'static str = "LightSource";
+    type Data = LightSourceData;
+
+    fn to_snapshot(&self) -> LightSourceData {
+        let light_type = match self.light_type {
+            LightSourceType::Warning { pulse_speed } => LightSourceTypeData::Warning { pulse_speed },
+            LightSourceType::Energy { flow_speed } => LightSourceTypeData::Energy { flow_speed },
+            LightSourceType::Control { flicker_speed } => LightSourceTypeData::Control { flicker_speed },
+            LightSourceType::Ambient => LightSourceTypeData::Ambient,
+        };
+
+        LightSourceData {
+            color: color_to_array(self.color),
+            intensity: self.intensity,
+            radius: self.radius,
+            light_type,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: LightSourceData, _entities: &EntityMap) -> Self {
+        let light_type = match data.light_type {
+            LightSourceTypeData::Warning { pulse_speed } => LightSourceType::Warning { pulse_speed },
+            LightSourceTypeData::Energy { flow_speed } => LightSourceType::Energy { flow_speed },
+            LightSourceTypeData::Control { flicker_speed } => LightSourceType::Control { flicker_speed },
+            LightSourceTypeData::Ambient => LightSourceType::Ambient,
+        };
+
+        LightSource::new(array_to_color(data.color), data.intensity, data.radius, light_type)
+            .with_enabled(data.enabled)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum RenderModeData {
+    Cube { size: [f32; 3] },
+    Sphere { radius: f32 },
+    Cylinder { radius: f32, height: f32 },
+    Plane { width: f32, height: f32 },
+    UseMeshData,
+    Custom,
+}
+
+/// Renderers are saved by texture and mesh names; the renderer loads the
+/// actual assets again on first use
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RendererData {
+    pub render_mode: RenderModeData,
+    pub color: [f32; 4],
+    pub texture_name: Option<String>,
+    pub custom_mesh_path: Option<String>,
+    pub visible: bool,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Renderer {
+    const NAME: &'static str = "Renderer";
+    type Data = RendererData;
+
+    fn to_snapshot(&self) -> RendererData {
+        let render_mode = match self.render_mode {
+            RenderMode::Cube { size } => RenderModeData::Cube { size: size.to_array() },
+            RenderMode::Sphere { radius } => RenderModeData::Sphere { radius },
+            RenderMode::Cylinder { radius, height } => RenderModeData::Cylinder { radius, height },
+            RenderMode::Plane { width, height } => RenderModeData::Plane { width, height },
+            RenderMode::UseMeshData => RenderModeData::UseMeshData,
+            RenderMode::Custom => RenderModeData::Custom,
+        };
+
+        RendererData {
+            render_mode,
+            color: color_to_array(self.material.color),
+            texture_name: self.material.texture_name.clone(),
+            custom_mesh_path: self.custom_mesh_path.clone(),
+            visible: self.material.visible,
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: RendererData, _entities: &EntityMap) -> Self {
+        let render_mode = match data.render_mode {
+            RenderModeData::Cube { size } => RenderMode::Cube { size: Vec3::from_array(size) },
+            RenderModeData::Sphere { radius } => RenderMode::Sphere { radius },
+            RenderModeData::Cylinder { radius, height } => RenderMode::Cylinder { radius, height },
+            RenderModeData::Plane { width, height } => RenderMode::Plane { width, height },
+            RenderModeData::UseMeshData => RenderMode::UseMeshData,
+            RenderModeData::Custom => RenderMode::Custom,
+        };
+
+        Renderer {
+            render_mode,
+            material: RenderMaterial {
+                color: array_to_color(data.color),
+                texture: None,
+                texture_name: data.texture_name,
+                visible: data.visible,
+            },
+            custom_mesh_path: data.custom_mesh_path,
+            enabled: data.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PathfinderData {
+    pub target: Option<[f32; 2]>,
+    pub current_path: Vec<[f32; 2]>,
+    pub path_index: usize,
+    pub movement_speed: f32,
+    pub rotation_speed: f32,
+    pub stuck_time: f32,
+    pub last_position: [f32; 2],
+    pub needs_recalculation: bool,
+    pub arrival_threshold: f32,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for Pathfinder {
+    const NAME: &'static str = "Pathfinder";
+    type Data = PathfinderData;
+
+    fn to_snapshot(&self) -> PathfinderData {
+        PathfinderData {
+            target: self.target.map(vec2_to_array),
+            current_path: self.current_path.iter().copied().map(vec2_to_array).collect(),
+            path_index: self.path_index,
+            movement_speed: self.movement_speed,
+            rotation_speed: self.rotation_speed,
+            stuck_time: self.stuck_time,
+            last_position: self.last_position.to_array(),
+            needs_recalculation: self.needs_recalculation,
+            arrival_threshold: self.arrival_threshold,
+            enabled: self.enabled,
+        }
+    }
+
+    /// Explored nodes are only kept for visualization and aren't saved
+    fn from_snapshot(data: PathfinderData, _entities: &EntityMap) -> Self {
+        Pathfinder {
+            target: data.target.map(Vec2::from_array),
+            current_path: data.current_path.into_iter().map(Vec2::from_array).collect(),
+            path_index: data.path_index,
+            movement_speed: data.movement_speed,
+            rotation_speed: data.rotation_speed,
+            stuck_time: data.stuck_time,
+            last_position: Vec2::from_array(data.last_position),
+            needs_recalculation: data.needs_recalculation,
+            explored_nodes: Vec::new(),
+            arrival_threshold: data.arrival_threshold,
+            enabled: data.enabled,
+        }
+    }
+}
+
+/// Test bots save how far into the test they are rather than when it started
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestBotData {
+    pub elapsed_seconds: f32,
+    pub test_duration_seconds: f32,
+    pub current_waypoint: usize,
+    pub waypoints: Vec<[f32; 2]>,
+    pub enabled: bool,
+}
+
+impl SnapshotComponent for TestBot {
+    const NAME: &'static str = "TestBot";
+    type Data = TestBotData;
+
+    fn to_snapshot(&self) -> TestBotData {
+        TestBotData {
+            elapsed_seconds: self.start_time.elapsed().as_secs_f32(),
+            test_duration_seconds: self.test_duration.as_secs_f32(),
+            current_waypoint: self.current_waypoint,
+            waypoints: self.waypoints.iter().copied().map(vec2_to_array).collect(),
+            enabled: self.enabled,
+        }
+    }
+
+    fn from_snapshot(data: TestBotData, _entities: &EntityMap) -> Self {
+        let elapsed = Duration::from_secs_f32(data.elapsed_seconds.max(0.0));
+        TestBot {
+            start_time: Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now),
+            test_duration: Duration::from_secs_f32(data.test_duration_seconds.max(0.0)),
+            current_waypoint: data.current_waypoint,
+            waypoints: data.waypoints.into_iter().map(Vec2::from_array).collect(),
+            enabled: data.enabled,
+        }
+    }
+}
+
+/// Parents are saved by entity id and remapped on load
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParentData {
+    pub parent: u32,
+}
+
+impl SnapshotComponent for Parent {
+    const NAME: &'static str = "Parent";
+    type Data = ParentData;
+
+    fn to_snapshot(&self) -> ParentData {
+        ParentData { parent: self.0.id }
+    }
+
+    fn from_snapshot(data: ParentData, entities: &EntityMap) -> Self {
+        // A parent missing from the snapshot becomes an invalid handle
+        Parent(entities.get(data.parent).unwrap_or(Entity::new(data.parent, 0)))
+    }
+
+    /// Goes through `World::set_parent` so the parent's `Children` match
+    fn insert(self, world: &mut World, entity: Entity) {
+        if !world.set_parent(entity, self.0) {
+            println!("⚠️ Snapshot parent {:?} of {:?} is missing", self.0, entity);
+        }
+    }
+}
+
+impl SnapshotComponent for Name {
+    const NAME: &'static str = "Name";
+    type Data = String;
+
+    fn to_snapshot(&self) -> String {
+        self.0.clone()
+    }
+
+    fn from_snapshot(data: String, _entities: &EntityMap) -> Self {
+        Name(data)
+    }
+}
+
+impl SnapshotComponent for Tags {
+    const NAME: &'static str = "Tags";
+    type Data = Vec<String>;
+
+    fn to_snapshot(&self) -> Vec<String> {
+        self.0.clone()
+    }
+
+    fn from_snapshot(data: Vec<String>, _entities: &EntityMap) -> Self {
+        Tags::new(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_world() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.spawn().with(Transform::new(Vec3::new(9.0, 0.0, 9.0))).build();
+        let player = world.spawn()
+            .with(Transform::new(Vec3::new(1.0, 0.6, 2.0)).with_rotation(Vec3::new(0.0, 1.5, 0.0)))
+            .with(Velocity::linear(Vec3::new(0.5, 0.0, 0.0)))
+            .with(Player::new())
+            .with(Collider::dynamic_solid(ColliderShape::Capsule { height: 1.8, radius: 0.25 }))
+            .build();
+        let lamp = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, 0.0)))
+            .with(LightSource::warning(2.0, 4.0))
+            .with(Renderer::sphere(0.2).with_texture_name("tech_panel.png".to_string()))
+            .with(Name::new("Lamp"))
+            .build();
+        world.set_parent(lamp, player);
+
+        // Leave a hole in the id space so loaded entities get different ids
+        let first = world.entities().iter().next().unwrap();
+        world.despawn(first);
+        (world, player, lamp)
+    }
+
+    fn assert_restored(world: &World, entities: &EntityMap, player: Entity, lamp: Entity) {
+        let player = entities.get(player.id).unwrap();
+        let lamp = entities.get(lamp.id).unwrap();
+        assert_eq!(entities.len(), 2);
+
+        assert_eq!(world.get::<Transform>(player).unwrap().rotation, Vec3::new(0.0, 1.5, 0.0));
+        assert_eq!(world.get::<Velocity>(player).unwrap().linear, Vec3::new(0.5, 0.0, 0.0));
+        assert!(world.has::<Player>(player));
+        assert!(matches!(world.get::<Collider>(player).unwrap().shape, ColliderShape::Capsule { .. }));
+
+        assert_eq!(world.parent(lamp), Some(player));
+        assert_eq!(world.find_by_name("Lamp"), Some(lamp));
+        assert_eq!(world.children(player), &[lamp]);
+        assert_eq!(world.get::<LightSource>(lamp).unwrap().radius, 4.0);
+        let renderer = world.get::<Renderer>(lamp).unwrap();
+        assert_eq!(renderer.material.texture_name.as_deref(), Some("tech_panel.png"));
+        assert!(matches!(renderer.render_mode, RenderMode::Sphere { radius } if radius == 0.2));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let (world, player, lamp) = build_world();
+        let registry = SnapshotRegistry::standard();
+
+        let json = registry.save_json(&world).unwrap();
+        let mut restored = World::new();
+        restored.spawn().build();
+        let entities = registry.load_json(&mut restored, &json).unwrap();
+        assert_restored(&restored, &entities, player, lamp);
+
+        let bytes = registry.save_binary(&world).unwrap();
+        assert!(bytes.len() < json.len());
+        let mut restored = World::new();
+        let entities = registry.load_binary(&mut restored, &bytes).unwrap();
+        assert_restored(&restored, &entities, player, lamp);
+    }
+
+    #[test]
+    fn test_snapshot_only_saves_registered_components() {
+        let (world, _, lamp) = build_world();
+        let mut registry = SnapshotRegistry::new();
+        registry.register::<LightSource>();
+
+        let mut restored = World::new();
+        let json = registry.save_json(&world).unwrap();
+        let entities = registry.load_json(&mut restored, &json).unwrap();
+        assert_eq!(entities.len(), 1);
+        let lamp = entities.get(lamp.id).unwrap();
+        assert!(restored.has::<LightSource>(lamp));
+        assert!(!restored.has::<Transform>(lamp));
+    }
+}
@@ -1,6 +1,16 @@
 //! World - the main ECS container
 
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
 use crate::ecs::{Entity, EntityManager, ComponentManager, Component};
+use crate::ecs::component::{TypedComponentStorage, RemovedComponents, ComponentHook, ComponentHooks};
+use crate::ecs::resource::{Resource, ResourceManager};
+use crate::ecs::commands::{Commands, CommandQueue};
+use crate::ecs::event::{Event, Events, EventWriter, EntitySpawned, EntityDespawned};
+use crate::ecs::query::{Query, QueryData, QueryFilter, ReadOnlyQueryData, IncludeInactive, With, Without};
+use crate::ecs::components::{Transform, PreviousTransform, Parent, Children, GlobalTransform, RenderTransform, Name, Tags};
+use crate::ecs::components::identity::IdentityIndex;
+use crate::ecs::reflect::Reflect;
 
 /// Read-only view of the world for component updates
 pub struct WorldView<'a> {
@@ -38,7 +48,7 @@ impl<'a> WorldView<'a> {
 
     /// Get all entities
     pub fn all_entities(&self) -> impl Iterator<Item = Entity> + '_ {
-        self.entities.all_entities().into_iter()
+        self.entities.iter()
     }
 }
 
@@ -48,6 +58,14 @@ pub struct World {
     entities: EntityManager,
     /// Component storage
     components: ComponentManager,
+    /// Global resources shared by all systems
+    resources: ResourceManager,
+    /// Buffer swaps for every registered event type
+    event_updaters: Vec<fn(&mut ResourceManager)>,
+    /// Edits recorded through `Commands`, applied by `apply_commands`
+    command_queue: CommandQueue,
+    /// Entities by `Name` and `Tags`
+    identity: IdentityIndex,
 }
 
 impl World {
@@ -56,44 +74,468 @@ impl World {
         Self {
             entities: EntityManager::new(),
             components: ComponentManager::new(),
+            resources: ResourceManager::new(),
+            event_updaters: Vec::new(),
+            command_queue: CommandQueue::default(),
+            identity: IdentityIndex::default(),
         }
     }
     
     /// Create a new entity
-    pub fn spawn(&mut self) -> EntityBuilder {
+    pub fn spawn(&mut self) -> EntityBuilder<'_> {
         let entity = self.entities.create();
+        self.send_event(EntitySpawned { entity });
         EntityBuilder::new(self, entity)
     }
     
-    /// Destroy an entity and all its components
-    pub fn despawn(&mut self, entity: Entity) -> bool {
-        if self.entities.destroy(entity) {
-            self.components.remove_all(entity);
+    /// Bring an entity reserved by `Commands::spawn` to life
+    pub(crate) fn spawn_reserved(&mut self, entity: Entity) -> bool {
+        if self.entities.materialize(entity) {
+            self.send_event(EntitySpawned { entity });
             true
         } else {
             false
         }
     }
     
+    /// Destroy an entity, its components and all of its descendants
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entities.is_valid(entity) {
+            return false;
+        }
+        
+        self.remove_parent(entity);
+        self.despawn_tree(entity);
+        true
+    }
+    
+    /// Destroy an entity and its descendants without touching its parent
+    fn despawn_tree(&mut self, entity: Entity) {
+        if let Some(children) = self.components.get::<Children>(entity).map(|children| children.0.clone()) {
+            for child in children {
+                self.despawn_tree(child);
+            }
+        }
+        
+        if self.entities.is_valid(entity) {
+            self.run_entity_hooks(entity, |hooks| hooks.on_remove);
+            self.unindex_component::<Name>(entity);
+            self.unindex_component::<Tags>(entity);
+        }
+        if self.entities.destroy(entity) {
+            self.components.remove_all(entity);
+            self.send_event(EntityDespawned { entity });
+        }
+    }
+    
+    /// Attach `child` to `parent`, detaching it from any previous parent
+    ///
+    /// Returns false if either entity is invalid or the attachment would
+    /// create a cycle.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> bool {
+        if child == parent || !self.is_valid(child) || !self.is_valid(parent) {
+            return false;
+        }
+        
+        // Refuse to attach an entity below one of its own descendants
+        let mut ancestor = Some(parent);
+        while let Some(current) = ancestor {
+            if current == child {
+                return false;
+            }
+            ancestor = self.parent(current);
+        }
+        
+        self.remove_parent(child);
+        self.add(child, Parent(parent));
+        match self.components.get_mut::<Children>(parent) {
+            Some(children) => children.0.push(child),
+            None => {
+                self.add(parent, Children(vec![child]));
+            }
+        }
+        self.refresh_active(child);
+        true
+    }
+    
+    /// Detach an entity from its parent, making it a root again
+    pub fn remove_parent(&mut self, child: Entity) -> bool {
+        let parent = match self.parent(child) {
+            Some(parent) => parent,
+            None => return false,
+        };
+        
+        self.remove::<Parent>(child);
+        if let Some(children) = self.components.get_mut::<Children>(parent) {
+            children.0.retain(|&other| other != child);
+            if children.0.is_empty() {
+                self.remove::<Children>(parent);
+            }
+        }
+        self.refresh_active(child);
+        true
+    }
+    
+    /// Get the parent of an entity
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.get::<Parent>(entity).map(|parent| parent.0)
+    }
+    
+    /// Get the children of an entity
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.get::<Children>(entity).map(|children| children.0.as_slice()).unwrap_or(&[])
+    }
+    
+    /// Update `GlobalTransform` for every entity with a `Transform`, walking
+    /// down from the root entities
+    ///
+    /// Only subtrees whose `Transform` or `Parent` changed since the last
+    /// `clear_trackers` are recomputed.
+    pub fn propagate_transforms(&mut self) {
+        // Roots that lost their Transform no longer have a world-space transform
+        let stale: Vec<Entity> = self.removed_components::<Transform>().iter()
+            .filter(|&entity| self.is_valid(entity) && !self.has::<Transform>(entity) && !self.has::<Parent>(entity))
+            .collect();
+        for entity in stale {
+            self.remove::<GlobalTransform>(entity);
+        }
+        
+        // Entities detached from their parent become roots and must be recomputed
+        let detached: Vec<Entity> = self.removed_components::<Parent>().iter().collect();
+        
+        let roots: Vec<Entity> = self.query_filtered::<Entity, (With<Transform>, Without<Parent>)>()
+            .into_iter()
+            .collect();
+        
+        for root in roots {
+            let dirty = detached.contains(&root);
+            self.propagate_from(root, None, dirty);
+        }
+    }
+    
+    /// Recompute an entity's global transform if it or an ancestor changed,
+    /// then continue with its children
+    fn propagate_from(&mut self, entity: Entity, parent: Option<&GlobalTransform>, parent_dirty: bool) {
+        let dirty = parent_dirty
+            || !self.components.has::<GlobalTransform>(entity)
+            || self.is_changed::<Transform>(entity)
+            || self.is_changed::<Parent>(entity);
+        
+        if dirty {
+            let global = match (parent, self.get::<Transform>(entity)) {
+                (Some(parent), Some(local)) => parent.mul_transform(local),
+                (Some(parent), None) => parent.clone(),
+                (None, Some(local)) => GlobalTransform::from(local.clone()),
+                (None, None) => return,
+            };
+            match self.components.get_mut::<GlobalTransform>(entity) {
+                Some(existing) => *existing = global,
+                None => {
+                    self.add(entity, global);
+                }
+            }
+        }
+        
+        let children = match self.get::<Children>(entity) {
+            Some(children) => children.0.clone(),
+            None => return,
+        };
+        let global = match self.get::<GlobalTransform>(entity) {
+            Some(global) => global.clone(),
+            None => return,
+        };
+        for child in children {
+            self.propagate_from(child, Some(&global), dirty);
+        }
+    }
+
+    /// Update `RenderTransform` for every entity with a `PreviousTransform`
+    /// and for their descendants, blending local transforms by `alpha`
+    ///
+    /// Run once per rendered frame after `propagate_transforms`, with how far
+    /// the frame is between the last two ticks (`FixedTime::alpha`). Entities
+    /// that are no longer interpolated lose their `RenderTransform`.
+    pub fn interpolate_transforms(&mut self, alpha: f32) {
+        let mut interpolated = HashSet::new();
+        let roots: Vec<Entity> = self.query_filtered::<Entity, (With<Transform>, Without<Parent>)>()
+            .into_iter()
+            .collect();
+        for root in roots {
+            self.interpolate_from(root, None, alpha, &mut interpolated);
+        }
+
+        let stale: Vec<Entity> = self.query_filtered::<Entity, With<RenderTransform>>()
+            .into_iter()
+            .filter(|entity| !interpolated.contains(entity))
+            .collect();
+        for entity in stale {
+            self.remove::<RenderTransform>(entity);
+        }
+    }
+
+    /// Blend an entity's transform if it or an ancestor is interpolated, then
+    /// continue with its children
+    fn interpolate_from(&mut self, entity: Entity, parent: Option<&GlobalTransform>, alpha: f32, interpolated: &mut HashSet<Entity>) {
+        let local = match (self.get::<Transform>(entity), self.get::<PreviousTransform>(entity)) {
+            (Some(local), Some(previous)) => Some(local.interpolated(&previous.0, alpha)),
+            (Some(local), None) if parent.is_some() => Some(local.clone()),
+            _ => None,
+        };
+        let render = match (parent, local) {
+            (Some(parent), Some(local)) => Some(parent.mul_transform(&local)),
+            (Some(parent), None) => Some(parent.clone()),
+            (None, Some(local)) => Some(GlobalTransform::from(local)),
+            (None, None) => None,
+        };
+
+        if let Some(render) = &render {
+            match self.get_mut::<RenderTransform>(entity) {
+                Some(existing) => *existing = RenderTransform::from(render.clone()),
+                None => {
+                    self.add(entity, RenderTransform::from(render.clone()));
+                }
+            }
+            interpolated.insert(entity);
+        }
+
+        let children = match self.get::<Children>(entity) {
+            Some(children) => children.0.clone(),
+            None => return,
+        };
+        for child in children {
+            self.interpolate_from(child, render.as_ref(), alpha, interpolated);
+        }
+    }
+
+    /// Start a new change detection period
+    ///
+    /// `Added<T>`, `Changed<T>`, `is_added`, `is_changed` and
+    /// `removed_components` all report what happened since the last call.
+    /// The game calls this once at the start of every frame.
+    pub fn clear_trackers(&mut self) {
+        self.components.clear_trackers();
+    }
+    
+    /// Check if an entity's component `T` was added since the last `clear_trackers`
+    #[allow(dead_code)] // The game reacts to new components through hooks instead
+    pub fn is_added<T: Component>(&self, entity: Entity) -> bool {
+        self.is_valid(entity) && self.components.storage::<T>().is_some_and(|storage| storage.is_added(entity))
+    }
+    
+    /// Check if an entity's component `T` was added or mutably accessed since
+    /// the last `clear_trackers`
+    pub fn is_changed<T: Component>(&self, entity: Entity) -> bool {
+        self.is_valid(entity) && self.components.storage::<T>().is_some_and(|storage| storage.is_changed(entity))
+    }
+    
+    /// Entities that lost component `T`, or were despawned with it, since the
+    /// last `clear_trackers`
+    pub fn removed_components<T: Component>(&self) -> RemovedComponents<'_, T> {
+        RemovedComponents::new(self.components.storage::<T>().map(|storage| storage.removed()).unwrap_or(&[]))
+    }
+    
     /// Check if an entity is valid
     pub fn is_valid(&self, entity: Entity) -> bool {
         self.entities.is_valid(entity)
     }
     
-    /// Add a component to an entity
-    pub fn add<T: Component>(&mut self, entity: Entity, component: T) -> bool {
+    /// Check if an entity and all of its ancestors are active
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.entities.is_active(entity)
+    }
+    
+    /// Check if an entity was itself set active, regardless of its ancestors
+    pub fn is_active_self(&self, entity: Entity) -> bool {
+        self.entities.is_active_self(entity)
+    }
+    
+    /// Activate or deactivate an entity together with its descendants
+    ///
+    /// Inactive entities keep their components but are skipped by queries
+    /// and component updates. An entity is only active if it and all of its
+    /// ancestors are, so reactivating a parent restores exactly the children
+    /// that were active before.
+    pub fn set_active(&mut self, entity: Entity, active: bool) {
+        if self.is_valid(entity) {
+            let parent_active = self.parent(entity).is_none_or(|parent| self.entities.is_active(parent));
+            self.update_active_tree(entity, active, parent_active);
+        }
+    }
+    
+    /// Recompute an entity's effective active state after its parent changed
+    fn refresh_active(&mut self, entity: Entity) {
+        let active_self = self.entities.is_active_self(entity);
+        self.set_active(entity, active_self);
+    }
+    
+    fn update_active_tree(&mut self, entity: Entity, active_self: bool, parent_active: bool) {
+        let active = active_self && parent_active;
+        self.with_enable_hooks(entity, |world| world.entities.set_active_state(entity, active_self, active));
+        
+        for child in self.children(entity).to_vec() {
+            let child_active_self = self.entities.is_active_self(child);
+            self.update_active_tree(child, child_active_self, active);
+        }
+    }
+    
+    /// Enable or disable every component of an entity
+    pub fn set_enabled(&mut self, entity: Entity, enabled: bool) {
         if self.entities.is_valid(entity) {
-            self.components.add(entity, component)
-        } else {
-            false
+            self.with_enable_hooks(entity, |world| world.components.set_enabled_all(entity, enabled));
+        }
+    }
+    
+    /// Make a change to an entity, then run `on_enable`/`on_disable` for the
+    /// hooked components it switched on or off
+    ///
+    /// A component counts as on while it is enabled and its entity is active.
+    fn with_enable_hooks(&mut self, entity: Entity, change: impl FnOnce(&mut Self)) {
+        let before: Vec<Option<bool>> = inventory::iter::<ComponentHooks>
+            .into_iter()
+            .map(|hooks| self.is_switched_on((hooks.type_id)(), entity))
+            .collect();
+        change(self);
+        
+        for (hooks, was_on) in inventory::iter::<ComponentHooks>.into_iter().zip(before) {
+            let hook = match (was_on, self.is_switched_on((hooks.type_id)(), entity)) {
+                (Some(false), Some(true)) => hooks.on_enable,
+                (Some(true), Some(false)) => hooks.on_disable,
+                _ => None,
+            };
+            if let Some(hook) = hook {
+                hook(self, entity);
+            }
+        }
+    }
+    
+    /// Check if an entity's component is enabled and the entity active,
+    /// `None` if it has no such component
+    fn is_switched_on(&self, type_id: TypeId, entity: Entity) -> Option<bool> {
+        self.components.enabled_type(type_id, entity)
+            .map(|enabled| enabled && self.entities.is_active(entity))
+    }
+    
+    /// Get a buffer for deferred edits to this world
+    ///
+    /// The buffer doesn't borrow the world, so it can be used while iterating
+    /// queries. Recorded commands run on the next `apply_commands`.
+    pub fn commands(&self) -> Commands {
+        Commands::new(self.command_queue.clone(), self.entities.reserver())
+    }
+    
+    /// Apply all recorded commands in the order they were recorded
+    pub fn apply_commands(&mut self) {
+        loop {
+            // Commands may record further commands, so drain until empty
+            let commands = std::mem::take(&mut *self.command_queue.lock().unwrap());
+            if commands.is_empty() {
+                break;
+            }
+            for command in commands {
+                command(self);
+            }
+        }
+    }
+    
+    /// Add a component to an entity, replacing any existing one of its type
+    pub fn add<T: Component>(&mut self, entity: Entity, component: T) -> bool {
+        if !self.entities.is_valid(entity) {
+            return false;
         }
+        
+        if self.components.has::<T>(entity) {
+            self.run_component_hooks(entity, TypeId::of::<T>(), |hooks| hooks.on_remove);
+        }
+        self.index_component(entity, &component);
+        let added = self.components.add(entity, component);
+        self.run_component_hooks(entity, TypeId::of::<T>(), |hooks| hooks.on_add);
+        added
     }
     
     /// Remove a component from an entity
     pub fn remove<T: Component>(&mut self, entity: Entity) -> bool {
+        if self.has::<T>(entity) {
+            self.run_component_hooks(entity, TypeId::of::<T>(), |hooks| hooks.on_remove);
+            self.unindex_component::<T>(entity);
+        }
         self.components.remove::<T>(entity)
     }
     
+    /// Run one lifecycle hook of every `ComponentHooks` registered for a type
+    fn run_component_hooks(&mut self, entity: Entity, type_id: TypeId, select: fn(&ComponentHooks) -> Option<ComponentHook>) {
+        for hooks in inventory::iter::<ComponentHooks> {
+            if (hooks.type_id)() == type_id {
+                if let Some(hook) = select(hooks) {
+                    hook(self, entity);
+                }
+            }
+        }
+    }
+    
+    /// Run one lifecycle hook for every hooked component the entity has
+    fn run_entity_hooks(&mut self, entity: Entity, select: fn(&ComponentHooks) -> Option<ComponentHook>) {
+        for hooks in inventory::iter::<ComponentHooks> {
+            if let Some(hook) = select(hooks) {
+                if self.components.has_type((hooks.type_id)(), entity) {
+                    hook(self, entity);
+                }
+            }
+        }
+    }
+    
+    /// Record a `Name` or `Tags` component in the index, replacing the
+    /// entity's current one
+    fn index_component<T: Component>(&mut self, entity: Entity, component: &T) {
+        self.unindex_component::<T>(entity);
+        
+        let component: &dyn Any = component;
+        if let Some(name) = component.downcast_ref::<Name>() {
+            self.identity.insert_name(entity, name);
+        } else if let Some(tags) = component.downcast_ref::<Tags>() {
+            self.identity.insert_tags(entity, tags);
+        }
+    }
+    
+    /// Drop an entity's `Name` or `Tags` component from the index
+    fn unindex_component<T: Component>(&mut self, entity: Entity) {
+        let type_id = TypeId::of::<T>();
+        if type_id == TypeId::of::<Name>() {
+            if let Some(name) = self.components.get::<Name>(entity) {
+                self.identity.remove_name(entity, name);
+            }
+        } else if type_id == TypeId::of::<Tags>() {
+            if let Some(tags) = self.components.get::<Tags>(entity) {
+                self.identity.remove_tags(entity, tags);
+            }
+        }
+    }
+    
+    /// Find the first entity spawned with a given name
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.identity.named(name).iter().copied().find(|&entity| self.is_valid(entity))
+    }
+    
+    /// Iterate over the entities carrying a tag, in the order they were tagged
+    pub fn query_tagged<'w>(&'w self, tag: &str) -> impl Iterator<Item = Entity> + 'w {
+        self.identity.tagged(tag).iter().copied().filter(move |&entity| self.is_valid(entity))
+    }
+    
+    /// Name or rename an entity
+    pub fn set_name(&mut self, entity: Entity, name: impl Into<String>) -> bool {
+        self.add(entity, Name::new(name))
+    }
+    
+    /// Add a tag to an entity, creating its `Tags` if needed
+    #[allow(dead_code)] // Levels attach all of an object's tags at once with `Tags::new`
+    pub fn add_tag(&mut self, entity: Entity, tag: impl Into<String>) -> bool {
+        if !self.is_valid(entity) {
+            return false;
+        }
+        let mut tags = self.components.get::<Tags>(entity).cloned().unwrap_or_default();
+        tags.insert(tag);
+        self.add(entity, tags)
+    }
+    
     /// Get a component for an entity
     pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
         if self.entities.is_valid(entity) {
@@ -121,13 +563,41 @@ impl World {
         }
     }
     
+    /// Every reflected component on an entity
+    pub fn reflect(&self, entity: Entity) -> Vec<&dyn Reflect> {
+        inventory::iter::<crate::ecs::component::ComponentRegistration>
+            .into_iter()
+            .filter_map(|registration| (registration.reflect?.get)(self, entity))
+            .collect()
+    }
+    
+    /// Look up a reflected component on an entity by type name, e.g. "Transform"
+    #[allow(dead_code)] // The game's debug output walks every component with `reflect`
+    pub fn reflect_component(&self, entity: Entity, type_name: &str) -> Option<&dyn Reflect> {
+        let reflect = inventory::iter::<crate::ecs::component::ComponentRegistration>
+            .into_iter()
+            .find(|registration| registration.type_name == type_name)?
+            .reflect?;
+        (reflect.get)(self, entity)
+    }
+    
+    /// Look up a reflected component on an entity by type name for editing
+    #[allow(dead_code)]
+    pub fn reflect_component_mut(&mut self, entity: Entity, type_name: &str) -> Option<&mut dyn Reflect> {
+        let reflect = inventory::iter::<crate::ecs::component::ComponentRegistration>
+            .into_iter()
+            .find(|registration| registration.type_name == type_name)?
+            .reflect?;
+        (reflect.get_mut)(self, entity)
+    }
+    
     /// Get component storage for iteration
-    pub fn storage<T: Component>(&self) -> Option<&crate::ecs::component::TypedComponentStorage<T>> {
+    pub fn storage<T: Component>(&self) -> Option<&TypedComponentStorage<T>> {
         self.components.storage::<T>()
     }
     
     /// Get mutable component storage for iteration
-    pub fn storage_mut<T: Component>(&mut self) -> Option<&mut crate::ecs::component::TypedComponentStorage<T>> {
+    pub fn storage_mut<T: Component>(&mut self) -> Option<&mut TypedComponentStorage<T>> {
         self.components.storage_mut::<T>()
     }
     
@@ -146,65 +616,117 @@ impl World {
         &mut self.components
     }
     
-    /// Simple query for entities with 3 components (immutable)
-    pub fn query_3<T1: Component, T2: Component, T3: Component>(&self) -> Vec<(Entity, &T1, &T2, &T3)> {
-        let mut results = Vec::new();
-        
-        // Get all entities that have all three component types
-        for entity in self.entities.all_entities() {
-            if let (Some(comp1), Some(comp2), Some(comp3)) = (
-                self.get::<T1>(entity),
-                self.get::<T2>(entity), 
-                self.get::<T3>(entity)
-            ) {
-                results.push((entity, comp1, comp2, comp3));
-            }
-        }
-        
-        results
+    /// Query entities for read-only component data
+    ///
+    /// `Q` is any tuple of `Entity`, `&T` and `Option<&T>`.
+    pub fn query<Q: ReadOnlyQueryData>(&self) -> Query<'_, Q> {
+        self.query_filtered::<Q, ()>()
     }
     
-    /// Simple query for entities with 2 components (immutable)
-    pub fn query_2<T1: Component, T2: Component>(&self) -> Vec<(Entity, &T1, &T2)> {
-        let mut results = Vec::new();
-        
-        // Get all entities that have both component types
-        for entity in self.entities.all_entities() {
-            if let (Some(comp1), Some(comp2)) = (
-                self.get::<T1>(entity),
-                self.get::<T2>(entity)
-            ) {
-                results.push((entity, comp1, comp2));
-            }
-        }
-        
-        results
+    /// Query entities for read-only component data, restricted by a filter
+    /// such as `With<T>`, `Without<T>` or a tuple of them
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&self) -> Query<'_, Q, F> {
+        Query::new_read_only(&self.entities, &self.components)
     }
     
-    /// Simple query for entities with 1 component (immutable)
-    pub fn query_1<T: Component>(&self) -> Vec<(Entity, &T)> {
-        let mut results = Vec::new();
-        
-        // Get all entities that have the component type
-        for entity in self.entities.all_entities() {
-            if let Some(comp) = self.get::<T>(entity) {
-                results.push((entity, comp));
+    /// Query entities with mutable component access
+    ///
+    /// `Q` may also contain `&mut T` and `Option<&mut T>`. Panics if the same
+    /// component type is borrowed mutably more than once.
+    pub fn query_mut<Q: QueryData>(&mut self) -> Query<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+    
+    /// Query entities with mutable component access, restricted by a filter
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> Query<'_, Q, F> {
+        // SAFETY: the world is exclusively borrowed for the lifetime of the query
+        unsafe { Query::new(&self.entities, &mut self.components) }
+    }
+    
+    /// Insert a resource, replacing any existing resource of the same type
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) {
+        self.resources.insert(resource);
+    }
+    
+    /// Get a resource
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get::<R>()
+    }
+    
+    /// Get a mutable resource
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut::<R>()
+    }
+    
+    /// Remove a resource, returning it
+    #[allow(dead_code)] // The game's resources live as long as the world so far
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources.remove::<R>()
+    }
+    
+    /// Check if a resource exists
+    pub fn has_resource<R: Resource>(&self) -> bool {
+        self.resources.has::<R>()
+    }
+    
+    /// Temporarily take a resource out of the world so it can be used
+    /// alongside mutable world access. Returns `None` if the resource is missing.
+    pub fn resource_scope<R: Resource, T>(&mut self, f: impl FnOnce(&mut World, &mut R) -> T) -> Option<T> {
+        let mut resource = self.resources.remove::<R>()?;
+        let result = f(self, &mut resource);
+        self.resources.insert(resource);
+        Some(result)
+    }
+    
+    /// Register an event type, creating its `Events<T>` channel
+    pub fn add_event<T: Event>(&mut self) {
+        if self.resources.has::<Events<T>>() {
+            return;
+        }
+        self.resources.insert(Events::<T>::new());
+        self.event_updaters.push(|resources| {
+            if let Some(events) = resources.get_mut::<Events<T>>() {
+                events.update();
             }
+        });
+    }
+    
+    /// Send an event. Returns false if the event type was never registered.
+    pub fn send_event<T: Event>(&mut self, event: T) -> bool {
+        self.event_writer().map(|mut writer| writer.send(event)).is_some()
+    }
+    
+    /// Get the channel of a registered event type
+    pub fn events<T: Event>(&self) -> Option<&Events<T>> {
+        self.resources.get::<Events<T>>()
+    }
+    
+    /// Get a writer for a registered event type
+    pub fn event_writer<T: Event>(&mut self) -> Option<EventWriter<'_, T>> {
+        self.resources.get_mut::<Events<T>>().map(EventWriter::new)
+    }
+    
+    /// Swap the buffers of every event channel; called once per frame
+    pub fn update_events(&mut self) {
+        for update in &self.event_updaters {
+            update(&mut self.resources);
         }
-        
-        results
     }
     
-    /// Clear all entities and components
+    /// Clear all entities and components (resources are kept)
     pub fn clear(&mut self) {
+        for entity in self.entities.iter().collect::<Vec<_>>() {
+            self.run_entity_hooks(entity, |hooks| hooks.on_remove);
+        }
         self.components.clear();
-        self.entities = EntityManager::new();
+        self.entities.clear();
+        self.identity.clear();
     }
     
     /// Check if a component should be processed by systems
-    /// Returns true only if the entity is valid AND the component exists AND the component is enabled
+    /// Returns true only if the entity is active AND the component exists AND the component is enabled
     pub fn should_process_component<T: crate::ecs::Component>(&self, entity: Entity) -> bool {
-        if !self.is_valid(entity) {
+        if !self.is_active(entity) {
             return false;
         }
         
@@ -216,17 +738,14 @@ impl World {
         }
     }
     
-    /// Get multiple mutable component references for the same entity
-    /// This is safe because we're getting different component types for the same entity
-    pub fn get_mut_pair<T1: Component + 'static, T2: Component + 'static>(&mut self, entity: Entity) -> (Option<&mut T1>, Option<&mut T2>) {
-        // Get the first component
-        let comp1 = self.components.get_mut::<T1>(entity);
-        let comp2 = self.components.get_mut::<T2>(entity);
-        
-        // This is a simplified version - in a real ECS we'd need more sophisticated handling
-        // For now, we'll just return None for both to avoid borrowing issues
-        // TODO: Implement proper multi-component access
-        (None, None)
+    /// Get mutable references to two different component types on the same entity
+    ///
+    /// Like `get_mut`, this ignores whether the entity is active. Panics if
+    /// `T1` and `T2` are the same type.
+    pub fn get_mut_pair<T1: Component, T2: Component>(&mut self, entity: Entity) -> (Option<&mut T1>, Option<&mut T2>) {
+        self.query_filtered_mut::<(Option<&mut T1>, Option<&mut T2>), IncludeInactive>()
+            .into_item(entity)
+            .unwrap_or((None, None))
     }
 
     /// Update all registered components using the inventory system
@@ -237,6 +756,74 @@ impl World {
         }
     }
     
+    /// Run the read-only `Component::pre_update` phase for every registered component type
+    pub fn run_pre_update_phase(&mut self, delta_time: f32) {
+        for registration in inventory::iter::<crate::ecs::component::ComponentRegistration> {
+            (registration.pre_updater)(self, delta_time);
+        }
+    }
+    
+    /// Run the `Component::update` phase for every registered component type
+    pub fn run_update_phase(&mut self, delta_time: f32) {
+        for registration in inventory::iter::<crate::ecs::component::ComponentRegistration> {
+            (registration.phase_updater)(self, delta_time);
+        }
+    }
+    
+    /// Call `Component::pre_update` on every enabled component of type `T`
+    ///
+    /// Only components whose hook reports a modification are marked changed.
+    ///
+    /// The components' own storage is detached while they run, so a component
+    /// cannot see other components of its type through the world.
+    pub fn pre_update_component_type<T: Component>(&mut self, delta_time: f32) {
+        if let Some(mut storage) = self.components.take_storage::<T>() {
+            if let Some(typed) = storage.as_any_mut().downcast_mut::<TypedComponentStorage<T>>() {
+                let mut modified = Vec::new();
+                for (entity, component) in typed.iter_mut_untracked() {
+                    if self.entities.is_active(entity) && component.is_enabled() && component.pre_update(delta_time, self, entity) {
+                        modified.push(entity);
+                    }
+                }
+                for entity in modified {
+                    typed.set_changed(entity);
+                }
+            }
+            self.components.restore_storage::<T>(storage);
+        }
+    }
+    
+    /// Call `Component::update` on every enabled component of type `T`
+    ///
+    /// Only components whose hook reports a modification are marked changed.
+    ///
+    /// Components may modify the rest of the world. Components whose entity is
+    /// despawned during the phase are dropped afterwards.
+    pub fn phase_update_component_type<T: Component>(&mut self, delta_time: f32) {
+        if let Some(mut storage) = self.components.take_storage::<T>() {
+            if let Some(typed) = storage.as_any_mut().downcast_mut::<TypedComponentStorage<T>>() {
+                let mut modified = Vec::new();
+                for (entity, component) in typed.iter_mut_untracked() {
+                    if self.entities.is_active(entity) && component.is_enabled() && component.update(delta_time, self, entity) {
+                        modified.push(entity);
+                    }
+                }
+                for entity in modified {
+                    typed.set_changed(entity);
+                }
+                
+                let despawned: Vec<Entity> = typed.entities().iter()
+                    .copied()
+                    .filter(|&entity| !self.entities.is_valid(entity))
+                    .collect();
+                for entity in despawned {
+                    typed.remove(entity);
+                }
+            }
+            self.components.restore_storage::<T>(storage);
+        }
+    }
+    
     /// Update all components of a specific type that implement AutoUpdatable
     pub fn update_component_type<T: crate::ecs::component::AutoUpdatable>(&mut self, delta_time: f32) {
         // Collect entities with this component type to avoid borrowing issues
@@ -257,7 +844,7 @@ impl World {
             // Check if component should be updated
             let should_update = {
                 if let Some(component) = self.get::<T>(entity) {
-                    entity.enabled && component.is_enabled()
+                    self.entities.is_active(entity) && component.is_enabled()
                 } else {
                     false
                 }
@@ -311,6 +898,7 @@ impl<'w> EntityBuilder<'w> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ecs::With;
     
     #[derive(Debug, PartialEq)]
     struct Position {
@@ -376,4 +964,421 @@ mod tests {
         assert!(!world.is_valid(entity));
         assert!(!world.has::<Position>(entity));
     }
-} 
\ No newline at end of file
+    
+    #[derive(Debug, PartialEq)]
+    struct Frozen;
+    
+    impl Component for Frozen {}
+    
+    #[test]
+    fn test_query_mutable_tuple() {
+        let mut world = World::new();
+        
+        let moving = world.spawn()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { dx: 1.0, dy: 2.0 })
+            .build();
+        let still = world.spawn()
+            .with(Position { x: 5.0, y: 5.0 })
+            .build();
+        
+        for (_, position, velocity) in world.query_mut::<(Entity, &mut Position, &Velocity)>() {
+            position.x += velocity.dx;
+            position.y += velocity.dy;
+        }
+        
+        assert_eq!(world.get::<Position>(moving), Some(&Position { x: 1.0, y: 2.0 }));
+        assert_eq!(world.get::<Position>(still), Some(&Position { x: 5.0, y: 5.0 }));
+        assert_eq!(world.query::<&Position>().count(), 2);
+    }
+    
+    #[test]
+    fn test_query_filters() {
+        use crate::ecs::query::Without;
+        
+        let mut world = World::new();
+        
+        let frozen = world.spawn()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Frozen)
+            .build();
+        let free = world.spawn()
+            .with(Position { x: 0.0, y: 0.0 })
+            .build();
+        
+        let with: Vec<Entity> = world.query_filtered::<Entity, With<Frozen>>().into_iter().collect();
+        let without: Vec<Entity> = world.query_filtered::<Entity, (With<Position>, Without<Frozen>)>().into_iter().collect();
+        assert_eq!(with, vec![frozen]);
+        assert_eq!(without, vec![free]);
+        
+        // Filtering on a component type that was never stored matches nothing
+        assert_eq!(world.query_filtered::<Entity, With<Velocity>>().count(), 0);
+    }
+    
+    #[test]
+    fn test_get_mut_pair() {
+        let mut world = World::new();
+        
+        let entity = world.spawn()
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Velocity { dx: 3.0, dy: 4.0 })
+            .build();
+        
+        if let (Some(position), Some(velocity)) = world.get_mut_pair::<Position, Velocity>(entity) {
+            position.x = velocity.dx;
+            velocity.dy = 0.0;
+        }
+        
+        assert_eq!(world.get::<Position>(entity), Some(&Position { x: 3.0, y: 0.0 }));
+        assert_eq!(world.get::<Velocity>(entity), Some(&Velocity { dx: 3.0, dy: 0.0 }));
+        
+        // Inactive entities are reachable, like with `get_mut`
+        world.set_active(entity, false);
+        if let (Some(position), Some(velocity)) = world.get_mut_pair::<Position, Velocity>(entity) {
+            position.y = 1.0;
+            velocity.dx = 0.0;
+        }
+        assert_eq!(world.get::<Position>(entity), Some(&Position { x: 3.0, y: 1.0 }));
+        assert_eq!(world.get::<Velocity>(entity), Some(&Velocity { dx: 0.0, dy: 0.0 }));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn test_query_rejects_aliasing_mutable_access() {
+        let mut world = World::new();
+        world.spawn().with(Position { x: 0.0, y: 0.0 }).build();
+        
+        let _ = world.query_mut::<(&mut Position, &Position)>();
+    }
+    
+    #[test]
+    fn test_resources() {
+        #[derive(Debug, PartialEq)]
+        struct Score(u32);
+        
+        impl Resource for Score {}
+        
+        let mut world = World::new();
+        assert!(!world.has_resource::<Score>());
+        
+        world.insert_resource(Score(1));
+        if let Some(score) = world.resource_mut::<Score>() {
+            score.0 += 1;
+        }
+        assert_eq!(world.resource::<Score>(), Some(&Score(2)));
+        
+        world.resource_scope::<Score, _>(|world, score| {
+            assert!(!world.has_resource::<Score>());
+            score.0 = 10;
+        });
+        assert_eq!(world.remove_resource::<Score>(), Some(Score(10)));
+        assert!(!world.has_resource::<Score>());
+    }
+    
+    #[test]
+    fn test_hierarchy_propagation_and_cascading_despawn() {
+        use crate::ecs::components::{Transform, GlobalTransform};
+        use macroquad::prelude::Vec3;
+        
+        let mut world = World::new();
+        let parent = world.spawn()
+            .with(Transform::new(Vec3::new(10.0, 0.0, 0.0)).with_rotation(Vec3::new(0.0, std::f32::consts::FRAC_PI_2, 0.0)))
+            .build();
+        let child = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 0.0, -2.0)))
+            .build();
+        let grandchild = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, 0.0)))
+            .build();
+        
+        assert!(world.set_parent(child, parent));
+        assert!(world.set_parent(grandchild, child));
+        assert!(!world.set_parent(parent, grandchild), "cycles must be rejected");
+        
+        world.propagate_transforms();
+        
+        // Rotating the parent a quarter turn around Y maps local -Z onto -X
+        let global = world.get::<GlobalTransform>(grandchild).unwrap();
+        assert!((global.position - Vec3::new(8.0, 1.0, 0.0)).length() < 1e-4);
+        assert_eq!(world.get::<GlobalTransform>(parent).unwrap().position, Vec3::new(10.0, 0.0, 0.0));
+        
+        assert!(world.despawn(child));
+        assert!(!world.is_valid(grandchild));
+        assert!(world.children(parent).is_empty());
+        assert!(world.is_valid(parent));
+    }
+
+    #[test]
+    fn test_interpolated_render_transforms() {
+        use crate::ecs::components::{Transform, PreviousTransform, RenderTransform, RigidBody};
+        use macroquad::prelude::Vec3;
+
+        let mut world = World::new();
+        let body = world.spawn()
+            .with(Transform::new(Vec3::new(2.0, 0.0, 0.0)).with_rotation(Vec3::new(0.0, -3.0, 0.0)))
+            .with(PreviousTransform(Transform::new(Vec3::ZERO).with_rotation(Vec3::new(0.0, 3.0, 0.0))))
+            .build();
+        let attached = world.spawn()
+            .with(Transform::new(Vec3::new(0.0, 1.0, 0.0)))
+            .build();
+        let still = world.spawn()
+            .with(Transform::new(Vec3::new(5.0, 0.0, 0.0)))
+            .build();
+        world.set_parent(attached, body);
+
+        world.propagate_transforms();
+        world.interpolate_transforms(0.25);
+
+        // Children follow their interpolated parent, others are drawn where they are
+        let render = world.get::<RenderTransform>(body).unwrap();
+        assert_eq!(render.position, Vec3::new(0.5, 0.0, 0.0));
+        // Rotation turns the short way round, through ±π rather than back through 0
+        let yaw = 3.0 + (std::f32::consts::TAU - 6.0) * 0.25;
+        assert!((render.rotation - Vec3::new(0.0, yaw, 0.0)).length() < 1e-4);
+        assert!((world.get::<RenderTransform>(attached).unwrap().position - Vec3::new(0.5, 1.0, 0.0)).length() < 1e-4);
+        assert!(!world.has::<RenderTransform>(still));
+
+        world.remove::<PreviousTransform>(body);
+        world.interpolate_transforms(0.25);
+        assert!(!world.has::<RenderTransform>(body));
+        assert!(!world.has::<RenderTransform>(attached));
+        
+        // Simulated bodies are interpolated whichever component comes first
+        let falling = world.spawn()
+            .with(RigidBody::new(1.0))
+            .with(Transform::new(Vec3::new(0.0, 3.0, 0.0)))
+            .build();
+        assert_eq!(world.get::<PreviousTransform>(falling).unwrap().0.position, Vec3::new(0.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn test_change_detection() {
+        use crate::ecs::query::{Added, Changed};
+        
+        let mut world = World::new();
+        let moving = world.spawn().with(Position { x: 0.0, y: 0.0 }).build();
+        let still = world.spawn().with(Position { x: 1.0, y: 1.0 }).build();
+        let doomed = world.spawn().with(Position { x: 2.0, y: 2.0 }).build();
+        assert_eq!(world.query_filtered::<Entity, Added<Position>>().count(), 3);
+        
+        world.clear_trackers();
+        assert_eq!(world.query_filtered::<Entity, Changed<Position>>().count(), 0);
+        
+        // Mutable access marks a component changed, whether or not it is written
+        world.get_mut::<Position>(moving).unwrap().x += 1.0;
+        world.despawn(doomed);
+        world.add(still, Velocity { dx: 0.0, dy: 0.0 });
+        
+        let changed: Vec<Entity> = world.query_filtered::<Entity, Changed<Position>>().into_iter().collect();
+        assert_eq!(changed, vec![moving]);
+        assert!(world.is_added::<Velocity>(still));
+        assert!(!world.is_added::<Position>(still));
+        assert_eq!(world.removed_components::<Position>().iter().collect::<Vec<_>>(), vec![doomed]);
+        
+        world.clear_trackers();
+        assert!(!world.is_changed::<Position>(moving));
+        assert_eq!(world.removed_components::<Position>().iter().count(), 0);
+        
+        // Counting a mutable query fetches nothing, so nothing is marked changed
+        assert_eq!(world.query_mut::<&mut Position>().count(), 2);
+        assert!(!world.is_changed::<Position>(moving));
+        assert!(!world.is_changed::<Position>(still));
+    }
+    
+    #[test]
+    fn test_update_phases_only_mark_modified_components_changed() {
+        struct Countdown(u32);
+        
+        impl Component for Countdown {
+            fn pre_update(&mut self, _delta_time: f32, _world: &World, _entity: Entity) -> bool {
+                false
+            }
+            
+            fn update(&mut self, _delta_time: f32, _world: &mut World, _entity: Entity) -> bool {
+                if self.0 == 0 {
+                    return false;
+                }
+                self.0 -= 1;
+                true
+            }
+        }
+        
+        let mut world = World::new();
+        let running = world.spawn().with(Countdown(1)).build();
+        let finished = world.spawn().with(Countdown(0)).build();
+        world.clear_trackers();
+        
+        world.pre_update_component_type::<Countdown>(0.016);
+        assert!(!world.is_changed::<Countdown>(running));
+        assert!(!world.is_changed::<Countdown>(finished));
+        
+        world.phase_update_component_type::<Countdown>(0.016);
+        assert!(world.is_changed::<Countdown>(running));
+        assert!(!world.is_changed::<Countdown>(finished));
+        assert_eq!(world.get::<Countdown>(running).unwrap().0, 0);
+    }
+    
+    #[test]
+    fn test_name_and_tag_index() {
+        let mut world = World::new();
+        let north = world.spawn().with(Name::new("Wall_North")).with(Tags::new(["wall", "solid"])).build();
+        let south = world.spawn().with(Name::new("Wall_South")).with(Tags::new(["wall"])).build();
+        
+        assert_eq!(world.find_by_name("Wall_North"), Some(north));
+        assert_eq!(world.query_tagged("wall").collect::<Vec<_>>(), vec![north, south]);
+        assert_eq!(world.query_tagged("solid").collect::<Vec<_>>(), vec![north]);
+        
+        // Renaming and retagging replace the old index entries
+        world.set_name(south, "Wall_Back");
+        world.add_tag(south, "solid");
+        assert_eq!(world.find_by_name("Wall_South"), None);
+        assert_eq!(world.find_by_name("Wall_Back"), Some(south));
+        assert_eq!(world.query_tagged("solid").collect::<Vec<_>>(), vec![north, south]);
+        
+        world.remove::<Tags>(north);
+        assert_eq!(world.query_tagged("wall").collect::<Vec<_>>(), vec![south]);
+        
+        world.despawn(south);
+        assert_eq!(world.find_by_name("Wall_Back"), None);
+        assert_eq!(world.query_tagged("wall").count(), 0);
+        
+        let respawned = world.spawn().with(Name::new("Wall_North")).build();
+        world.despawn(north);
+        assert_eq!(world.find_by_name("Wall_North"), Some(respawned));
+    }
+    
+    #[test]
+    fn test_active_state_propagates_to_children() {
+        use crate::ecs::query::IncludeInactive;
+        
+        let mut world = World::new();
+        let parent = world.spawn().with(Position { x: 0.0, y: 0.0 }).build();
+        let child = world.spawn().with(Position { x: 1.0, y: 0.0 }).build();
+        let grandchild = world.spawn().with(Position { x: 2.0, y: 0.0 }).build();
+        world.set_parent(child, parent);
+        world.set_parent(grandchild, child);
+        
+        world.set_active(grandchild, false);
+        world.set_active(parent, false);
+        assert!(!world.is_active(child));
+        assert!(world.is_active_self(child));
+        assert_eq!(world.query::<&Position>().count(), 0);
+        assert_eq!(world.query_filtered::<&Position, IncludeInactive>().count(), 3);
+        assert!(world.get::<Position>(child).is_some(), "direct access ignores the active state");
+        
+        // Reactivating the parent restores only children that were active themselves
+        world.set_active(parent, true);
+        assert!(world.is_active(child));
+        assert!(!world.is_active(grandchild));
+        
+        // Moving an entity under an inactive parent deactivates it too
+        let other = world.spawn().with(Position { x: 3.0, y: 0.0 }).build();
+        world.set_active(other, false);
+        world.set_parent(child, other);
+        assert!(!world.is_active(child));
+        world.remove_parent(child);
+        assert!(world.is_active(child));
+    }
+    
+    #[derive(Debug)]
+    struct Hooked(bool);
+    
+    impl Component for Hooked {
+        fn is_enabled(&self) -> bool {
+            self.0
+        }
+        
+        fn enable(&mut self) {
+            self.0 = true;
+        }
+        
+        fn disable(&mut self) {
+            self.0 = false;
+        }
+    }
+    
+    #[derive(Debug, Default)]
+    struct HookLog(Vec<&'static str>);
+    
+    impl Resource for HookLog {}
+    
+    fn log_hook(world: &mut World, event: &'static str) {
+        world.resource_mut::<HookLog>().unwrap().0.push(event);
+    }
+    
+    inventory::submit! {
+        ComponentHooks {
+            on_add: Some(|world, _| log_hook(world, "add")),
+            on_remove: Some(|world, entity| {
+                assert!(world.has::<Hooked>(entity), "on_remove runs before the component is gone");
+                log_hook(world, "remove");
+            }),
+            on_enable: Some(|world, _| log_hook(world, "enable")),
+            on_disable: Some(|world, _| log_hook(world, "disable")),
+            ..ComponentHooks::new::<Hooked>("Hooked")
+        }
+    }
+    
+    #[test]
+    fn test_component_lifecycle_hooks() {
+        let mut world = World::new();
+        world.insert_resource(HookLog::default());
+        
+        let entity = world.spawn().with(Hooked(true)).with(Position { x: 0.0, y: 0.0 }).build();
+        world.add(entity, Hooked(true));
+        world.set_enabled(entity, false);
+        
+        // A disabled component stays off whatever the entity's active state
+        world.set_active(entity, false);
+        world.set_active(entity, true);
+        world.set_enabled(entity, true);
+        
+        // An inactive entity's components stay off whether enabled or not
+        world.set_active(entity, false);
+        world.set_enabled(entity, false);
+        world.set_enabled(entity, true);
+        world.set_active(entity, true);
+        
+        // Parenting under an inactive entity switches the component off
+        let parent = world.spawn().build();
+        world.set_active(parent, false);
+        world.set_parent(entity, parent);
+        world.remove_parent(entity);
+        
+        world.remove::<Hooked>(entity);
+        world.remove::<Hooked>(entity);
+        
+        let other = world.spawn().with(Hooked(true)).build();
+        world.despawn(other);
+        
+        assert_eq!(world.resource::<HookLog>().unwrap().0, vec![
+            "add", "remove", "add", "disable", "enable", "disable", "enable",
+            "disable", "enable", "remove", "add", "remove",
+        ]);
+    }
+    
+    #[test]
+    fn test_spawn_and_despawn_events() {
+        use crate::ecs::event::EventReader;
+        
+        let mut world = World::new();
+        world.add_event::<EntitySpawned>();
+        world.add_event::<EntityDespawned>();
+        let mut spawned = EventReader::<EntitySpawned>::new();
+        let mut despawned = EventReader::<EntityDespawned>::new();
+        
+        let entity = world.spawn().build();
+        world.update_events();
+        world.despawn(entity);
+        
+        let spawned: Vec<_> = spawned.read(world.events().unwrap()).copied().collect();
+        let despawned: Vec<_> = despawned.read(world.events().unwrap()).copied().collect();
+        assert_eq!(spawned, vec![EntitySpawned { entity }]);
+        assert_eq!(despawned, vec![EntityDespawned { entity }]);
+        
+        // Two frames later the events have been dropped
+        world.update_events();
+        world.update_events();
+        assert!(world.events::<EntitySpawned>().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+//! Component lifecycle hooks for gameplay
+//!
+//! Hooks keep derived state in step with the components it depends on,
+//! wherever in the code those components are added, removed or switched.
+
+use crate::ecs::*;
+use crate::ecs::component::ComponentHooks;
+use super::resources::TextureRequests;
+
+/// Queue a texture for the renderer to load
+fn request_texture(world: &mut World, texture_name: Option<String>) {
+    if let (Some(texture_name), Some(requests)) = (texture_name, world.resource_mut::<TextureRequests>()) {
+        requests.pending.insert(texture_name);
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(|world, entity| {
+            let texture_name = world.get::<Renderer>(entity).and_then(|renderer| renderer.material.texture_name.clone());
+            request_texture(world, texture_name);
+        }),
+        ..ComponentHooks::new::<Renderer>("Renderer")
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(|world, entity| {
+            let texture_name = world.get::<StaticRenderer>(entity).and_then(StaticRenderer::get_texture_name);
+            request_texture(world, texture_name);
+        }),
+        ..ComponentHooks::new::<StaticRenderer>("StaticRenderer")
+    }
+}
+
+/// Make pathfinders with a target plan again when a blocking collider
+/// appears, disappears or is switched on or off
+fn invalidate_paths(world: &mut World, entity: Entity) {
+    // Disabling happens before the hook runs, so check the kind of collider
+    // rather than `blocks_movement`
+    if world.get::<Collider>(entity).is_none_or(|collider| collider.is_trigger) {
+        return;
+    }
+    
+    for pathfinder in world.query_mut::<&mut Pathfinder>() {
+        if pathfinder.target.is_some() {
+            pathfinder.needs_recalculation = true;
+        }
+    }
+}
+
+inventory::submit! {
+    ComponentHooks {
+        on_add: Some(invalidate_paths),
+        on_remove: Some(invalidate_paths),
+        on_enable: Some(invalidate_paths),
+        on_disable: Some(invalidate_paths),
+        ..ComponentHooks::new::<Collider>("Collider")
+    }
+}
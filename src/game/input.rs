@@ -10,6 +10,7 @@ pub struct PlayerInput {
     pub forward_move: f32,    // -1.0 to 1.0 (S to W)
     pub strafe_move: f32,     // -1.0 to 1.0 (A to D)
     pub jump_pressed: bool,   // Spacebar pressed this frame
+    pub vertical_move: f32,   // -1.0 to 1.0 (Ctrl to Space), only thrusts in zero-g
     
     // Look inputs
     pub mouse_delta: Vec2,    // Mouse movement delta
@@ -19,6 +20,9 @@ pub struct PlayerInput {
     pub toggle_pillars_pressed: bool,  // T key - Toggle middle pillars
     pub debug_info_pressed: bool,      // F1 key - Toggle debug info
     pub performance_test_pressed: bool,  // P key - Run performance tests
+    pub pause_pressed: bool,           // F5 key - Pause/resume the simulation
+    pub step_pressed: bool,            // F6 key - Advance one tick while paused
+    pub slow_motion_pressed: bool,     // F7 key - Toggle slow motion
     
     // Settings
     pub mouse_sensitivity: f32,
@@ -26,6 +30,8 @@ pub struct PlayerInput {
     pub turn_speed: f32,
 }
 
+impl crate::ecs::Resource for PlayerInput {}
+
 /// Input handler that captures and processes all player inputs
 pub struct InputHandler {
     base_mouse_sensitivity: f32,  // User-configurable base sensitivity
@@ -95,10 +101,17 @@ impl InputHandler {
         // Capture jump input
         input.jump_pressed = is_key_pressed(KeyCode::Space);
         
+        // Capture up and down thrust for zero-g
+        if is_key_down(KeyCode::Space) { input.vertical_move += 1.0; }
+        if is_key_down(KeyCode::LeftControl) { input.vertical_move -= 1.0; }
+        
         // Capture debug/test inputs
         input.toggle_pillars_pressed = is_key_pressed(KeyCode::T);
         input.debug_info_pressed = is_key_pressed(KeyCode::F1);
         input.performance_test_pressed = is_key_pressed(KeyCode::P);
+        input.pause_pressed = is_key_pressed(KeyCode::F5);
+        input.step_pressed = is_key_pressed(KeyCode::F6);
+        input.slow_motion_pressed = is_key_pressed(KeyCode::F7);
         
         // Capture mouse look
         input.mouse_delta = mouse_delta_position();
@@ -136,7 +149,7 @@ impl InputHandler {
 impl PlayerInput {
     /// Check if any movement input is active
     pub fn has_movement(&self) -> bool {
-        self.forward_move.abs() > 0.001 || self.strafe_move.abs() > 0.001
+        self.forward_move.abs() > 0.001 || self.strafe_move.abs() > 0.001 || self.vertical_move.abs() > 0.001
     }
     
     /// Check if any look input is active
@@ -18,6 +18,8 @@ pub struct LevelData {
     pub settings: Option<WorldSettings>,
 }
 
+impl crate::ecs::Resource for LevelData {}
+
 impl PartialEq for LevelData {
     fn eq(&self, other: &Self) -> bool {
         self.player == other.player &&
@@ -102,6 +104,8 @@ pub struct LightConfig {
     pub radius: f32,
     #[serde(default)]
     pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,         // Labels for finding groups of lights, e.g. "accent"
 }
 
 /// Object configuration  
@@ -113,7 +117,7 @@ pub struct ObjectConfig {
     #[serde(default)]
     pub texture: Option<String>,   // Optional texture file path
     #[serde(default = "default_collision_type")]
-    pub collision_type: String,    // "solid", "trigger", "none"
+    pub collision_type: String,    // "solid", "mesh", "convex", "trigger", "dynamic", "none"
     pub position: [f32; 3],        // [x, y, z]
     #[serde(default = "default_scale")]
     pub scale: [f32; 3],           // [x, y, z] - defaults to [1,1,1]
@@ -123,6 +127,206 @@ pub struct ObjectConfig {
     pub color: Option<[f32; 4]>,   // Optional color override
     #[serde(default)]
     pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,         // Labels for finding groups of objects, e.g. "pillar"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ObjectConfig>, // Attached objects; their transforms are relative to this object
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_enter: Vec<TriggerActionConfig>, // Run when something enters a "trigger" object
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_exit: Vec<TriggerActionConfig>,  // Run when something leaves a "trigger" object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collider: Option<ColliderConfig>,   // Collider shape and material; defaults to a box the size of `scale`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathConfig>,           // Scripted motion that makes the object a moving door or platform
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gravity: Option<GravityConfig>,     // Gravity inside the object's collider, usually a "trigger"
+}
+
+impl ObjectConfig {
+    /// The actions to attach to this object's trigger collider, if it has any
+    pub fn trigger_actions(&self) -> Option<crate::ecs::TriggerActions> {
+        if self.on_enter.is_empty() && self.on_exit.is_empty() {
+            return None;
+        }
+        Some(crate::ecs::TriggerActions {
+            on_enter: self.on_enter.iter().map(TriggerActionConfig::to_action).collect(),
+            on_exit: self.on_exit.iter().map(TriggerActionConfig::to_action).collect(),
+            enabled: true,
+        })
+    }
+
+    /// The kinematic body moving this object along its path, if it has one
+    pub fn kinematic_body(&self) -> Option<crate::ecs::KinematicBody> {
+        use crate::ecs::{KinematicBody, PathMode};
+        let path = self.path.as_ref()?;
+        let mode = match path.mode.as_str() {
+            "once" => PathMode::Once,
+            "ping_pong" => PathMode::PingPong,
+            "loop" => PathMode::Loop,
+            other => {
+                println!("⚠️ Unknown path mode: {}", other);
+                PathMode::PingPong
+            }
+        };
+        let waypoints = std::iter::once(self.position).chain(path.waypoints.iter().copied()).map(Vec3::from).collect();
+        let body = KinematicBody::new(waypoints, path.speed)
+            .with_mode(mode)
+            .with_pause(path.pause);
+        Some(if path.auto_start { body } else { body.stopped() })
+    }
+
+    /// The gravity volume changing gravity inside this object, if it has one
+    pub fn gravity_volume(&self) -> Option<crate::ecs::GravityVolume> {
+        use crate::ecs::GravityVolume;
+        let gravity = self.gravity.as_ref()?;
+        let volume = match gravity.vector {
+            Some(vector) => GravityVolume::directional(Vec3::from(vector)),
+            None => GravityVolume::scaled(gravity.scale),
+        };
+        Some(volume.with_priority(gravity.priority))
+    }
+
+    /// The shape of this object's collider
+    pub fn collider_shape(&self) -> crate::ecs::ColliderShape {
+        use crate::ecs::ColliderShape;
+        let scale = Vec3::from(self.scale);
+        let Some(collider) = &self.collider else {
+            return ColliderShape::Box { size: scale };
+        };
+        let radius = collider.radius.unwrap_or(scale.x.max(scale.z) * 0.5);
+        match collider.shape.as_str() {
+            "sphere" => ColliderShape::Sphere { radius },
+            "capsule" => ColliderShape::Capsule {
+                height: collider.height.unwrap_or((scale.y - 2.0 * radius).max(0.0)),
+                radius,
+            },
+            "box" => ColliderShape::Box { size: collider.size.map(Vec3::from).unwrap_or(scale) },
+            other => {
+                println!("⚠️ Unknown collider shape: {}", other);
+                ColliderShape::Box { size: scale }
+            }
+        }
+    }
+
+    /// A static collider following this object's glTF geometry, either its
+    /// exact triangles or their convex hull, at the object's scale
+    ///
+    /// Falls back to `collider_shape` when the mesh can't be used.
+    pub fn mesh_collider_shape(&self, convex: bool) -> crate::ecs::ColliderShape {
+        use crate::ecs::{ColliderShape, ConvexHull, TriMesh};
+        use crate::game::rendering::GltfLoader;
+
+        if !GltfLoader::is_gltf_file(&self.mesh) {
+            println!("⚠️ Mesh collider needs a GLTF mesh, not {}", self.mesh);
+            return self.collider_shape();
+        }
+        let (vertices, triangles) = match GltfLoader::load_collision_geometry(&self.mesh) {
+            Ok(geometry) => geometry,
+            Err(e) => {
+                println!("⚠️ Failed to load collision mesh {}: {}", self.mesh, e);
+                return self.collider_shape();
+            }
+        };
+
+        let scale = Vec3::from(self.scale);
+        let vertices: Vec<Vec3> = vertices.into_iter().map(|vertex| vertex * scale).collect();
+        if !convex {
+            return ColliderShape::TriMesh { mesh: Arc::new(TriMesh::new(vertices, triangles)) };
+        }
+        match ConvexHull::new(&vertices) {
+            Some(hull) => ColliderShape::ConvexHull { hull: Arc::new(hull) },
+            None => {
+                println!("⚠️ Collision mesh {} is flat, it has no convex hull", self.mesh);
+                self.collider_shape()
+            }
+        }
+    }
+
+    /// The physics material of this object's collider
+    pub fn collider_material(&self) -> crate::ecs::ColliderMaterial {
+        use crate::ecs::ColliderMaterial;
+        match self.collider.as_ref().map(|collider| collider.material.as_str()) {
+            None | Some("standard") => ColliderMaterial::standard(),
+            Some("bouncy") => ColliderMaterial::bouncy(),
+            Some("slippery") => ColliderMaterial::slippery(),
+            Some(other) => {
+                println!("⚠️ Unknown collider material: {}", other);
+                ColliderMaterial::standard()
+            }
+        }
+    }
+}
+
+/// Collider configuration, e.g. `{ "shape": "sphere", "radius": 0.07, "material": "bouncy" }`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ColliderConfig {
+    #[serde(default = "default_collider_shape")]
+    pub shape: String,             // "box", "sphere", "capsule"
+    #[serde(default)]
+    pub size: Option<[f32; 3]>,    // Box size, defaults to the object's scale
+    #[serde(default)]
+    pub radius: Option<f32>,       // Sphere and capsule radius, defaults to half the object's width
+    #[serde(default)]
+    pub height: Option<f32>,       // Capsule height between the cap centres
+    #[serde(default = "default_collider_material")]
+    pub material: String,          // "standard", "bouncy", "slippery"
+}
+
+/// Path configuration for a moving object, e.g.
+/// `{ "waypoints": [[5.0, 2.0, 5.0]], "speed": 1.5, "mode": "ping_pong" }`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathConfig {
+    pub waypoints: Vec<[f32; 3]>,  // Positions to visit after the object's own, which comes first
+    #[serde(default = "default_path_speed")]
+    pub speed: f32,                // Units per second
+    #[serde(default = "default_path_mode")]
+    pub mode: String,              // "once", "ping_pong", "loop"
+    #[serde(default)]
+    pub pause: f32,                // Seconds to wait at each waypoint
+    #[serde(default = "default_auto_start")]
+    pub auto_start: bool,          // Set to false to wait for a "move_to" trigger action
+}
+
+/// Gravity volume configuration, e.g. `{}` for zero-g, `{ "scale": 0.3 }` for
+/// low gravity or `{ "vector": [0.0, 0.0, -9.0] }` to pull along -Z
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GravityConfig {
+    #[serde(default)]
+    pub vector: Option<[f32; 3]>,  // Gravity inside the volume, overriding `scale`
+    #[serde(default)]
+    pub scale: f32,                // Multiplier for the level's gravity, 0 for zero-g
+    #[serde(default)]
+    pub priority: u32,             // Overlapping volumes with a higher priority win
+}
+
+/// Trigger action configuration, e.g. `{ "action": "disable", "target": "Door" }`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TriggerActionConfig {
+    Teleport { position: [f32; 3] },    // Move whoever entered or left
+    Enable { target: String },          // Entity name or tag
+    Disable { target: String },         // Entity name or tag
+    ToggleLights { target: String },    // Light name or tag
+    MoveTo { target: String, waypoint: usize }, // Moving object name or tag; waypoint 0 is where it started
+    LoadLevel { path: String },         // Level JSON file, e.g. "maps/engine_room.json"
+    Message { text: String },
+}
+
+impl TriggerActionConfig {
+    /// Convert to the action run by the trigger's `TriggerActions` component
+    pub fn to_action(&self) -> crate::ecs::TriggerAction {
+        use crate::ecs::TriggerAction;
+        match self {
+            Self::Teleport { position } => TriggerAction::Teleport { position: Vec3::from(*position) },
+            Self::Enable { target } => TriggerAction::Enable { target: target.clone() },
+            Self::Disable { target } => TriggerAction::Disable { target: target.clone() },
+            Self::ToggleLights { target } => TriggerAction::ToggleLights { target: target.clone() },
+            Self::MoveTo { target, waypoint } => TriggerAction::MoveTo { target: target.clone(), waypoint: *waypoint },
+            Self::LoadLevel { path } => TriggerAction::LoadLevel { path: path.clone() },
+            Self::Message { text } => TriggerAction::Message { text: text.clone() },
+        }
+    }
 }
 
 /// World settings
@@ -134,6 +338,8 @@ pub struct WorldSettings {
     pub fog_color: Option<[f32; 4]>,      // Fog color
     #[serde(default)]
     pub fog_density: Option<f32>,         // Fog density
+    #[serde(default)]
+    pub gravity: Option<[f32; 3]>,        // Gravity outside any gravity volume, [0, -12, 0] if unset
 }
 
 fn default_scale() -> [f32; 3] {
@@ -148,6 +354,26 @@ fn default_collision_type() -> String {
     "solid".to_string()
 }
 
+fn default_collider_shape() -> String {
+    "box".to_string()
+}
+
+fn default_collider_material() -> String {
+    "standard".to_string()
+}
+
+fn default_path_speed() -> f32 {
+    2.0
+}
+
+fn default_path_mode() -> String {
+    "ping_pong".to_string()
+}
+
+fn default_auto_start() -> bool {
+    true
+}
+
 impl Default for LightConfig {
     fn default() -> Self {
         Self {
@@ -158,6 +384,7 @@ impl Default for LightConfig {
             intensity: 1.0,
             radius: 5.0,
             enabled: true,
+            tags: Vec::new(),
         }
     }
 }
@@ -174,6 +401,13 @@ impl Default for ObjectConfig {
             rotation: default_rotation(),
             color: None,
             enabled: true,
+            tags: Vec::new(),
+            children: Vec::new(),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+            collider: None,
+            path: None,
+            gravity: None,
         }
     }
 }
@@ -216,6 +450,7 @@ impl LevelData {
                     intensity: 2.0,
                     radius: 8.0,
                     enabled: true,
+                    tags: Vec::new(),
                 }
             ],
             objects: vec![
@@ -225,6 +460,7 @@ impl LevelData {
                 ambient_light: Some([0.1, 0.1, 0.2, 1.0]),  // Subtle blue ambient
                 fog_color: None,
                 fog_density: None,
+                gravity: None,
             }),
         }
     }
@@ -454,6 +690,7 @@ impl LevelDataHotReload {
                     intensity: 2.0,
                     radius: 8.0,
                     enabled: true,
+                    tags: Vec::new(),
                 },
                 LightConfig {
                     name: Some("AccentLight_Corner".to_string()),
@@ -463,6 +700,7 @@ impl LevelDataHotReload {
                     intensity: 1.5,
                     radius: 6.0,
                     enabled: true,
+                    tags: Vec::new(),
                 }
             ],
             objects: vec![
@@ -477,6 +715,13 @@ impl LevelDataHotReload {
                     rotation: [0.0, 0.0, 0.0],
                     color: None,
                     enabled: true,
+                    tags: vec!["wall".to_string()],
+                    children: Vec::new(),
+                    on_enter: Vec::new(),
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: None,
+                    gravity: None,
                 },
                 // Example decorative sphere
                 ObjectConfig {
@@ -489,6 +734,13 @@ impl LevelDataHotReload {
                     rotation: [0.0, 0.0, 0.0],
                     color: Some([0.8, 0.2, 0.2, 1.0]), // Red
                     enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: Vec::new(),
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: None,
+                    gravity: None,
                 },
                 // Example floor section
                 ObjectConfig {
@@ -501,12 +753,101 @@ impl LevelDataHotReload {
                     rotation: [0.0, 0.0, 0.0],
                     color: None,
                     enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: Vec::new(),
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: None,
+                    gravity: None,
+                },
+                // Example trigger that greets the player
+                ObjectConfig {
+                    name: Some("Trigger_Welcome".to_string()),
+                    mesh: "cube".to_string(),
+                    texture: None,
+                    collision_type: "trigger".to_string(),
+                    position: [5.0, 1.0, 5.0],
+                    scale: [2.0, 2.0, 2.0],
+                    rotation: [0.0, 0.0, 0.0],
+                    color: Some([0.2, 0.8, 0.2, 0.3]), // Translucent green
+                    enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: vec![TriggerActionConfig::Message { text: "Welcome aboard".to_string() }],
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: None,
+                    gravity: None,
+                },
+                // Example sliding door, open while something stands in front of it
+                ObjectConfig {
+                    name: Some("Door_Sliding".to_string()),
+                    mesh: "cube".to_string(),
+                    texture: None,
+                    collision_type: "solid".to_string(),
+                    position: [3.0, 1.0, 9.0],
+                    scale: [1.5, 2.0, 0.2],
+                    rotation: [0.0, 0.0, 0.0],
+                    color: Some([0.6, 0.6, 0.7, 1.0]),
+                    enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: Vec::new(),
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: Some(PathConfig {
+                        waypoints: vec![[4.5, 1.0, 9.0]],
+                        speed: 2.0,
+                        mode: "once".to_string(),
+                        pause: 0.0,
+                        auto_start: false,
+                    }),
+                    gravity: None,
+                },
+                ObjectConfig {
+                    name: Some("Trigger_Door".to_string()),
+                    mesh: "cube".to_string(),
+                    texture: None,
+                    collision_type: "trigger".to_string(),
+                    position: [3.0, 1.0, 8.0],
+                    scale: [2.0, 2.0, 1.5],
+                    rotation: [0.0, 0.0, 0.0],
+                    color: Some([0.2, 0.2, 0.8, 0.3]), // Translucent blue
+                    enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: vec![TriggerActionConfig::MoveTo { target: "Door_Sliding".to_string(), waypoint: 1 }],
+                    on_exit: vec![TriggerActionConfig::MoveTo { target: "Door_Sliding".to_string(), waypoint: 0 }],
+                    collider: None,
+                    path: None,
+                    gravity: None,
+                },
+                // Example zero-g zone
+                ObjectConfig {
+                    name: Some("Volume_ZeroG".to_string()),
+                    mesh: "cube".to_string(),
+                    texture: None,
+                    collision_type: "trigger".to_string(),
+                    position: [8.0, 2.0, 3.0],
+                    scale: [3.0, 4.0, 3.0],
+                    rotation: [0.0, 0.0, 0.0],
+                    color: Some([0.6, 0.2, 0.8, 0.2]), // Translucent purple
+                    enabled: true,
+                    tags: Vec::new(),
+                    children: Vec::new(),
+                    on_enter: Vec::new(),
+                    on_exit: Vec::new(),
+                    collider: None,
+                    path: None,
+                    gravity: Some(GravityConfig { vector: None, scale: 0.0, priority: 0 }),
                 }
             ],
             settings: Some(WorldSettings {
                 ambient_light: Some([0.1, 0.1, 0.2, 1.0]),  // Subtle blue ambient
                 fog_color: Some([0.2, 0.2, 0.3, 1.0]),
                 fog_density: Some(0.02),
+                gravity: None,
             }),
         };
         
@@ -13,6 +13,8 @@ pub struct Map {
     pub world_max_z: f32,
 }
 
+impl crate::ecs::Resource for Map {}
+
 /// Wall texture types for sci-fi space station
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tile {
@@ -12,6 +12,9 @@ pub mod level_generator;
 pub mod level_data;
 pub mod state;
 pub mod ecs_state;
+pub mod resources;
+pub mod systems;
+pub mod hooks;
 pub mod input;
 pub mod player;
 pub mod rendering;
@@ -1,13 +1,14 @@
 //! Deferred rendering pipeline optimized for ECS architecture
 //! 
 //! This renderer separates geometry and lighting into two passes:
-//! 1. Geometry Pass: Render all StaticRenderer + Transform entities to G-buffer
-//! 2. Lighting Pass: Process all LightSource + Transform entities in screen space
+//! 1. Geometry Pass: Render all StaticRenderer + GlobalTransform entities to G-buffer
+//! 2. Lighting Pass: Process all LightSource + GlobalTransform entities in screen space
 //! 3. Final Composition: Combine lighting with materials/textures
 
 use macroquad::prelude::*;
 use crate::game::Player;
-use crate::ecs::{World, Transform, StaticRenderer, LightSource, LightReceiver, StaticMesh, Renderer, RenderMode};
+use crate::game::resources::TextureRequests;
+use crate::ecs::{Entity, World, Transform, GlobalTransform, RenderTransform, StaticRenderer, LightSource, LightReceiver, StaticMesh, Renderer, RenderMode};
 use super::gltf_loader::GltfLoader;
 use std::collections::HashMap;
 use futures;
@@ -168,20 +169,16 @@ impl DeferredRenderer {
         let mut required_textures: std::collections::HashSet<String> = std::collections::HashSet::new();
         
         // Scan all Renderer components for texture names
-        for (entity, _) in world.query_1::<Renderer>() {
-            if let Some(renderer) = world.get::<Renderer>(entity) {
-                if let Some(texture_name) = &renderer.material.texture_name {
-                    required_textures.insert(texture_name.clone());
-                }
+        for renderer in world.query::<&Renderer>() {
+            if let Some(texture_name) = &renderer.material.texture_name {
+                required_textures.insert(texture_name.clone());
             }
         }
         
         // Scan all StaticRenderer components for texture names
-        for (entity, _) in world.query_1::<StaticRenderer>() {
-            if let Some(static_renderer) = world.get::<StaticRenderer>(entity) {
-                if let Some(texture_name) = static_renderer.get_texture_name() {
-                    required_textures.insert(texture_name);
-                }
+        for static_renderer in world.query::<&StaticRenderer>() {
+            if let Some(texture_name) = static_renderer.get_texture_name() {
+                required_textures.insert(texture_name);
             }
         }
         
@@ -193,12 +190,10 @@ impl DeferredRenderer {
         let mut gltf_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
         
         // Scan all Renderer components for GLTF mesh paths
-        for (entity, _) in world.query_1::<Renderer>() {
-            if let Some(renderer) = world.get::<Renderer>(entity) {
-                if let Some(mesh_path) = &renderer.custom_mesh_path {
-                    if mesh_path.ends_with(".gltf") || mesh_path.ends_with(".glb") {
-                        gltf_paths.insert(mesh_path.clone());
-                    }
+        for renderer in world.query::<&Renderer>() {
+            if let Some(mesh_path) = &renderer.custom_mesh_path {
+                if mesh_path.ends_with(".gltf") || mesh_path.ends_with(".glb") {
+                    gltf_paths.insert(mesh_path.clone());
                 }
             }
         }
@@ -206,31 +201,19 @@ impl DeferredRenderer {
         gltf_paths.len()
     }
 
-    /// Preload textures from world entities (scan JSON configuration dynamically)
-    pub async fn preload_textures_from_world(&mut self, world: &World) {
-        println!("🖼️ Deferred Renderer: Scanning world for required textures...");
-        
-        let mut required_textures: std::collections::HashSet<String> = std::collections::HashSet::new();
-        
-        // Scan all Renderer components for texture names
-        for (entity, _) in world.query_1::<Renderer>() {
-            if let Some(renderer) = world.get::<Renderer>(entity) {
-                if let Some(texture_name) = &renderer.material.texture_name {
-                    required_textures.insert(texture_name.clone());
-                }
-            }
-        }
-        
-        // Scan all StaticRenderer components for texture names
-        for (entity, _) in world.query_1::<StaticRenderer>() {
-            if let Some(static_renderer) = world.get::<StaticRenderer>(entity) {
-                if let Some(texture_name) = static_renderer.get_texture_name() {
-                    required_textures.insert(texture_name);
-                }
-            }
+    /// Load the textures queued by render components added since the last call
+    pub async fn load_requested_textures(&mut self, world: &mut World) {
+        let required_textures: Vec<String> = match world.resource_mut::<TextureRequests>() {
+            Some(requests) => requests.pending.drain()
+                .filter(|texture_name| !self.textures.contains_key(texture_name))
+                .collect(),
+            None => return,
+        };
+        if required_textures.is_empty() {
+            return;
         }
         
-        println!("🔍 Found {} unique textures referenced in world configuration", required_textures.len());
+        println!("🔍 Found {} new textures referenced by render components", required_textures.len());
         for texture_name in &required_textures {
             println!("   - {}", texture_name);
         }
@@ -274,12 +257,10 @@ impl DeferredRenderer {
         let mut mesh_paths: Vec<String> = Vec::new();
         
         // Collect all custom mesh paths from Renderer components
-        for (entity, _) in world.query_1::<Renderer>() {
-            if let Some(renderer) = world.get::<Renderer>(entity) {
-                if let Some(custom_path) = &renderer.custom_mesh_path {
-                    if !mesh_paths.contains(custom_path) {
-                        mesh_paths.push(custom_path.clone());
-                    }
+        for renderer in world.query::<&Renderer>() {
+            if let Some(custom_path) = &renderer.custom_mesh_path {
+                if !mesh_paths.contains(custom_path) {
+                    mesh_paths.push(custom_path.clone());
                 }
             }
         }
@@ -338,8 +319,9 @@ impl DeferredRenderer {
         let mut geometry_count = 0;
 
         // Render all static meshes to G-buffer (walls, floor, ceiling, props)
-        for (entity, transform, static_mesh) in world.query_2::<Transform, StaticMesh>() {
-            if world.is_valid(entity) && entity.enabled && static_mesh.is_enabled() && transform.is_enabled() {
+        for (global, render, static_mesh) in world.query::<(&GlobalTransform, Option<&RenderTransform>, &StaticMesh)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if static_mesh.is_enabled() && transform.is_enabled() {
                 if let Some(mesh) = &static_mesh.mesh {
                     draw_mesh(mesh);
                     geometry_count += 1;
@@ -348,16 +330,18 @@ impl DeferredRenderer {
         }
 
         // Render individual entities to G-buffer (legacy support)
-        for (entity, transform, static_renderer) in world.query_2::<Transform, StaticRenderer>() {
-            if world.is_valid(entity) && entity.enabled && static_renderer.is_enabled() && transform.is_enabled() {
+        for (global, render, static_renderer) in world.query::<(&GlobalTransform, Option<&RenderTransform>, &StaticRenderer)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if static_renderer.is_enabled() && transform.is_enabled() {
                 self.render_entity_to_gbuffer(transform, static_renderer);
                 geometry_count += 1;
             }
         }
 
         // Render new Renderer components (light spheres, etc.)
-        for (entity, transform, renderer) in world.query_2::<Transform, Renderer>() {
-            if world.is_valid(entity) && entity.enabled && renderer.enabled && transform.is_enabled() {
+        for (entity, global, render, renderer) in world.query::<(Entity, &GlobalTransform, Option<&RenderTransform>, &Renderer)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if renderer.enabled && transform.is_enabled() {
                 self.render_renderer_to_gbuffer(entity, transform, renderer, world).await;
                 geometry_count += 1;
             }
@@ -376,8 +360,9 @@ impl DeferredRenderer {
         // Collect all ECS light sources with their world positions
         let mut light_sources = Vec::new();
         
-        for (entity, transform, light_source) in world.query_2::<Transform, LightSource>() {
-            if world.is_valid(entity) && entity.enabled && light_source.is_enabled() && transform.is_enabled() {
+        for (global, render, light_source) in world.query::<(&GlobalTransform, Option<&RenderTransform>, &LightSource)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if light_source.is_enabled() && transform.is_enabled() {
                 light_sources.push((transform.position, light_source.clone()));
             }
         }
@@ -409,8 +394,9 @@ impl DeferredRenderer {
         let mut lighting_updates = Vec::new();
         
         // Process all entities with LightReceiver components
-        for (entity, transform, light_receiver) in world.query_2::<Transform, LightReceiver>() {
-            if !world.is_valid(entity) || !entity.enabled || !light_receiver.is_enabled() || !transform.is_enabled() {
+        for (entity, global, render, light_receiver) in world.query::<(Entity, &GlobalTransform, Option<&RenderTransform>, &LightReceiver)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if !light_receiver.is_enabled() || !transform.is_enabled() {
                 continue;
             }
             
@@ -529,17 +515,17 @@ impl DeferredRenderer {
                 }
             },
             RenderMode::Cube { size } => {
-                draw_cube(transform.position, *size, texture, renderer.material.color);
+                draw_oriented(transform, |position| draw_cube(position, *size, texture, renderer.material.color));
             },
             RenderMode::Cylinder { radius, height } => {
                 // Render as cube until we have cylinder primitive
                 let size = Vec3::new(*radius * 2.0, *height, *radius * 2.0);
-                draw_cube(transform.position, size, texture, renderer.material.color);
+                draw_oriented(transform, |position| draw_cube(position, size, texture, renderer.material.color));
             },
             RenderMode::Plane { width, height } => {
                 // Render as thin cube
                 let size = Vec3::new(*width, 0.01, *height);
-                draw_cube(transform.position, size, texture, renderer.material.color);
+                draw_oriented(transform, |position| draw_cube(position, size, texture, renderer.material.color));
             },
             RenderMode::UseMeshData => {
                 // Check for StaticMesh component on same entity
@@ -558,18 +544,8 @@ impl DeferredRenderer {
                         // Since macroquad's draw_mesh doesn't support transforms, 
                         // we need to pre-transform the vertices but do it correctly
                         
-                        // Convert degrees to radians for rotation
-                        let rotation_rad = Vec3::new(
-                            transform.rotation.x.to_radians(),
-                            transform.rotation.y.to_radians(), 
-                            transform.rotation.z.to_radians()
-                        );
-                        
-                        // Build transformation matrix in correct order: T * R * S
-                        let scale_matrix = Mat4::from_scale(transform.scale);
-                        let rotation_matrix = Mat4::from_euler(EulerRot::XYZ, rotation_rad.x, rotation_rad.y, rotation_rad.z);
-                        let translation_matrix = Mat4::from_translation(transform.position);
-                        let transform_matrix = translation_matrix * rotation_matrix * scale_matrix;
+                        // T * R * S, with the same radians colliders are oriented by
+                        let transform_matrix = transform.matrix();
                         
                         // Transform vertices
                         let mut transformed_vertices = mesh.vertices.clone();
@@ -622,8 +598,9 @@ impl DeferredRenderer {
         let mut final_color = ambient;
         
         // Collect all ECS light sources
-        for (entity, transform, light_source) in world.query_2::<Transform, LightSource>() {
-            if !world.is_valid(entity) || !entity.enabled || !light_source.is_enabled() || !transform.is_enabled() {
+        for (global, render, light_source) in world.query::<(&GlobalTransform, Option<&RenderTransform>, &LightSource)>() {
+            let transform = RenderTransform::resolve(render, global);
+            if !light_source.is_enabled() || !transform.is_enabled() {
                 continue;
             }
             
@@ -698,4 +675,21 @@ impl DeferredRenderer {
             draw_sphere(transform.position, glow_radius, None, glow_color);
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Run a draw call with the transform's rotation applied
+///
+/// macroquad's primitives are axis-aligned, so rotated objects are drawn at
+/// the origin under a model matrix instead. `draw` receives the position to
+/// draw at.
+fn draw_oriented(transform: &Transform, draw: impl FnOnce(Vec3)) {
+    if transform.rotation == Vec3::ZERO {
+        draw(transform.position);
+        return;
+    }
+    
+    let model = Mat4::from_rotation_translation(transform.orientation(), transform.position);
+    unsafe { get_internal_gl() }.quad_gl.push_model_matrix(model);
+    draw(Vec3::ZERO);
+    unsafe { get_internal_gl() }.quad_gl.pop_model_matrix();
+}
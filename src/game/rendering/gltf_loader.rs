@@ -4,6 +4,12 @@ use macroquad::prelude::*;
 use std::path::Path;
 use futures;
 
+/// Global scale applied to GLTF positions to make models appropriately sized
+const GLTF_SCALE_FACTOR: f32 = 0.01; // Makes models 100x smaller
+
+/// Vertices and triangle indices read from a GLTF file for colliders
+pub type CollisionGeometry = (Vec<Vec3>, Vec<[u32; 3]>);
+
 /// Represents a loaded GLTF mesh ready for rendering
 pub struct LoadedGltfMesh {
     pub mesh: Mesh,
@@ -26,12 +32,64 @@ impl GltfLoader {
             return Err(format!("GLTF file not found: {}", file_path).into());
         }
 
-        // Load GLTF file (we'll handle textures separately through our texture system)
-        // Use import_slice to only load buffer data, not external textures
+        let (gltf, buffers) = Self::read_document(path)?;
+        let mut meshes = Vec::new();
+
+        // Process each mesh in the GLTF file
+        for mesh in gltf.meshes() {
+            for primitive in mesh.primitives() {
+                if let Some(loaded_mesh) = Self::process_primitive(&primitive, &buffers, &gltf, path).await? {
+                    meshes.push(loaded_mesh);
+                }
+            }
+        }
+
+        println!("✅ Loaded {} meshes from GLTF file: {}", meshes.len(), file_path);
+        Ok(meshes)
+    }
+
+    /// Load the triangles of every primitive in a GLTF file for building
+    /// colliders, at the same scale as the rendered meshes
+    pub fn load_collision_geometry(file_path: &str) -> Result<CollisionGeometry, Box<dyn std::error::Error>> {
+        let path = Path::new(file_path);
+        if !path.exists() {
+            return Err(format!("GLTF file not found: {}", file_path).into());
+        }
+        let (gltf, buffers) = Self::read_document(path)?;
+
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        for mesh in gltf.meshes() {
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    continue;
+                }
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                let positions = Self::read_positions(&reader)?;
+                let first = vertices.len() as u32;
+                triangles.extend(
+                    Self::read_indices(&reader, positions.len())
+                        .chunks_exact(3)
+                        .map(|triangle| [first + triangle[0], first + triangle[1], first + triangle[2]])
+                );
+                vertices.extend(positions);
+            }
+        }
+
+        if triangles.is_empty() {
+            return Err(format!("No triangles found in GLTF file: {}", file_path).into());
+        }
+        Ok((vertices, triangles))
+    }
+
+    /// Parse a GLTF file and load its buffer data
+    ///
+    /// Textures are handled separately through our texture system, so only
+    /// the buffers are loaded here.
+    fn read_document(path: &Path) -> Result<(gltf::Gltf, Vec<gltf::buffer::Data>), Box<dyn std::error::Error>> {
         let gltf_data = std::fs::read(path)?;
         let gltf = gltf::Gltf::from_slice(&gltf_data)?;
         
-        // Load only the buffer data
         let mut buffers = Vec::new();
         for buffer in gltf.buffers() {
             match buffer.source() {
@@ -53,19 +111,29 @@ impl GltfLoader {
                 },
             }
         }
-        let mut meshes = Vec::new();
+        Ok((gltf, buffers))
+    }
 
-        // Process each mesh in the GLTF file
-        for mesh in gltf.meshes() {
-            for primitive in mesh.primitives() {
-                if let Some(loaded_mesh) = Self::process_primitive(&primitive, &buffers, &gltf, path).await? {
-                    meshes.push(loaded_mesh);
-                }
-            }
+    /// Read a primitive's vertex positions, scaled by `GLTF_SCALE_FACTOR`
+    fn read_positions<'a, 's, F>(reader: &gltf::mesh::Reader<'a, 's, F>) -> Result<Vec<Vec3>, Box<dyn std::error::Error>>
+    where
+        F: Clone + Fn(gltf::Buffer<'a>) -> Option<&'s [u8]>,
+    {
+        match reader.read_positions() {
+            Some(positions) => Ok(positions.map(|position| Vec3::from(position) * GLTF_SCALE_FACTOR).collect()),
+            None => Err("GLTF primitive missing position data".into()),
         }
+    }
 
-        println!("✅ Loaded {} meshes from GLTF file: {}", meshes.len(), file_path);
-        Ok(meshes)
+    /// Read a primitive's triangle list indices, generating them if missing
+    fn read_indices<'a, 's, F>(reader: &gltf::mesh::Reader<'a, 's, F>, vertex_count: usize) -> Vec<u32>
+    where
+        F: Clone + Fn(gltf::Buffer<'a>) -> Option<&'s [u8]>,
+    {
+        match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..vertex_count as u32).collect(),
+        }
     }
 
     /// Process a single GLTF primitive and convert to macroquad mesh
@@ -79,11 +147,7 @@ impl GltfLoader {
         let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
         
         // Extract positions (required)
-        let positions: Vec<[f32; 3]> = if let Some(positions) = reader.read_positions() {
-            positions.collect()
-        } else {
-            return Err("GLTF primitive missing position data".into());
-        };
+        let positions = Self::read_positions(&reader)?;
 
         // Extract normals (optional, generate if missing)
         let normals: Vec<[f32; 3]> = if let Some(normals) = reader.read_normals() {
@@ -102,25 +166,16 @@ impl GltfLoader {
         };
 
         // Extract indices (optional, generate if missing)
-        let indices: Vec<u16> = if let Some(indices) = reader.read_indices() {
-            indices.into_u32().map(|i| i as u16).collect()
-        } else {
-            // Generate indices for triangle list
-            (0..positions.len() as u16).collect()
-        };
+        let indices: Vec<u16> = Self::read_indices(&reader, positions.len())
+            .into_iter()
+            .map(|i| i as u16)
+            .collect();
 
         // Convert to macroquad vertex format
-        // Apply global scale factor to make GLTF models appropriately sized
-        const GLTF_SCALE_FACTOR: f32 = 0.01; // Makes models 100x smaller
-        
         let mut vertices = Vec::new();
         for i in 0..positions.len() {
             vertices.push(Vertex {
-                position: Vec3::new(
-                    positions[i][0] * GLTF_SCALE_FACTOR, 
-                    positions[i][1] * GLTF_SCALE_FACTOR, 
-                    positions[i][2] * GLTF_SCALE_FACTOR
-                ),
+                position: positions[i],
                 uv: Vec2::new(uvs[i][0], uvs[i][1]),
                 normal: Vec4::new(normals[i][0], normals[i][1], normals[i][2], 0.0),
                 color: [255, 255, 255, 255], // Default white in u8 format
@@ -3,7 +3,7 @@
 use macroquad::prelude::*;
 use crate::game::{Player};
 use crate::game::map::WallType;
-use crate::ecs::{World, Transform, StaticRenderer, LightSource, Renderable, RenderData, RenderType, Renderer, RenderMode, StaticMesh};
+use crate::ecs::{Entity, World, Transform, GlobalTransform, RenderTransform, StaticRenderer, LightSource, Renderable, RenderData, RenderType, Renderer, RenderMode, StaticMesh};
 use std::collections::HashMap;
 
 /// Modern 3D renderer with ECS-based rendering only
@@ -109,7 +109,8 @@ impl Modern3DRenderer {
         let mut mesh_count = 0;
         
         // Render StaticMesh components (walls, floors, ceilings)
-        for (entity, transform, static_mesh) in world.query_2::<Transform, StaticMesh>() {
+        for (entity, global, render, static_mesh) in world.query::<(Entity, &GlobalTransform, Option<&RenderTransform>, &StaticMesh)>() {
+            let transform = RenderTransform::resolve(render, global);
             if self.should_render_entity(entity, world) && static_mesh.enabled && transform.is_enabled() {
                 if let Some(mesh) = &static_mesh.mesh {
                     draw_mesh(mesh);
@@ -134,7 +135,8 @@ impl Modern3DRenderer {
         let mut renderer_count = 0;
         
         // Render new Renderer components (replaces StaticRenderer)
-        for (entity, transform, renderer) in world.query_2::<Transform, Renderer>() {
+        for (entity, global, render, renderer) in world.query::<(Entity, &GlobalTransform, Option<&RenderTransform>, &Renderer)>() {
+            let transform = RenderTransform::resolve(render, global);
             renderer_count += 1;
             if self.should_render_entity(entity, world) && renderer.should_render() && transform.is_enabled() {
                 self.render_with_renderer_component(entity, transform, renderer, world);
@@ -143,7 +145,8 @@ impl Modern3DRenderer {
         }
         
         // Still support legacy StaticRenderer for backward compatibility during transition
-        for (entity, transform, static_renderer) in world.query_2::<Transform, StaticRenderer>() {
+        for (entity, global, render, static_renderer) in world.query::<(Entity, &GlobalTransform, Option<&RenderTransform>, &StaticRenderer)>() {
+            let transform = RenderTransform::resolve(render, global);
             if self.should_render_entity(entity, world) && static_renderer.should_render() && transform.is_enabled() {
                 // Check if this entity is a light source to render as sphere
                 let is_light_source = world.has::<LightSource>(entity);
@@ -188,7 +191,7 @@ impl Modern3DRenderer {
     }
 
     /// Render using the new Renderer component
-    fn render_with_renderer_component(&self, entity: crate::ecs::Entity, transform: &Transform, renderer: &Renderer, world: &World) {
+    fn render_with_renderer_component(&self, entity: Entity, transform: &Transform, renderer: &Renderer, world: &World) {
         // Check if this entity is a light source for special handling
         let is_light_source = world.has::<LightSource>(entity);
         
@@ -279,8 +282,8 @@ impl Modern3DRenderer {
     }
 
     /// Check if an entity should be rendered (unified enable/disable logic)
-    fn should_render_entity(&self, entity: crate::ecs::Entity, world: &World) -> bool {
-        world.is_valid(entity) && entity.enabled
+    fn should_render_entity(&self, entity: Entity, world: &World) -> bool {
+        world.is_active(entity)
     }
     
     /// Render a single static entity
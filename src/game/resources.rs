@@ -0,0 +1,65 @@
+//! Game-specific ECS resources
+
+use std::collections::HashSet;
+use std::time::Instant;
+use crate::ecs::{Entity, Resource};
+
+/// The entity currently driven as the player
+///
+/// Visual tests swap this to the test bot entity while they run.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerEntity(pub Entity);
+
+impl Resource for PlayerEntity {}
+
+/// Middle pillars that can be toggled on and off for pathfinding tests
+#[derive(Debug, Clone)]
+pub struct MiddlePillars {
+    pub entities: Vec<Entity>,      // Pillar entities affected by toggling
+    pub enabled: bool,              // Current state of the pillars
+    pub last_toggle_time: Instant,  // When the pillars were last toggled
+}
+
+impl MiddlePillars {
+    pub fn new() -> Self {
+        Self {
+            entities: Vec::new(),
+            enabled: true,
+            last_toggle_time: Instant::now(),
+        }
+    }
+}
+
+impl Default for MiddlePillars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resource for MiddlePillars {}
+
+/// Entities currently inside each trigger collider, as `(trigger, entity)` pairs
+#[derive(Debug, Clone, Default)]
+pub struct TriggerOccupancy {
+    pub inside: HashSet<(Entity, Entity)>,
+}
+
+impl Resource for TriggerOccupancy {}
+
+/// Texture names referenced by newly added render components, loaded by the
+/// renderer before it next draws
+#[derive(Debug, Clone, Default)]
+pub struct TextureRequests {
+    pub pending: HashSet<String>,
+}
+
+impl Resource for TextureRequests {}
+
+/// Level file requested by a trigger, loaded by the game state once the
+/// current frame's update has finished
+#[derive(Debug, Clone, Default)]
+pub struct PendingLevel {
+    pub path: Option<String>,
+}
+
+impl Resource for PendingLevel {}
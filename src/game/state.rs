@@ -5,6 +5,7 @@ use super::rendering::DeferredRenderer;
 use super::ecs_state::EcsGameState;
 use super::level_data::LevelDataHotReload;
 use super::config::GameConfig;
+use super::resources::PendingLevel;
 
 /// Overall game state for testing and gameplay
 pub struct GameState {
@@ -70,7 +71,7 @@ impl GameState {
 
     /// Create a new game state with specific configuration
     pub fn with_config(config: GameConfig) -> Self {
-        GameState {
+        let mut state = GameState {
             map: Map::new(),
             frame_count: 0,
             start_time: Instant::now(),
@@ -91,6 +92,17 @@ impl GameState {
             config,
             // Loading progress display
             loading_progress: None,
+        };
+        state.apply_controls_config();
+        state
+    }
+    
+    /// Apply the control settings from config.ini to the player entity
+    fn apply_controls_config(&mut self) {
+        if let Some(player_entity) = self.ecs_state.player_entity() {
+            if let Some(player) = self.ecs_state.world.get_mut::<crate::ecs::Player>(player_entity) {
+                player.jump_strength = crate::ecs::Player::DEFAULT_JUMP_STRENGTH * self.config.controls.jump_strength;
+            }
         }
     }
     
@@ -118,6 +130,20 @@ impl GameState {
         }
     }
     
+    /// Switch to the level a trigger asked for, if any, and watch its file
+    /// for hot-reload from then on
+    pub async fn load_pending_level(&mut self) {
+        let path = match self.ecs_state.world.resource_mut::<PendingLevel>().and_then(|pending| pending.path.take()) {
+            Some(path) => path,
+            None => return,
+        };
+        
+        println!("🚪 Loading level: {}", path);
+        if let Err(e) = self.init_hot_reload(&path).await {
+            println!("❌ Failed to load level {}: {}", path, e);
+        }
+    }
+    
     /// Async initialization that sets up textures and meshes
     pub async fn initialize(&mut self) {
         self.ecs_state.initialize().await;
@@ -224,6 +250,10 @@ impl GameState {
             self.ecs_state.update_pathfinding_map(pathfinding_map);
         }
         
+        self.ecs_state.set_level_data(config.clone());
+        self.ecs_state.world.propagate_transforms();
+        self.ecs_state.world.send_event(crate::ecs::LevelReloaded { full_reload: false });
+        
         // TODO: GLTF meshes need to be preloaded for hot-reload changes
         // Currently only initial loading supports GLTF preloading due to async constraints
         if diff.objects_added.len() > 0 || diff.objects_modified.len() > 0 {
@@ -279,7 +309,12 @@ impl GameState {
         
         // Mark solid objects as walls
         for obj in &config.objects {
-            if !obj.enabled || obj.collision_type != "solid" {
+            if !obj.enabled || !matches!(obj.collision_type.as_str(), "solid" | "mesh" | "convex") {
+                continue;
+            }
+            
+            // Moving objects don't stay where the grid would mark them
+            if obj.path.is_some() {
                 continue;
             }
             
@@ -373,6 +408,7 @@ impl GameState {
         let pathfinding_map = self.generate_pathfinding_map_from_level(config);
         self.map = pathfinding_map.clone();
         self.ecs_state.update_pathfinding_map(pathfinding_map);
+        self.ecs_state.set_level_data(config.clone());
         
         // Remove all existing config-created entities (lights and objects)
         self.remove_all_config_entities();
@@ -431,6 +467,8 @@ impl GameState {
                     .with_enabled(true))
                 .build();
             
+            self.apply_config_identity(light_entity, &light_config.name, &light_config.tags);
+            
             // Track the created light entity
             self.world_config_light_entities[i] = Some(light_entity);
             
@@ -445,96 +483,7 @@ impl GameState {
                 continue;
             }
             
-            let position = Vec3::new(
-                object_config.position[0],
-                object_config.position[1],
-                object_config.position[2]
-            );
-            
-            let scale = Vec3::new(
-                object_config.scale[0],
-                object_config.scale[1],
-                object_config.scale[2]
-            );
-            
-            let rotation = Vec3::new(
-                object_config.rotation[0],
-                object_config.rotation[1],
-                object_config.rotation[2]
-            );
-            
-            let color = object_config.color.map(|c| Color::new(c[0], c[1], c[2], c[3]));
-            
-            // Create the object entity with appropriate components
-            let mut entity_builder = self.ecs_state.world.spawn()
-                .with(crate::ecs::Transform::new(position)
-                    .with_scale(scale)
-                    .with_rotation(rotation));
-            
-            // Add rendering component based on mesh type
-            let renderer = match object_config.mesh.as_str() {
-                "cube" => crate::ecs::Renderer::cube(scale),
-                "sphere" => {
-                    let radius = scale.x.max(scale.y).max(scale.z) * 0.5;
-                    crate::ecs::Renderer::sphere(radius)
-                },
-                "cylinder" => {
-                    let radius = scale.x.max(scale.z) * 0.5;
-                    let height = scale.y;
-                    crate::ecs::Renderer::cylinder(radius, height)
-                },
-                "plane" => crate::ecs::Renderer::plane(scale.x, scale.z),
-                            // Custom mesh file
-            mesh_path => {
-                // Check if it's a GLTF file
-                if crate::game::rendering::GltfLoader::is_gltf_file(mesh_path) {
-                    println!("🔧 Attempting to load GLTF mesh: {}", mesh_path);
-                    // Use Custom render mode with mesh path for GLTF loading
-                    crate::ecs::Renderer {
-                        render_mode: crate::ecs::RenderMode::Custom,
-                        material: crate::ecs::RenderMaterial::default(),
-                        custom_mesh_path: Some(mesh_path.to_string()),
-                        enabled: true,
-                    }
-                } else {
-                    println!("⚠️ Unsupported mesh format: {}", mesh_path);
-                    crate::ecs::Renderer::cube(scale)
-                }
-            }
-            };
-            
-            // Apply texture and color
-            let mut final_renderer = renderer;
-            if let Some(texture_name) = &object_config.texture {
-                final_renderer = final_renderer.with_texture_name(texture_name.clone());
-            }
-            if let Some(color) = color {
-                final_renderer = final_renderer.with_color(color);
-            }
-            
-            entity_builder = entity_builder.with(final_renderer.with_enabled(true));
-            
-            // Add collision component based on collision type
-            match object_config.collision_type.as_str() {
-                "solid" => {
-                    entity_builder = entity_builder.with(crate::ecs::Collider::static_solid(
-                        crate::ecs::ColliderShape::Box { size: scale }
-                    ));
-                },
-                "trigger" => {
-                    entity_builder = entity_builder.with(crate::ecs::Collider::static_trigger(
-                        crate::ecs::ColliderShape::Box { size: scale }
-                    ));
-                },
-                "none" => {
-                    // No collision component
-                },
-                _ => {
-                    println!("⚠️ Unknown collision type: {}", object_config.collision_type);
-                }
-            }
-            
-            let object_entity = entity_builder.build();
+            let object_entity = self.create_object_entity(object_config);
             
             // Track the created object entity
             self.world_config_object_entities[i] = Some(object_entity);
@@ -542,10 +491,11 @@ impl GameState {
             let default_name = format!("Object_{}", i);
             let object_name = object_config.name.as_deref().unwrap_or(&default_name);
             println!("  ✅ Created {} object '{}' at {:?} (collision: {})", 
-                    object_config.mesh, object_name, position, object_config.collision_type);
+                    object_config.mesh, object_name, object_config.position, object_config.collision_type);
         }
         
         // Apply global settings
+        self.apply_world_gravity(config);
         if let Some(settings) = &config.settings {
             if let Some(ambient) = settings.ambient_light {
                 println!("  🌅 Ambient light: {:?}", ambient);
@@ -558,12 +508,14 @@ impl GameState {
             }
         }
         
+        self.ecs_state.world.propagate_transforms();
         println!("✅ World configuration applied successfully!");
+        self.ecs_state.world.send_event(crate::ecs::LevelReloaded { full_reload: true });
         
         // Show loading progress for texture loading
         self.loading_progress = Some(LoadingProgress::new("Loading Textures"));
         
-        // Preload textures from actual world configuration (with progress updates)
+        // Load the textures requested by the new render components (with progress updates)
         println!("🖼️ Preloading textures from world configuration...");
         let texture_count = self.deferred_renderer.get_required_texture_count(&self.ecs_state.world);
         for i in 0..texture_count {
@@ -573,7 +525,7 @@ impl GameState {
             self.draw_loading_screen();
             next_frame().await;
         }
-        self.deferred_renderer.preload_textures_from_world(&self.ecs_state.world).await;
+        self.deferred_renderer.load_requested_textures(&mut self.ecs_state.world).await;
         
         // Update progress for GLTF loading
         if let Some(progress) = &mut self.loading_progress {
@@ -626,7 +578,7 @@ impl GameState {
     
     /// Apply player configuration to the existing player entity
     fn apply_player_config(&mut self, player_config: &super::level_data::PlayerConfig) {
-        if let Some(player_entity) = self.ecs_state.player_entity {
+        if let Some(player_entity) = self.ecs_state.player_entity() {
             // Update player transform (position and rotation)
             if let Some(transform) = self.ecs_state.world.get_mut::<crate::ecs::Transform>(player_entity) {
                 transform.position = Vec3::new(
@@ -638,15 +590,24 @@ impl GameState {
                 transform.rotation.x = player_config.spawn_rotation[1]; // Pitch
             }
             
-            // Update player collider if configuration changed
-            if let Some(collider) = self.ecs_state.world.get_mut::<crate::ecs::Collider>(player_entity) {
-                *collider = crate::ecs::Collider::dynamic_solid(crate::ecs::ColliderShape::Capsule {
-                    height: player_config.height,
-                    radius: player_config.radius,
-                });
+            // Move with the configured capsule and settle onto the ground below the spawn point
+            let shape = self.ecs_state.world.get_mut::<crate::ecs::CharacterController>(player_entity).map(|controller| {
+                controller.height = player_config.height;
+                controller.radius = player_config.radius;
+                controller.leave_ground();
+                controller.collider_shape()
+            });
+            
+            // Keep the player collider covering the same capsule
+            if let (Some(shape), Some(collider)) = (shape, self.ecs_state.world.get_mut::<crate::ecs::Collider>(player_entity)) {
+                collider.shape = shape;
+            }
+            if let Some(velocity) = self.ecs_state.world.get_mut::<crate::ecs::Velocity>(player_entity) {
+                velocity.linear = Vec3::ZERO;
             }
             
             let player_name = player_config.name.as_deref().unwrap_or("Player");
+            self.ecs_state.world.set_name(player_entity, player_name);
             println!("  ✅ Updated player '{}' at {:?} (height: {:.1}, radius: {:.2})", 
                     player_name, 
                     [player_config.spawn_position[0], player_config.spawn_position[1], player_config.spawn_position[2]],
@@ -751,6 +712,7 @@ impl GameState {
 
     /// Apply global settings configuration
     fn apply_settings_config(&mut self, config: &super::level_data::LevelData) {
+        self.apply_world_gravity(config);
         if let Some(settings) = &config.settings {
             if let Some(ambient) = settings.ambient_light {
                 println!("  🌅 Ambient light: {:?}", ambient);
@@ -764,6 +726,18 @@ impl GameState {
         }
     }
 
+    /// Set the gravity outside gravity volumes, back to the default when the
+    /// level doesn't say
+    fn apply_world_gravity(&mut self, config: &super::level_data::LevelData) {
+        let gravity = config.settings.as_ref()
+            .and_then(|settings| settings.gravity)
+            .map_or(crate::ecs::Gravity::DEFAULT, Vec3::from);
+        if gravity != crate::ecs::Gravity::DEFAULT {
+            println!("  🪐 Gravity: {:?}", gravity);
+        }
+        self.ecs_state.world.insert_resource(crate::ecs::Gravity(gravity));
+    }
+
     /// Create a light entity from configuration
     fn create_light_entity(&mut self, light_config: &super::level_data::LightConfig) -> crate::ecs::Entity {
         let position = Vec3::new(
@@ -780,7 +754,7 @@ impl GameState {
         );
         
         // Create light entity with visible sphere
-        self.ecs_state.world.spawn()
+        let entity = self.ecs_state.world.spawn()
             .with(crate::ecs::Transform::new(position))
             .with(crate::ecs::LightSource::new(
                 color,
@@ -801,7 +775,10 @@ impl GameState {
                     1.0
                 ))
                 .with_enabled(true))
-            .build()
+            .build();
+        
+        self.apply_config_identity(entity, &light_config.name, &light_config.tags);
+        entity
     }
 
     /// Create an object entity from configuration
@@ -847,7 +824,7 @@ impl GameState {
             "plane" => crate::ecs::Renderer::plane(scale.x, scale.z),
             // Custom mesh file
             mesh_path => {
-                if mesh_path.ends_with(".gltf") || mesh_path.ends_with(".glb") {
+                if crate::game::rendering::GltfLoader::is_gltf_file(mesh_path) {
                     println!("🔧 Attempting to load GLTF mesh: {}", mesh_path);
                     crate::ecs::Renderer::custom().with_custom_mesh_path(mesh_path.to_string())
                 } else {
@@ -872,14 +849,27 @@ impl GameState {
         // Add collision component based on collision type
         match object_config.collision_type.as_str() {
             "solid" => {
-                entity_builder = entity_builder.with(crate::ecs::Collider::static_solid(
-                    crate::ecs::ColliderShape::Box { size: scale }
-                ));
+                entity_builder = entity_builder.with(crate::ecs::Collider::static_solid(object_config.collider_shape())
+                    .with_material(object_config.collider_material()));
+            },
+            "mesh" | "convex" => {
+                let shape = object_config.mesh_collider_shape(object_config.collision_type == "convex");
+                entity_builder = entity_builder.with(crate::ecs::Collider::static_solid(shape)
+                    .with_material(object_config.collider_material()));
+            },
+            "dynamic" => {
+                let collider = crate::ecs::Collider::dynamic_solid(object_config.collider_shape())
+                    .with_material(object_config.collider_material());
+                entity_builder = entity_builder
+                    .with(crate::ecs::RigidBody::from_collider(&collider))
+                    .with(crate::ecs::Velocity::default())
+                    .with(collider);
             },
             "trigger" => {
-                entity_builder = entity_builder.with(crate::ecs::Collider::static_trigger(
-                    crate::ecs::ColliderShape::Box { size: scale }
-                ));
+                entity_builder = entity_builder.with(crate::ecs::Collider::static_trigger(object_config.collider_shape()));
+                if let Some(actions) = object_config.trigger_actions() {
+                    entity_builder = entity_builder.with(actions);
+                }
             },
             "none" => {
                 // No collision component
@@ -889,7 +879,38 @@ impl GameState {
             }
         }
         
-        entity_builder.build()
+        // Doors and platforms move along their path
+        if let Some(body) = object_config.kinematic_body() {
+            entity_builder = entity_builder.with(body);
+        }
+        
+        // Zero-g zones and other gravity volumes
+        if let Some(volume) = object_config.gravity_volume() {
+            entity_builder = entity_builder.with(volume);
+        }
+        
+        let entity = entity_builder.build();
+        self.apply_config_identity(entity, &object_config.name, &object_config.tags);
+        self.spawn_object_children(entity, object_config);
+        entity
+    }
+    
+    /// Attach the configured name and tags so the entity can be looked up later
+    fn apply_config_identity(&mut self, entity: crate::ecs::Entity, name: &Option<String>, tags: &[String]) {
+        if let Some(name) = name {
+            self.ecs_state.world.set_name(entity, name.clone());
+        }
+        if !tags.is_empty() {
+            self.ecs_state.world.add(entity, crate::ecs::Tags::new(tags.iter().cloned()));
+        }
+    }
+    
+    /// Create the nested children of an object and attach them to its entity
+    fn spawn_object_children(&mut self, parent: crate::ecs::Entity, object_config: &super::level_data::ObjectConfig) {
+        for child_config in object_config.children.iter().filter(|child| child.enabled) {
+            let child = self.create_object_entity(child_config);
+            self.ecs_state.world.set_parent(child, parent);
+        }
     }
     
     /// Draw the game state
@@ -908,6 +929,9 @@ impl GameState {
     
     /// Draw 3D mode content
     async fn draw_3d_mode_content(&mut self, current_player: &Player) {
+        // Textures for render components added at runtime, e.g. by hot reload
+        self.deferred_renderer.load_requested_textures(&mut self.ecs_state.world).await;
+        
         // Deferred rendering
         self.deferred_renderer.update_camera(&current_player);
         let time = self.start_time.elapsed().as_secs_f32();
@@ -86,6 +86,7 @@ async fn run_game_loop(mut game_state: GameState, test_duration: Option<u64>) {
         let dt = get_frame_time();
         
         game_state.update(dt);
+        game_state.load_pending_level().await;
         game_state.draw().await;
         
         frame_counter += 1;
@@ -1,7 +1,10 @@
-//! Performance testing for enabled/disabled entity systems
+//! Performance testing for enabled/disabled entity systems and component storage
 
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::time::Instant;
-use crate::ecs::{World, Transform, StaticRenderer, Wall};
+use crate::ecs::{Component, Entity, World, Transform, StaticRenderer, Wall, Velocity};
+use crate::ecs::entity::EntityId;
 
 /// Simple entity with enabled field for comparison testing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -17,6 +20,73 @@ impl SimpleEntity {
     }
 }
 
+/// Component storage as it was before the sparse-set rework, kept as the
+/// baseline for the storage benchmark
+///
+/// Storages are boxed in a `HashMap` by type and keep an id -> index map next
+/// to their sparse array. Queries collect every entity and look each
+/// component up by type, like the old `World::query_2`.
+#[derive(Default)]
+struct LegacyComponentStorage {
+    entities: Vec<Entity>,
+    storages: HashMap<TypeId, Box<dyn Any>>,
+}
+
+struct LegacyTypedStorage<T> {
+    sparse: Vec<Option<usize>>,
+    dense: Vec<T>,
+    entities: Vec<Entity>,
+    entity_to_dense: HashMap<EntityId, usize>,
+}
+
+impl LegacyComponentStorage {
+    fn spawn(&mut self) -> Entity {
+        let entity = Entity::new(self.entities.len() as EntityId, 1);
+        self.entities.push(entity);
+        entity
+    }
+    
+    /// Freshly allocated list of every entity, like `EntityManager::all_entities`
+    fn all_entities(&self) -> Vec<Entity> {
+        self.entities.clone()
+    }
+    
+    fn insert<T: 'static>(&mut self, entity: Entity, component: T) {
+        let storage = self.storages.entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(LegacyTypedStorage::<T> {
+                sparse: Vec::new(),
+                dense: Vec::new(),
+                entities: Vec::new(),
+                entity_to_dense: HashMap::new(),
+            }))
+            .downcast_mut::<LegacyTypedStorage<T>>()
+            .unwrap();
+        if entity.id as usize >= storage.sparse.len() {
+            storage.sparse.resize(entity.id as usize + 1, None);
+        }
+        storage.sparse[entity.id as usize] = Some(storage.dense.len());
+        storage.entity_to_dense.insert(entity.id, storage.dense.len());
+        storage.dense.push(component);
+        storage.entities.push(entity);
+    }
+    
+    fn get<T: 'static>(&self, entity: Entity) -> Option<&T> {
+        let storage = self.storages.get(&TypeId::of::<T>())?.downcast_ref::<LegacyTypedStorage<T>>()?;
+        let index = (*storage.sparse.get(entity.id as usize)?)?;
+        (storage.entities[index] == entity).then(|| &storage.dense[index])
+    }
+    
+    fn query_2<T1: 'static, T2: 'static>(&self) -> Vec<(Entity, &T1, &T2)> {
+        let mut results = Vec::new();
+        for entity in self.all_entities() {
+            if let (Some(comp1), Some(comp2)) = (self.get::<T1>(entity), self.get::<T2>(entity)) {
+                results.push((entity, comp1, comp2));
+            }
+        }
+        results
+    }
+}
+
 /// Performance test results
 #[derive(Debug)]
 pub struct PerformanceTestResult {
@@ -27,6 +97,20 @@ pub struct PerformanceTestResult {
     pub performance_difference_percent: f64,
 }
 
+/// Component storage benchmark results
+#[derive(Debug)]
+pub struct StorageBenchmarkResult {
+    pub entity_count: usize,
+    pub iterations: usize,
+    /// `query_2` over the old HashMap-backed storage
+    pub legacy_query_time_ms: f64,
+    /// Typed query iterating the smallest storage
+    pub query_time_ms: f64,
+    /// Mutable typed query updating positions
+    pub query_mut_time_ms: f64,
+    pub speedup: f64,
+}
+
 /// Performance testing for enabled/disabled systems
 pub struct PerformanceTest;
 
@@ -192,4 +276,80 @@ impl PerformanceTest {
         // Stress test: 10,000 entities, 100 iterations
         Self::run_enabled_state_benchmark(100, 10000)
     }
-} 
\ No newline at end of file
+    
+    /// Benchmark component storage iteration at a given world size
+    ///
+    /// Every entity has a `Transform`; one in ten also has a `Velocity`, so a
+    /// `(&Transform, &Velocity)` query only needs to visit a tenth of the world.
+    pub fn run_storage_benchmark(iterations: usize, entity_count: usize) -> StorageBenchmarkResult {
+        println!("🏃‍♂️ Running component storage benchmark...");
+        println!("   Iterations: {}, Entities: {}", iterations, entity_count);
+        
+        let mut world = World::new();
+        let mut legacy = LegacyComponentStorage::default();
+        for i in 0..entity_count {
+            let transform = Transform::new(macroquad::prelude::Vec3::new(i as f32, 0.0, 0.0));
+            let entity = world.spawn().with(transform.clone()).build();
+            let legacy_entity = legacy.spawn();
+            legacy.insert(legacy_entity, transform);
+            if i % 10 == 0 {
+                world.add(entity, Velocity::linear(macroquad::prelude::Vec3::X));
+                legacy.insert(legacy_entity, Velocity::linear(macroquad::prelude::Vec3::X));
+            }
+        }
+        
+        // Old storage: every entity is collected and looked up per component type
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let mut sum = 0.0;
+            for (_, transform, velocity) in legacy.query_2::<Transform, Velocity>() {
+                sum += transform.position.x + velocity.linear.x;
+            }
+            assert!(sum > 0.0);
+        }
+        let legacy_query_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+        
+        // Read-only query, driven by the smaller Velocity storage
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let mut sum = 0.0;
+            for (transform, velocity) in world.query::<(&Transform, &Velocity)>() {
+                sum += transform.position.x + velocity.linear.x;
+            }
+            assert!(sum > 0.0);
+        }
+        let query_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+        
+        // Mutable query integrating velocities
+        let start = Instant::now();
+        for _ in 0..iterations {
+            for (transform, velocity) in world.query_mut::<(&mut Transform, &Velocity)>() {
+                transform.position += velocity.linear * 0.016;
+            }
+        }
+        let query_mut_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+        
+        let result = StorageBenchmarkResult {
+            entity_count,
+            iterations,
+            legacy_query_time_ms: legacy_query_time,
+            query_time_ms: query_time,
+            query_mut_time_ms: query_mut_time,
+            speedup: legacy_query_time / query_time.max(f64::EPSILON),
+        };
+        
+        println!("   • Old storage query:  {:.4} ms/iteration", result.legacy_query_time_ms);
+        println!("   • Query:              {:.4} ms/iteration", result.query_time_ms);
+        println!("   • Mutable query:      {:.4} ms/iteration", result.query_mut_time_ms);
+        println!("   • Query speedup:      {:.1}x", result.speedup);
+        result
+    }
+    
+    /// Storage benchmarks at 10k and 100k entities
+    pub fn run_storage_benchmarks() -> Vec<StorageBenchmarkResult> {
+        vec![
+            Self::run_storage_benchmark(100, 10_000),
+            Self::run_storage_benchmark(20, 100_000),
+        ]
+    }
+}